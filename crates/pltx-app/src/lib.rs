@@ -1,16 +1,23 @@
 //! Contains the application state. The [`App`] is passed to all functions
 //! that require state throughout the application.
 
-use pltx_config::{Config, ProfileConfig};
+use std::time::{Duration, Instant};
+
+use color_eyre::Result;
+use pltx_config::{ColorMode, Config, ConfigError, ConfigWatcher, ProfileConfig};
 use pltx_database::Database;
+use pltx_sync::{BackgroundSync, SyncStatus};
+use pltx_utils::DateTime;
 use state::{AppModule, AppPopup, Mode, ModeColors, View};
 
 mod module;
+mod notification;
 /// Application state that affects what is rendered on the screen.
 pub mod state;
 mod widget;
 
 pub use module::*;
+pub use notification::*;
 pub use widget::*;
 
 /// The position of the debug pane on the screen.
@@ -80,6 +87,18 @@ impl DebugMode {
     }
 }
 
+/// How long a status message is shown in the status bar.
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(4);
+
+/// A message shown in the status bar for a few seconds, e.g., to describe what
+/// was undone.
+pub struct StatusMessage {
+    /// The text of the message.
+    pub text: String,
+    /// When the message was created.
+    pub created: Instant,
+}
+
 /// The application state.
 pub struct App {
     /// The user configuration after it has been merged with the base
@@ -88,6 +107,9 @@ pub struct App {
     /// The merged profile config values to determine which files should be used
     /// for handling data.
     pub profile: ProfileConfig,
+    /// The colors that the terminal supports, which the rendered colors are
+    /// converted to.
+    pub color_mode: ColorMode,
     /// The current view to prioritize/render.
     pub view: View,
     /// The current application mode.
@@ -101,8 +123,19 @@ pub struct App {
     pub breadcrumbs: Vec<String>,
     /// The database state and utility methods.
     pub db: Database,
+    /// Syncs the database with the sync server of the profile.
+    pub sync: BackgroundSync,
     /// The debug state.
     pub debug: DebugMode,
+    /// The message shown in the status bar, if any.
+    pub status_message: Option<StatusMessage>,
+    /// The notifications shown as toasts and kept in the history.
+    pub notifications: Notifications,
+    /// Reloads the config when the config file of the profile changes.
+    config_watcher: Option<ConfigWatcher>,
+    /// The problems found in the config file when it was last reloaded, which
+    /// haven't been shown yet.
+    pub config_errors: Vec<ConfigError>,
     /// When set to true, the application will quit on the next frame render.
     pub exit: bool,
 }
@@ -112,22 +145,38 @@ impl App {
     pub fn new(config: Config, profile: ProfileConfig) -> App {
         let debug_enabled = &config.log_level == "debug";
         let db_file = profile.db_file.to_owned();
+        let backups = profile.backups.max(0) as usize;
+        let idle_timeout = profile.idle_timeout.max(0) as u64;
+        let sync = BackgroundSync::new(
+            &profile.sync_server,
+            Duration::from_secs(profile.sync_interval.max(1) as u64),
+        );
+        DateTime::set_format(config.datetime.format());
+        let color_mode = ColorMode::from_config(&config.color_mode);
 
         App {
             config,
             profile,
+            color_mode,
             view: View::Default,
             mode: Mode::Normal,
             module: AppModule::Home,
             popup: AppPopup::None,
             breadcrumbs: vec![],
-            db: Database::init(db_file),
+            db: Database::init(db_file)
+                .backup_retention(backups)
+                .idle_timeout(idle_timeout),
+            sync,
             debug: DebugMode {
                 enabled: debug_enabled,
                 show: false,
                 min_preview: true,
                 position: DebugPosition::TopRight,
             },
+            status_message: None,
+            notifications: Notifications::default(),
+            config_watcher: None,
+            config_errors: vec![],
             exit: false,
         }
     }
@@ -137,8 +186,103 @@ impl App {
         self.exit = true
     }
 
+    /// Show a message in the status bar.
+    pub fn status_message<S: Into<String>>(&mut self, text: S) {
+        self.status_message = Some(StatusMessage {
+            text: text.into(),
+            created: Instant::now(),
+        });
+    }
+
+    /// Show a notification as a toast and add it to the history.
+    pub fn notify<S: Into<String>>(&mut self, level: NotificationLevel, text: S) {
+        self.notifications.push(level, text);
+    }
+
+    /// Show an info notification.
+    pub fn info<S: Into<String>>(&mut self, text: S) {
+        self.notify(NotificationLevel::Info, text);
+    }
+
+    /// Show a notification that an action succeeded.
+    pub fn success<S: Into<String>>(&mut self, text: S) {
+        self.notify(NotificationLevel::Success, text);
+    }
+
+    /// Show a warning notification.
+    pub fn warning<S: Into<String>>(&mut self, text: S) {
+        self.notify(NotificationLevel::Warning, text);
+    }
+
+    /// Show a notification that an action failed, e.g., because a value
+    /// wasn't valid, instead of returning an error.
+    pub fn error<S: Into<String>>(&mut self, text: S) {
+        self.notify(NotificationLevel::Error, text);
+    }
+
+    /// Reload the config on tick when config.toml or the config file of the
+    /// profile changes.
+    pub fn watch_config(&mut self) {
+        self.config_watcher = Some(ConfigWatcher::new(&self.profile));
+    }
+
+    /// Swap in the config if a config file has changed. A config with
+    /// problems is not used, and the problems are kept in `config_errors` to be
+    /// shown instead. Changes to the profiles take effect after a restart.
+    fn reload_config(&mut self) {
+        let Some(reloaded) = self.config_watcher.as_mut().and_then(|w| w.poll()) else {
+            return;
+        };
+        match reloaded {
+            Ok((config, errors)) if errors.is_empty() => {
+                self.debug.enabled = config.log_level == "debug";
+                DateTime::set_format(config.datetime.format());
+                self.color_mode = ColorMode::from_config(&config.color_mode);
+                self.config = config;
+                self.success("Reloaded the config");
+            }
+            Ok((_, errors)) => {
+                self.warning("The config has problems and wasn't reloaded");
+                self.config_errors = errors;
+            }
+            Err(err) => self.error(format!("Failed to reload the config: {}", err)),
+        }
+    }
+
+    /// Tick the background sync, and notify when it starts failing or
+    /// recovers. A sync that keeps failing is only reported once. A sync that
+    /// can't be started is reported too, and retried after the interval.
+    fn sync_notifications(&mut self) {
+        let failed = matches!(self.sync.status, SyncStatus::Failed(_));
+        if let Err(err) = self.sync.tick(&self.db) {
+            self.error(format!("Failed to start a sync: {}", err));
+            return;
+        }
+        match &self.sync.status {
+            SyncStatus::Failed(err) if !failed => {
+                let text = format!("Sync failed: {}", err);
+                self.warning(text);
+            }
+            SyncStatus::Connected if failed => self.success("Sync is connected again"),
+            _ => {}
+        }
+    }
+
     /// Handle the tick event.
-    pub fn tick(&self) {}
+    pub fn tick(&mut self) -> Result<()> {
+        self.db.sync_session()?;
+        self.sync_notifications();
+        self.reload_config();
+        self.notifications.tick();
+        if self
+            .status_message
+            .as_ref()
+            .is_some_and(|m| m.created.elapsed() >= STATUS_MESSAGE_DURATION)
+        {
+            self.status_message = None;
+        }
+        Ok(())
+    }
 
     /// Returns the current mode's colors.
     pub fn mode_colors(&self) -> ModeColors {
@@ -0,0 +1,156 @@
+use core::fmt;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use pltx_config::ColorsConfig;
+use pltx_utils::DateTime;
+use ratatui::style::Color;
+
+/// How many notifications are kept in the history.
+const HISTORY_LIMIT: usize = 100;
+
+/// How many notifications are shown as toasts at once. Older toasts are
+/// dismissed when newer ones arrive, but stay in the history.
+const TOAST_LIMIT: usize = 4;
+
+/// How severe a notification is, which decides its color and how long its
+/// toast is shown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NotificationLevel {
+    /// Something happened that may be of interest.
+    Info,
+    /// An action succeeded.
+    Success,
+    /// Something didn't go as expected, but nothing was lost.
+    Warning,
+    /// An action failed.
+    Error,
+}
+
+impl NotificationLevel {
+    /// How long the toast of a notification of this level is shown. Problems
+    /// stay longer so there is time to read them.
+    pub fn duration(&self) -> Duration {
+        match self {
+            NotificationLevel::Info | NotificationLevel::Success => Duration::from_secs(4),
+            NotificationLevel::Warning => Duration::from_secs(6),
+            NotificationLevel::Error => Duration::from_secs(8),
+        }
+    }
+
+    /// The color of the level.
+    pub fn color(&self, colors: &ColorsConfig) -> Color {
+        match self {
+            NotificationLevel::Info => colors.primary,
+            NotificationLevel::Success => colors.success,
+            NotificationLevel::Warning => colors.warning,
+            NotificationLevel::Error => colors.danger,
+        }
+    }
+}
+
+impl fmt::Display for NotificationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                NotificationLevel::Info => "Info",
+                NotificationLevel::Success => "Success",
+                NotificationLevel::Warning => "Warning",
+                NotificationLevel::Error => "Error",
+            }
+        )
+    }
+}
+
+/// A message shown as a toast over the module, which stays in the
+/// notification history after the toast is gone.
+#[derive(Clone)]
+pub struct Notification {
+    /// How severe the notification is.
+    pub level: NotificationLevel,
+    /// The text of the notification.
+    pub text: String,
+    /// When the notification was created, to show in the history.
+    pub created_at: DateTime,
+    /// When the notification was created, to time out the toast.
+    created: Instant,
+}
+
+impl Notification {
+    /// Whether the toast of the notification has been shown long enough.
+    pub fn is_expired(&self) -> bool {
+        self.created.elapsed() >= self.level.duration()
+    }
+}
+
+/// The notifications of the application. Modules add notifications with
+/// [`App::notify`](crate::App::notify) or one of its shorthands, and the toasts
+/// time out on tick.
+#[derive(Default)]
+pub struct Notifications {
+    /// The notifications that are shown as toasts, oldest first.
+    toasts: Vec<Notification>,
+    /// Every recent notification, newest first.
+    history: VecDeque<Notification>,
+    /// The number of notifications added since the history was last viewed.
+    unread: usize,
+}
+
+impl Notifications {
+    /// Add a notification and show its toast.
+    pub fn push<S: Into<String>>(&mut self, level: NotificationLevel, text: S) {
+        let notification = Notification {
+            level,
+            text: text.into(),
+            created_at: DateTime::now(),
+            created: Instant::now(),
+        };
+
+        self.history.push_front(notification.clone());
+        self.history.truncate(HISTORY_LIMIT);
+        self.unread = (self.unread + 1).min(HISTORY_LIMIT);
+
+        self.toasts.push(notification);
+        if self.toasts.len() > TOAST_LIMIT {
+            self.toasts.remove(0);
+        }
+    }
+
+    /// Remove the toasts that have been shown long enough.
+    pub fn tick(&mut self) {
+        self.toasts.retain(|n| !n.is_expired());
+    }
+
+    /// The notifications that are shown as toasts, oldest first.
+    pub fn toasts(&self) -> &[Notification] {
+        &self.toasts
+    }
+
+    /// Every recent notification, newest first.
+    pub fn history(&self) -> &VecDeque<Notification> {
+        &self.history
+    }
+
+    /// The number of notifications added since the history was last viewed.
+    pub fn unread(&self) -> usize {
+        self.unread
+    }
+
+    /// Mark the history as viewed and dismiss the toasts, which are in the
+    /// history anyway.
+    pub fn read(&mut self) {
+        self.unread = 0;
+        self.toasts.clear();
+    }
+
+    /// Remove every notification.
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+        self.history.clear();
+        self.unread = 0;
+    }
+}
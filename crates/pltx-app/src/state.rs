@@ -167,4 +167,8 @@ pub struct ModuleText<'a> {
 pub enum AppPopup {
     #[default]
     None,
+    Search,
+    Theme,
+    ConfigErrors,
+    Notifications,
 }
@@ -12,9 +12,12 @@ include!("src/config.rs");
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DefaultConfig {
     pub log_level: String,
+    pub color_mode: String,
     pub default_profile: String,
     pub colors: ColorsConfig<String, String>,
+    pub datetime: DateTimeConfig,
     pub modules: ModulesConfig,
+    pub keys: KeysConfig<String>,
     pub profiles: Vec<ProfileConfig<String>>,
 }
 
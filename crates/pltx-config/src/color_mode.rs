@@ -0,0 +1,220 @@
+//! Adapt the colors to what the terminal supports. The colors of the config
+//! are 24-bit, so they are converted to the nearest 256 or 16 colors when the
+//! terminal doesn't support them, or replaced with text modifiers when colors
+//! are turned off with `NO_COLOR`. The rendered buffer is adapted, so the
+//! colors that don't come from the config, e.g., syntax highlighting, are
+//! converted too.
+
+use std::env;
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+use crate::ColorsConfig;
+
+/// The values that `color_mode` can be set to. "auto" detects the mode from
+/// the environment.
+pub const COLOR_MODES: [&str; 5] = ["auto", "truecolor", "256", "16", "none"];
+
+/// The levels of each channel of the 6x6x6 color cube of the 256 colors.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// The 16 colors with the RGB values of xterm.
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The colors that the terminal can show.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorMode {
+    /// 24-bit colors.
+    TrueColor,
+    /// The 256 indexed colors.
+    Ansi256,
+    /// The 16 basic colors.
+    Ansi16,
+    /// No colors, only text modifiers.
+    Monochrome,
+}
+
+impl ColorMode {
+    /// Detect the colors that the terminal supports. `NO_COLOR` turns the
+    /// colors off, and otherwise `COLORTERM` and `TERM` are checked.
+    pub fn detect() -> Self {
+        if env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return ColorMode::Monochrome;
+        }
+
+        let colorterm = env::var("COLORTERM").unwrap_or_default().to_lowercase();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorMode::TrueColor;
+        }
+
+        let term = env::var("TERM").unwrap_or_default().to_lowercase();
+        if term == "dumb" {
+            ColorMode::Monochrome
+        } else if term.contains("truecolor") || term.contains("direct") {
+            ColorMode::TrueColor
+        } else if term.contains("256") {
+            ColorMode::Ansi256
+        } else if term.is_empty() && cfg!(windows) {
+            // The Windows terminals support 24-bit colors without setting TERM.
+            ColorMode::TrueColor
+        } else {
+            ColorMode::Ansi16
+        }
+    }
+
+    /// The mode of a `color_mode` value, which is detected for "auto". A mode
+    /// that is set takes precedence over `NO_COLOR`.
+    pub fn from_config(color_mode: &str) -> Self {
+        match color_mode {
+            "truecolor" => ColorMode::TrueColor,
+            "256" => ColorMode::Ansi256,
+            "16" => ColorMode::Ansi16,
+            "none" => ColorMode::Monochrome,
+            _ => ColorMode::detect(),
+        }
+    }
+
+    /// Convert a color to the nearest color of the mode. The colors are reset
+    /// in monochrome mode.
+    pub fn color(&self, color: Color) -> Color {
+        match (self, color) {
+            (ColorMode::TrueColor, _) | (_, Color::Reset) => color,
+            (ColorMode::Monochrome, _) => Color::Reset,
+            (ColorMode::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest_256((r, g, b))),
+            (ColorMode::Ansi256, _) => color,
+            (ColorMode::Ansi16, Color::Rgb(r, g, b)) => nearest_16((r, g, b)),
+            (ColorMode::Ansi16, Color::Indexed(i)) => nearest_16(indexed_rgb(i)),
+            (ColorMode::Ansi16, _) => color,
+        }
+    }
+
+    /// Adapt the colors of a rendered buffer to the mode. In monochrome mode,
+    /// the focused and active backgrounds are reversed and the emphasized
+    /// colors are bold, so focus and modes can still be told apart, while the
+    /// muted colors are dimmed.
+    pub fn adapt_buffer(&self, buffer: &mut Buffer, colors: &ColorsConfig) {
+        if *self == ColorMode::TrueColor {
+            return;
+        }
+
+        let reversed = [
+            colors.input_focus_bg,
+            colors.active_bg,
+            colors.input_cursor_bg,
+            colors.input_cursor_insert_bg,
+            colors.status_bar_normal_mode_bg,
+            colors.status_bar_insert_mode_bg,
+            colors.status_bar_delete_mode_bg,
+        ];
+        let bold = [
+            colors.highlight_fg,
+            colors.primary,
+            colors.success,
+            colors.warning,
+            colors.danger,
+            colors.border_active,
+            colors.border_insert,
+            colors.tab_active_fg,
+            colors.keybind_key,
+        ];
+        let dim = [
+            colors.secondary_fg,
+            colors.tertiary_fg,
+            colors.border,
+            colors.tab_fg,
+        ];
+
+        for cell in buffer.content.iter_mut() {
+            if *self == ColorMode::Monochrome {
+                let mut modifier = cell.modifier;
+                if reversed.contains(&cell.bg) {
+                    modifier |= Modifier::REVERSED;
+                }
+                if bold.contains(&cell.fg) {
+                    modifier |= Modifier::BOLD;
+                } else if dim.contains(&cell.fg) {
+                    modifier |= Modifier::DIM;
+                }
+                cell.modifier = modifier;
+            }
+            cell.fg = self.color(cell.fg);
+            cell.bg = self.color(cell.bg);
+            cell.underline_color = self.color(cell.underline_color);
+        }
+    }
+}
+
+/// The RGB value of an indexed color.
+fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => ANSI_COLORS[index as usize].1,
+        16..=231 => {
+            let i = index - 16;
+            (
+                CUBE_LEVELS[(i / 36) as usize],
+                CUBE_LEVELS[(i / 6 % 6) as usize],
+                CUBE_LEVELS[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// The squared distance between two colors, weighted by how sensitive the eye
+/// is to each channel.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    2 * d(a.0, b.0) + 4 * d(a.1, b.1) + 3 * d(a.2, b.2)
+}
+
+/// The nearest of the 256 colors, which is either in the color cube or on the
+/// grayscale ramp.
+fn nearest_256(rgb: (u8, u8, u8)) -> u8 {
+    let level = |v: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|i| (CUBE_LEVELS[*i] as i32 - v as i32).abs())
+            .unwrap_or(0) as u8
+    };
+    let cube = 16 + 36 * level(rgb.0) + 6 * level(rgb.1) + level(rgb.2);
+
+    let average = (rgb.0 as u32 + rgb.1 as u32 + rgb.2 as u32) / 3;
+    let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+    if distance(rgb, indexed_rgb(gray)) < distance(rgb, indexed_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+/// The nearest of the 16 colors.
+fn nearest_16(rgb: (u8, u8, u8)) -> Color {
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, ansi)| distance(rgb, *ansi))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
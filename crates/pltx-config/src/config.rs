@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 /// The base/merged colors config.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ColorsConfig<S = String, C = Color> {
-    // TODO: color presets to be implemented
+    /// A built-in preset or the name of a theme file in the themes directory.
     pub preset: S,
     pub fg: C,
     pub bg: C,
@@ -49,6 +49,9 @@ pub struct ColorsConfig<S = String, C = Color> {
     pub status_bar_delete_mode_fg: C,
 }
 
+/// The user colors config, which is also the format of a theme file.
+pub type ColorsConfigFile = ColorsConfig<Option<String>, Option<String>>;
+
 /// The base/merged home module config.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct HomeModule<S> {
@@ -61,6 +64,7 @@ pub struct HomeModule<S> {
 pub struct ProjectManagementModule<N = i32, C = String> {
     pub max_lists: N,
     pub due_soon_days: N,
+    pub archive_purge_days: N,
     pub completed_char: C,
     pub overdue_char: C,
     pub due_soon_char: C,
@@ -83,13 +87,31 @@ pub struct ModulesConfigFile {
     pub project_management: Option<ProjectManagementModule<Option<i32>, Option<String>>>,
 }
 
+/// The base/merged date and time config.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DateTimeConfig<S = String, B = bool> {
+    pub date_format: S,
+    pub clock: S,
+    pub first_day_of_week: S,
+    pub relative_dates: B,
+    pub timezone: S,
+}
+
+/// The user date and time config.
+pub type DateTimeConfigFile = DateTimeConfig<Option<String>, Option<bool>>;
+
 /// The base/merged profile config
 #[derive(Clone, Debug, Deserialize, Serialize)]
-pub struct ProfileConfig<S = String> {
+pub struct ProfileConfig<S = String, B = bool, N = i32> {
     pub name: S,
     pub config_file: S,
     pub db_file: S,
     pub log_file: S,
+    pub encrypted: B,
+    pub backups: N,
+    pub idle_timeout: N,
+    pub sync_server: S,
+    pub sync_interval: N,
 }
 
 impl From<ProfileConfig<&'static str>> for ProfileConfig<String> {
@@ -99,16 +121,239 @@ impl From<ProfileConfig<&'static str>> for ProfileConfig<String> {
             config_file: config.config_file.into(),
             db_file: config.db_file.into(),
             log_file: config.log_file.into(),
+            encrypted: config.encrypted,
+            backups: config.backups,
+            idle_timeout: config.idle_timeout,
+            sync_server: config.sync_server.into(),
+            sync_interval: config.sync_interval,
         }
     }
 }
 
+/// The user profile config.
+pub type ProfileConfigFile = ProfileConfig<Option<String>, Option<bool>, Option<i32>>;
+
 /// The user config.
 #[derive(Deserialize, Serialize)]
 pub struct ConfigFile {
     pub log_level: Option<String>,
+    pub color_mode: Option<String>,
     pub default_profile: Option<String>,
-    pub profiles: Option<Vec<ProfileConfig<Option<String>>>>,
-    pub colors: Option<ColorsConfig<Option<String>, Option<String>>>,
+    pub profiles: Option<Vec<ProfileConfigFile>>,
+    pub colors: Option<ColorsConfigFile>,
+    pub datetime: Option<DateTimeConfigFile>,
     pub modules: Option<ModulesConfigFile>,
+    pub keys: Option<KeysConfigFile>,
+}
+
+/// Define a scope of keybindings. Each action is a field with the keys that
+/// trigger it, and the doc comment of the action describes it on the
+/// keybindings help page.
+macro_rules! key_scope {
+    ($(#[doc = $scope_doc:literal])* $scope:ident { $($(#[doc = $doc:literal])+ $action:ident,)+ }) => {
+        $(#[doc = $scope_doc])*
+        #[derive(Clone, Debug, Deserialize, Serialize)]
+        pub struct $scope<K> {
+            $($(#[doc = $doc])+ pub $action: K,)+
+        }
+
+        impl<K> $scope<K> {
+            /// The name, description, and keys of every action.
+            pub fn actions(&self) -> KeyActions<K> {
+                vec![$((stringify!($action), concat!($($doc),+).trim(), &self.$action),)+]
+            }
+        }
+    };
+}
+
+key_scope!(
+    /// The keys that are available in every view, except while typing.
+    GlobalKeys {
+        /// Open the command prompt
+        command,
+        /// Search all cards
+        search,
+        /// Show the notification history
+        notifications,
+        /// Undo the last change
+        undo,
+        /// Redo the last undone change
+        redo,
+        /// Show the debug pane
+        debug_pane,
+        /// Rotate the debug pane
+        debug_position,
+        /// Toggle the minimum size preview
+        debug_min_preview,
+    }
+);
+
+key_scope!(
+    /// The keys that move around lists, pages, and popups.
+    NavigationKeys {
+        /// Move up
+        up,
+        /// Move down
+        down,
+        /// Move to the left
+        left,
+        /// Move to the right
+        right,
+        /// Go to the top
+        top,
+        /// Go to the bottom
+        bottom,
+        /// Open or choose the focused item
+        open,
+        /// Go back
+        back,
+        /// Close a popup
+        close,
+        /// Focus on the next pane
+        next_pane,
+        /// Focus on the previous pane
+        prev_pane,
+        /// Go to the next tab
+        next_tab,
+        /// Go to the previous tab
+        prev_tab,
+    }
+);
+
+key_scope!(
+    /// The keys of lists where items can be selected, such as subtasks and
+    /// labels.
+    SelectionKeys {
+        /// Select or unselect the focused item
+        select,
+        /// Select or unselect all items
+        select_all,
+        /// Invert the selection
+        invert_selection,
+    }
+);
+
+key_scope!(
+    /// The keys that change the focused item, where it supports them.
+    EditingKeys {
+        /// Create new
+        new,
+        /// Edit
+        edit,
+        /// Delete, or archive if it can be archived
+        delete,
+        /// Duplicate
+        duplicate,
+        /// Save a form
+        submit,
+        /// Show the archived items
+        archived,
+        /// Restore an archived item
+        restore,
+    }
+);
+
+key_scope!(
+    /// The keys that answer a prompt, e.g., whether to delete an item.
+    ConfirmKeys {
+        /// Yes
+        yes,
+        /// No
+        no,
+    }
+);
+
+key_scope!(
+    /// The keys of text inputs in normal mode. The keys in insert mode insert
+    /// the characters that are typed.
+    TextInputKeys {
+        /// Insert
+        insert,
+        /// Insert at the start of the line
+        insert_start,
+        /// Insert after the cursor
+        append,
+        /// Insert at the end of the line
+        append_end,
+        /// Move left a character
+        cursor_left,
+        /// Move right a character
+        cursor_right,
+        /// Move right a word
+        next_word,
+        /// Move left a word
+        prev_word,
+        /// Go to the start of the line
+        line_start,
+        /// Go to the end of the line
+        line_end,
+        /// Delete a character
+        delete_char,
+        /// Delete the line when pressed twice
+        delete_line,
+    }
+);
+
+key_scope!(
+    /// The keys of an open project.
+    ProjectManagementKeys {
+        /// Show the recent activity
+        activity,
+        /// Mark the focused card as completed
+        complete_card,
+        /// Mark the focused card as important
+        important_card,
+        /// Move the focused card up
+        move_up,
+        /// Move the focused card down
+        move_down,
+        /// Move the focused card or list to the left
+        move_left,
+        /// Move the focused card or list to the right
+        move_right,
+    }
+);
+
+/// The base/merged keybindings config. Each scope is active in its own
+/// contexts, so the same key can be used for different actions in different
+/// scopes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeysConfig<K> {
+    pub global: GlobalKeys<K>,
+    pub navigation: NavigationKeys<K>,
+    pub selection: SelectionKeys<K>,
+    pub editing: EditingKeys<K>,
+    pub confirm: ConfirmKeys<K>,
+    pub text_input: TextInputKeys<K>,
+    pub project_management: ProjectManagementKeys<K>,
+}
+
+/// The actions of a scope with their name, description, and keys.
+pub type KeyActions<'a, K> = Vec<(&'static str, &'static str, &'a K)>;
+
+impl<K> KeysConfig<K> {
+    /// The name and actions of every scope.
+    pub fn scopes(&self) -> Vec<(&'static str, KeyActions<K>)> {
+        vec![
+            ("global", self.global.actions()),
+            ("navigation", self.navigation.actions()),
+            ("selection", self.selection.actions()),
+            ("editing", self.editing.actions()),
+            ("confirm", self.confirm.actions()),
+            ("text_input", self.text_input.actions()),
+            ("project_management", self.project_management.actions()),
+        ]
+    }
+}
+
+/// The user keybindings config.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct KeysConfigFile {
+    pub global: Option<GlobalKeys<Option<String>>>,
+    pub navigation: Option<NavigationKeys<Option<String>>>,
+    pub selection: Option<SelectionKeys<Option<String>>>,
+    pub editing: Option<EditingKeys<Option<String>>>,
+    pub confirm: Option<ConfirmKeys<Option<String>>>,
+    pub text_input: Option<TextInputKeys<Option<String>>>,
+    pub project_management: Option<ProjectManagementKeys<Option<String>>>,
 }
@@ -0,0 +1,511 @@
+use std::{fmt, ops::Range, str::FromStr};
+
+use pltx_utils::{DateTimeFormat, CLOCKS, WEEKDAYS};
+use ratatui::style::Color;
+use toml_edit::{DocumentMut, ImDocument, Item, TableLike};
+
+use crate::{
+    base_config,
+    color_mode::COLOR_MODES,
+    keys::{key_conflicts, Keys},
+    merge_keys, theme_names,
+    themes::read_theme,
+    Config, ConfigFile, ProfileConfigFile, MAIN_CONFIG_FILE,
+};
+
+/// The levels that `log_level` can be set to.
+pub const LOG_LEVELS: [&str; 4] = ["debug", "info", "warn", "error"];
+
+/// A problem in a config file. The value that has the problem falls back to
+/// its default, so a mistake doesn't stop the application from starting.
+#[derive(Clone, Debug)]
+pub struct ConfigError {
+    /// The name of the file in the config directory.
+    pub file: String,
+    /// The line of the value, if it's known.
+    pub line: Option<usize>,
+    /// The path of the key, e.g., `colors.fg` or `profiles[1].db_file`. It's
+    /// empty if the problem is with the whole file.
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.file)?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if !self.key.is_empty() {
+            write!(f, ": {}", self.key)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// A part of the path of a key.
+#[derive(Clone)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+fn path_string(path: &[Segment]) -> String {
+    let mut string = String::new();
+    for segment in path {
+        match segment {
+            Segment::Key(key) if string.is_empty() => string.push_str(key),
+            Segment::Key(key) => string.push_str(&format!(".{}", key)),
+            Segment::Index(index) => string.push_str(&format!("[{}]", index)),
+        }
+    }
+    string
+}
+
+/// The line number of a byte offset, starting from 1.
+fn line_at(contents: &str, offset: usize) -> usize {
+    contents[..offset.min(contents.len())].matches('\n').count() + 1
+}
+
+/// The span of the key at a path, or of the table for a path that ends with an
+/// index.
+fn span(table: &dyn TableLike, path: &[Segment]) -> Option<Range<usize>> {
+    match path {
+        [Segment::Key(key)] => table
+            .key(key)
+            .and_then(|k| k.span())
+            .or_else(|| table.get(key)?.span()),
+        [Segment::Key(key), Segment::Index(index), rest @ ..] => {
+            let table = table.get(key)?.as_array_of_tables()?.get(*index)?;
+            if rest.is_empty() {
+                table.span()
+            } else {
+                span(table, rest)
+            }
+        }
+        [Segment::Key(key), rest @ ..] => span(table.get(key)?.as_table_like()?, rest),
+        _ => None,
+    }
+}
+
+/// The path of the value that contains a byte offset.
+fn path_at(table: &dyn TableLike, offset: usize, path: &mut Vec<Segment>) -> bool {
+    for (key, item) in table.iter() {
+        path.push(Segment::Key(key.to_string()));
+        let found = match item {
+            Item::ArrayOfTables(tables) => tables.iter().enumerate().any(|(i, table)| {
+                path.push(Segment::Index(i));
+                let found = path_at(table, offset, path);
+                if !found {
+                    path.pop();
+                }
+                found
+            }),
+            Item::Table(table) => path_at(table, offset, path),
+            Item::Value(value) => {
+                value
+                    .as_inline_table()
+                    .is_some_and(|t| path_at(t, offset, path))
+                    || value.span().is_some_and(|s| s.contains(&offset))
+            }
+            Item::None => false,
+        };
+        if found {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Remove the key at a path. Returns whether it existed.
+fn remove(table: &mut dyn TableLike, path: &[Segment]) -> bool {
+    match path {
+        [Segment::Key(key)] => table.remove(key).is_some(),
+        [Segment::Key(key), Segment::Index(index), rest @ ..] => {
+            let Some(tables) = table.get_mut(key).and_then(|i| i.as_array_of_tables_mut()) else {
+                return false;
+            };
+            match tables.get_mut(*index) {
+                Some(table) if !rest.is_empty() => remove(table, rest),
+                Some(_) => {
+                    tables.remove(*index);
+                    true
+                }
+                None => false,
+            }
+        }
+        [Segment::Key(key), rest @ ..] => table
+            .get_mut(key)
+            .and_then(|i| i.as_table_like_mut())
+            .is_some_and(|t| remove(t, rest)),
+        _ => false,
+    }
+}
+
+/// Find the keys that aren't part of the config. `known` is the config after
+/// it has been deserialized, which only has the keys that are used.
+fn unknown_keys(
+    table: &dyn TableLike,
+    known: &toml::Table,
+    path: &mut Vec<Segment>,
+    unknown: &mut Vec<Vec<Segment>>,
+) {
+    for (key, item) in table.iter() {
+        path.push(Segment::Key(key.to_string()));
+        match (item, known.get(key)) {
+            (_, None) => unknown.push(path.clone()),
+            (Item::ArrayOfTables(tables), Some(toml::Value::Array(values))) => {
+                for (i, (table, value)) in tables.iter().zip(values).enumerate() {
+                    if let Some(value) = value.as_table() {
+                        path.push(Segment::Index(i));
+                        unknown_keys(table, value, path, unknown);
+                        path.pop();
+                    }
+                }
+            }
+            (item, Some(toml::Value::Table(value))) => {
+                if let Some(table) = item.as_table_like() {
+                    unknown_keys(table, value, path, unknown);
+                }
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+}
+
+/// The problems found in a config file, with the lines looked up in the
+/// original contents.
+struct Diagnostics<'a> {
+    file: &'a str,
+    contents: &'a str,
+    original: ImDocument<&'a str>,
+    errors: &'a mut Vec<ConfigError>,
+}
+
+impl Diagnostics<'_> {
+    fn report(&mut self, path: &[Segment], message: String) {
+        let line = span(self.original.as_table(), path).map(|s| line_at(self.contents, s.start));
+        self.errors.push(ConfigError {
+            file: self.file.to_string(),
+            line,
+            key: path_string(path),
+            message,
+        });
+    }
+}
+
+/// Parse a config file and collect its problems. The keys with invalid values
+/// are left out, so they fall back to their defaults. Returns `None` if the
+/// file cannot be parsed at all.
+pub(crate) fn parse_config_file(
+    file: &str,
+    contents: &str,
+    errors: &mut Vec<ConfigError>,
+) -> Option<ConfigFile> {
+    let original = match ImDocument::parse(contents) {
+        Ok(original) => original,
+        Err(err) => {
+            errors.push(ConfigError {
+                file: file.to_string(),
+                line: err.span().map(|s| line_at(contents, s.start)),
+                key: String::new(),
+                message: format!(
+                    "{}, so the whole file is ignored",
+                    err.message().trim().replace('\n', ", ")
+                ),
+            });
+            return None;
+        }
+    };
+    let mut doc = contents.parse::<DocumentMut>().ok()?;
+    let first_error = errors.len();
+    let mut diagnostics = Diagnostics {
+        file,
+        contents,
+        original,
+        errors,
+    };
+
+    check_values(&mut doc, &mut diagnostics);
+
+    // Every key that fails to deserialize is removed until the rest of the file
+    // can be used.
+    let mut config = loop {
+        let text = doc.to_string();
+        let err = match toml::from_str::<ConfigFile>(&text) {
+            Ok(config) => break config,
+            Err(err) => err,
+        };
+        let mut path = vec![];
+        let found = match (err.span(), ImDocument::parse(text.as_str())) {
+            (Some(span), Ok(current)) => path_at(current.as_table(), span.start, &mut path),
+            _ => false,
+        };
+        if found && remove(doc.as_table_mut(), &path) {
+            diagnostics.report(&path, err.message().to_string());
+        } else {
+            diagnostics.report(
+                &[],
+                format!("{}, so the whole file is ignored", err.message()),
+            );
+            return None;
+        }
+    };
+
+    if let Some(checked) = check_key_conflicts(&mut doc, &mut diagnostics) {
+        config = checked;
+    }
+
+    if let Ok(toml::Value::Table(known)) = toml::Value::try_from(&config) {
+        let mut unknown = vec![];
+        unknown_keys(doc.as_table(), &known, &mut vec![], &mut unknown);
+        for path in unknown {
+            diagnostics.report(&path, "unknown key, so it's ignored".to_string());
+        }
+    }
+
+    // The profiles are only read from config.toml, since they are needed to
+    // find the config file of a profile.
+    if file != MAIN_CONFIG_FILE {
+        for key in ["default_profile", "profiles"] {
+            if doc.contains_key(key) {
+                diagnostics.report(
+                    &[Segment::Key(key.to_string())],
+                    format!("can only be set in {}, so it's ignored", MAIN_CONFIG_FILE),
+                );
+            }
+        }
+        config.default_profile = None;
+        config.profiles = None;
+    }
+
+    if let Some(profiles) = config.profiles.take() {
+        config.profiles = Some(check_profiles(profiles, &mut diagnostics));
+    }
+
+    if let Some(default_profile) = &config.default_profile {
+        // The user profiles are added to the base profiles.
+        let exists = base_config()
+            .profiles
+            .iter()
+            .any(|p| p.name == default_profile)
+            || config.profiles.as_ref().is_some_and(|profiles| {
+                profiles
+                    .iter()
+                    .any(|p| p.name.as_ref() == Some(default_profile))
+            });
+        if !exists {
+            diagnostics.report(
+                &[Segment::Key("default_profile".to_string())],
+                format!("no profile named \"{}\"", default_profile),
+            );
+            config.default_profile = None;
+        }
+    }
+
+    diagnostics.errors[first_error..].sort_by_key(|e| e.line);
+    Some(config)
+}
+
+/// Check the values that have the right type but are invalid, and remove
+/// them.
+fn check_values(doc: &mut DocumentMut, diagnostics: &mut Diagnostics) {
+    let mut invalid = vec![];
+
+    if let Some(log_level) = doc.get("log_level").and_then(|i| i.as_str()) {
+        if !LOG_LEVELS.contains(&log_level) {
+            invalid.push((
+                vec![Segment::Key("log_level".to_string())],
+                format!(
+                    "invalid log level \"{}\", expected one of {}",
+                    log_level,
+                    LOG_LEVELS.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if let Some(color_mode) = doc.get("color_mode").and_then(|i| i.as_str()) {
+        if !COLOR_MODES.contains(&color_mode) {
+            invalid.push((
+                vec![Segment::Key("color_mode".to_string())],
+                format!(
+                    "invalid color mode \"{}\", expected one of {}",
+                    color_mode,
+                    COLOR_MODES.join(", ")
+                ),
+            ));
+        }
+    }
+
+    if let Some(colors) = doc.get("colors").and_then(|i| i.as_table_like()) {
+        for (key, item) in colors.iter() {
+            let Some(value) = item.as_str() else {
+                continue;
+            };
+            let message = if key == "preset" {
+                let themes = theme_names();
+                if !themes.iter().any(|t| t == value) {
+                    Some(format!(
+                        "no theme named \"{}\", expected one of {}",
+                        value,
+                        themes.join(", ")
+                    ))
+                } else {
+                    read_theme(value).err().map(|err| err.to_string())
+                }
+            } else {
+                Color::from_str(value).is_err().then(|| {
+                    format!(
+                        "invalid color \"{}\", expected a hex color like \"#ff0000\" or a color \
+                         name",
+                        value
+                    )
+                })
+            };
+            if let Some(message) = message {
+                invalid.push((
+                    vec![
+                        Segment::Key("colors".to_string()),
+                        Segment::Key(key.to_string()),
+                    ],
+                    message,
+                ));
+            }
+        }
+    }
+
+    if let Some(datetime) = doc.get("datetime").and_then(|i| i.as_table_like()) {
+        for (key, item) in datetime.iter() {
+            let Some(value) = item.as_str() else {
+                continue;
+            };
+            let message = match key {
+                "date_format" => DateTimeFormat::check_date_format(value).err(),
+                "timezone" => DateTimeFormat::check_timezone(value).err(),
+                "clock" => (!CLOCKS.contains(&value)).then(|| {
+                    format!(
+                        "invalid clock \"{}\", expected one of {}",
+                        value,
+                        CLOCKS.join(", ")
+                    )
+                }),
+                "first_day_of_week" => (!WEEKDAYS.contains(&value)).then(|| {
+                    format!(
+                        "invalid day \"{}\", expected one of {}",
+                        value,
+                        WEEKDAYS.join(", ")
+                    )
+                }),
+                _ => None,
+            };
+            if let Some(message) = message {
+                invalid.push((
+                    vec![
+                        Segment::Key("datetime".to_string()),
+                        Segment::Key(key.to_string()),
+                    ],
+                    message,
+                ));
+            }
+        }
+    }
+
+    if let Some(keys) = doc.get("keys").and_then(|i| i.as_table_like()) {
+        for (scope, item) in keys.iter() {
+            let Some(actions) = item.as_table_like() else {
+                continue;
+            };
+            for (action, item) in actions.iter() {
+                if let Some(Err(err)) = item.as_str().map(Keys::from_str) {
+                    invalid.push((
+                        vec![
+                            Segment::Key("keys".to_string()),
+                            Segment::Key(scope.to_string()),
+                            Segment::Key(action.to_string()),
+                        ],
+                        err,
+                    ));
+                }
+            }
+        }
+    }
+
+    for (path, message) in invalid {
+        remove(doc.as_table_mut(), &path);
+        diagnostics.report(&path, message);
+    }
+}
+
+/// Leave out the keys that are bound to another action that is available at
+/// the same time, so the action falls back to its default keys. Returns the
+/// config without them if any were left out.
+fn check_key_conflicts(doc: &mut DocumentMut, diagnostics: &mut Diagnostics) -> Option<ConfigFile> {
+    let base_keys = Config::from(base_config()).keys;
+    let mut config: Option<ConfigFile> = None;
+    loop {
+        let user_keys = match &config {
+            Some(config) => config.keys.clone(),
+            None => toml::from_str::<ConfigFile>(&doc.to_string()).ok()?.keys,
+        }?;
+        let keys = merge_keys(user_keys, base_keys.clone());
+
+        // Leave out a user key of the first conflict that has one, and check
+        // again with the default keys of the action.
+        let left_out = key_conflicts(&keys).into_iter().find_map(|conflict| {
+            [
+                (conflict.second, conflict.first),
+                (conflict.first, conflict.second),
+            ]
+            .into_iter()
+            .map(|((scope, action), other)| {
+                let path = vec![
+                    Segment::Key("keys".to_string()),
+                    Segment::Key(scope.to_string()),
+                    Segment::Key(action.to_string()),
+                ];
+                (path, other)
+            })
+            .find(|(path, _)| remove(doc.as_table_mut(), path))
+            .map(|(path, (scope, action))| {
+                let message = format!(
+                    "\"{}\" is also bound to keys.{}.{}, so the default is used",
+                    conflict.key, scope, action
+                );
+                (path, message)
+            })
+        });
+
+        match left_out {
+            Some((path, message)) => {
+                diagnostics.report(&path, message);
+                config = Some(toml::from_str(&doc.to_string()).ok()?);
+            }
+            None => return config,
+        }
+    }
+}
+
+/// Leave out the profiles without a name. The values that aren't set fall back
+/// to the base profile with the same name, or to their defaults, when the
+/// config is merged.
+fn check_profiles(
+    profiles: Vec<ProfileConfigFile>,
+    diagnostics: &mut Diagnostics,
+) -> Vec<ProfileConfigFile> {
+    let mut checked = vec![];
+    for (i, profile) in profiles.into_iter().enumerate() {
+        let path = vec![Segment::Key("profiles".to_string()), Segment::Index(i)];
+        if profile.name.is_none() {
+            diagnostics.report(
+                &path,
+                "the profile has no name, so it's ignored".to_string(),
+            );
+            continue;
+        }
+        checked.push(profile);
+    }
+    checked
+}
@@ -0,0 +1,212 @@
+//! Change the values of a config file in place, so its comments and formatting
+//! are kept. A key is the path of a value, e.g., `colors.fg`.
+
+use std::{fs, io::ErrorKind};
+
+use color_eyre::{eyre::eyre, Result};
+use pltx_utils::dirs;
+use toml_edit::{DocumentMut, Item, Table, TableLike, Value};
+
+use crate::{base_config, diagnostics::parse_config_file, Config, ProfileConfig, MAIN_CONFIG_FILE};
+
+/// Read a config file to be edited. It's empty if it doesn't exist.
+pub(crate) fn read_config_document(file: &str) -> Result<DocumentMut> {
+    match fs::read_to_string(dirs::config_dir().join(file)) {
+        Ok(contents) => contents
+            .parse::<DocumentMut>()
+            .map_err(|e| eyre!("failed to parse {}: {}", file, e.message())),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(DocumentMut::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// The default value of a key, which has the type that the key must be set
+/// to.
+fn base_value(key: &str) -> Option<toml::Value> {
+    let base = toml::Value::try_from(base_config()).ok()?;
+    key.split('.')
+        .try_fold(&base, |value, segment| value.get(segment))
+        .cloned()
+}
+
+/// Parse a value for a key with the type of its default value.
+fn parse_value(key: &str, value: &str) -> Result<Value> {
+    match base_value(key) {
+        Some(toml::Value::String(_)) => Ok(Value::from(value)),
+        Some(toml::Value::Integer(_)) => value
+            .trim()
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| eyre!("{} must be a whole number", key)),
+        Some(toml::Value::Boolean(_)) => value
+            .trim()
+            .parse::<bool>()
+            .map(Value::from)
+            .map_err(|_| eyre!("{} must be true or false", key)),
+        Some(toml::Value::Table(_)) => Err(eyre!("{} is a table, set one of its keys", key)),
+        Some(_) => Err(eyre!("{} cannot be set", key)),
+        None => Err(eyre!("unknown key {}", key)),
+    }
+}
+
+/// The table that a key is in. The tables that don't exist are added if
+/// `create` is set.
+fn parent_table<'a>(
+    doc: &'a mut DocumentMut,
+    key: &str,
+    create: bool,
+) -> Result<Option<&'a mut dyn TableLike>> {
+    let mut parent: &mut dyn TableLike = doc.as_table_mut();
+    let segments = key.split('.').collect::<Vec<&str>>();
+    for segment in &segments[..segments.len() - 1] {
+        if !create && !parent.contains_key(segment) {
+            return Ok(None);
+        }
+        // A new table only has a header if it has values.
+        let mut table = Table::new();
+        table.set_implicit(true);
+        parent = parent
+            .entry(segment)
+            .or_insert(Item::Table(table))
+            .as_table_like_mut()
+            .ok_or_else(|| eyre!("{} is not a table", segment))?;
+    }
+    Ok(Some(parent))
+}
+
+/// Remove a key. The comments above it are moved to the next key, since they
+/// often describe the table rather than the value.
+fn remove_key(parent: &mut dyn TableLike, name: &str) {
+    let next = parent
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .skip_while(|key| key != name)
+        .nth(1);
+    let comments = parent
+        .key(name)
+        .and_then(|key| key.leaf_decor().prefix())
+        .and_then(|prefix| prefix.as_str())
+        .filter(|prefix| prefix.contains('#'))
+        .map(|prefix| prefix.to_string());
+    parent.remove(name);
+
+    if let (Some(comments), Some(mut next)) = (comments, next.and_then(|n| parent.key_mut(&n))) {
+        let prefix = next
+            .leaf_decor()
+            .prefix()
+            .and_then(|prefix| prefix.as_str())
+            .unwrap_or_default()
+            .to_string();
+        next.leaf_decor_mut()
+            .set_prefix(format!("{}{}", comments, prefix.trim_start_matches(' ')));
+    }
+}
+
+/// The config file that a key is set in for a profile. The profiles are only
+/// read from config.toml, and the other values from the config file of the
+/// profile.
+pub fn config_key_file(profile: &ProfileConfig, key: &str) -> String {
+    match key.split('.').next() {
+        Some("default_profile" | "profiles") => MAIN_CONFIG_FILE.to_string(),
+        _ => profile.config_file.clone(),
+    }
+}
+
+/// Get a value that is set in a config file. Strings are returned without
+/// quotes. Returns `None` if it isn't set.
+pub fn config_file_value(file: &str, key: &str) -> Option<String> {
+    let doc = read_config_document(file).ok()?;
+    let item = key
+        .split('.')
+        .try_fold(doc.as_item(), |item, segment| item.get(segment))?;
+    match item.as_value()? {
+        Value::String(value) => Some(value.value().to_string()),
+        value => Some(value.to_string().trim().to_string()),
+    }
+}
+
+/// Set a value in a config document. A value of `None` removes the key.
+pub(crate) fn set_value(doc: &mut DocumentMut, key: &str, value: Option<&str>) -> Result<()> {
+    let name = key.rsplit('.').next().unwrap_or(key);
+    match value {
+        Some(value) => {
+            let value = parse_value(key, value)?;
+            let parent = parent_table(doc, key, true)?.expect("the table is created");
+            match parent.get_mut(name).and_then(|i| i.as_value_mut()) {
+                // The comment after the old value is kept.
+                Some(old) => {
+                    let decor = old.decor().clone();
+                    *old = value;
+                    *old.decor_mut() = decor;
+                }
+                None => {
+                    parent.insert(name, Item::Value(value));
+                }
+            }
+        }
+        None => {
+            if let Some(parent) = parent_table(doc, key, false)? {
+                remove_key(parent, name);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Set values in a config file. A value of `None` removes the key, so it falls
+/// back to its default. The file is only written if the values don't cause new
+/// problems, which are returned as the error instead.
+pub fn set_config_values(file: &str, values: &[(&str, Option<&str>)]) -> Result<()> {
+    let mut doc = read_config_document(file)?;
+    let original = doc.to_string();
+
+    for (key, value) in values {
+        set_value(&mut doc, key, *value)?;
+    }
+
+    let contents = doc.to_string();
+    let mut original_errors = vec![];
+    parse_config_file(file, &original, &mut original_errors);
+    let mut errors = vec![];
+    parse_config_file(file, &contents, &mut errors);
+    let new_errors = errors
+        .into_iter()
+        .filter(|e| {
+            !original_errors
+                .iter()
+                .any(|o| o.key == e.key && o.message == e.message)
+        })
+        .map(|e| {
+            if e.key.is_empty() {
+                e.message
+            } else {
+                format!("{}: {}", e.key, e.message)
+            }
+        })
+        .collect::<Vec<String>>();
+    if !new_errors.is_empty() {
+        return Err(eyre!(new_errors.join(", ")));
+    }
+
+    fs::write(dirs::config_dir().join(file), contents)?;
+    Ok(())
+}
+
+/// Get a value of the merged config. Strings are returned without quotes, and
+/// tables as TOML.
+pub fn config_value(config: &Config, key: &str) -> Option<String> {
+    let config = toml::Value::try_from(config).ok()?;
+    let value = key
+        .split('.')
+        .try_fold(&config, |value, segment| value.get(segment))?;
+    match value {
+        toml::Value::String(value) => Some(value.clone()),
+        toml::Value::Table(table) => toml::to_string(table).ok(),
+        value => Some(value.to_string()),
+    }
+}
+
+/// The merged config as TOML.
+pub fn config_to_toml(config: &Config) -> Result<String> {
+    Ok(toml::to_string(config)?)
+}
@@ -0,0 +1,70 @@
+//! Override config values with `PLTX_*` environment variables, which are
+//! merged on top of the config files. The key is the path of the value in
+//! upper case, with a double underscore between the tables, e.g.,
+//! `PLTX_LOG_LEVEL=debug` or `PLTX_COLORS__FG="#ff0000"`.
+
+use std::env;
+
+use color_eyre::eyre::eyre;
+use toml_edit::DocumentMut;
+
+use crate::{diagnostics::parse_config_file, edit::set_value, ConfigError, ConfigFile};
+
+/// The name that the problems of the environment variables are reported with.
+const ENV_FILE: &str = "environment";
+
+/// The variables that are used before the config is read.
+const NOT_CONFIG_VALUES: [&str; 2] = ["PLTX_CONFIG_DIR", "PLTX_DATA_DIR"];
+
+/// The key of a variable, e.g., `colors.fg` for `PLTX_COLORS__FG`.
+fn env_key(var: &str) -> Option<String> {
+    let key = var.strip_prefix("PLTX_")?;
+    Some(
+        key.to_lowercase()
+            .split("__")
+            .collect::<Vec<&str>>()
+            .join("."),
+    )
+}
+
+/// Read the config values that are set with environment variables. The
+/// problems are added to `errors` like the problems of a config file. Returns
+/// `None` if none are set.
+pub(crate) fn read_env_config(errors: &mut Vec<ConfigError>) -> Option<ConfigFile> {
+    let mut doc = DocumentMut::new();
+    let mut vars = env::vars_os()
+        .filter_map(|(var, value)| Some((var.into_string().ok()?, value)))
+        .filter(|(var, _)| !NOT_CONFIG_VALUES.contains(&var.as_str()))
+        .filter_map(|(var, value)| Some((env_key(&var)?, var, value)))
+        .collect::<Vec<_>>();
+    if vars.is_empty() {
+        return None;
+    }
+    vars.sort_by(|a, b| a.1.cmp(&b.1));
+
+    for (key, var, value) in vars {
+        let result = match value.to_str() {
+            Some(value) => set_value(&mut doc, &key, Some(value)),
+            None => Err(eyre!("the value isn't valid unicode")),
+        };
+        if let Err(err) = result {
+            errors.push(ConfigError {
+                file: ENV_FILE.to_string(),
+                line: None,
+                key: var,
+                message: format!("{}, so it's ignored", err),
+            });
+        }
+    }
+
+    let first_error = errors.len();
+    let config = parse_config_file(ENV_FILE, &doc.to_string(), errors);
+    // The problems are reported with the variable, since there are no lines.
+    for error in &mut errors[first_error..] {
+        error.line = None;
+        if !error.key.is_empty() {
+            error.key = format!("PLTX_{}", error.key.to_uppercase().replace('.', "__"));
+        }
+    }
+    config
+}
@@ -0,0 +1,204 @@
+//! Keybindings. A key is written as a character, e.g., `"H"`, or the name of
+//! a key, e.g., `"enter"`, with optional `ctrl+` and `alt+` modifiers. An
+//! action can have several keys separated by spaces, e.g., `"l right"`.
+
+use std::{fmt, str::FromStr};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::KeysConfig;
+
+/// The scopes that are active at the same time. A key can only be bound to
+/// one action of the scopes in each group.
+const SCOPES_USED_TOGETHER: [&[&str]; 4] = [
+    &["global", "navigation", "editing", "project_management"],
+    &["global", "navigation", "editing", "selection"],
+    &["confirm"],
+    &["text_input"],
+];
+
+/// The names of the keys that aren't characters.
+const KEY_NAMES: [(&str, KeyCode); 15] = [
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("space", KeyCode::Char(' ')),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+];
+
+/// A key with its modifiers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Whether a key event is this key. Shift is part of the character, so
+    /// only the control and alt modifiers are compared.
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        let modifiers = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        key_event.code == self.code && key_event.modifiers & modifiers == self.modifiers
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut key = s;
+        // A single character is always the key, so "+" can be bound.
+        while key.chars().count() > 1 {
+            if let Some(rest) = key.strip_prefix("ctrl+") {
+                modifiers |= KeyModifiers::CONTROL;
+                key = rest;
+            } else if let Some(rest) = key.strip_prefix("alt+") {
+                modifiers |= KeyModifiers::ALT;
+                key = rest;
+            } else {
+                break;
+            }
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => KEY_NAMES
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, code)| *code)
+                .ok_or_else(|| format!("unknown key \"{}\"", s))?,
+        };
+
+        Ok(KeyBinding { code, modifiers })
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        match KEY_NAMES.iter().find(|(_, code)| *code == self.code) {
+            Some((name, _)) => write!(f, "{}", name),
+            None => match self.code {
+                KeyCode::Char(c) => write!(f, "{}", c),
+                code => write!(f, "{:?}", code),
+            },
+        }
+    }
+}
+
+/// The keys that trigger an action.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Keys(pub Vec<KeyBinding>);
+
+impl Keys {
+    /// Whether a key event is one of the keys.
+    pub fn matches(&self, key_event: &KeyEvent) -> bool {
+        self.0.iter().any(|k| k.matches(key_event))
+    }
+
+    /// The first key, which is shown in hints.
+    pub fn first(&self) -> String {
+        self.0.first().map(|k| k.to_string()).unwrap_or_default()
+    }
+}
+
+impl FromStr for Keys {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(KeyBinding::from_str)
+            .collect::<Result<Vec<KeyBinding>, String>>()?;
+        if keys.is_empty() {
+            return Err("no keys are set".to_string());
+        }
+        Ok(Keys(keys))
+    }
+}
+
+impl fmt::Display for Keys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let keys = self
+            .0
+            .iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", keys.join(" "))
+    }
+}
+
+impl Serialize for Keys {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Keys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let keys = String::deserialize(deserializer)?;
+        Keys::from_str(&keys).map_err(de::Error::custom)
+    }
+}
+
+/// Two actions of scopes that are used together which have the same key.
+pub(crate) struct KeyConflict {
+    pub key: KeyBinding,
+    pub first: (&'static str, &'static str),
+    pub second: (&'static str, &'static str),
+}
+
+/// Find the actions that have the same key as an earlier action of a scope
+/// that is used at the same time.
+pub(crate) fn key_conflicts(keys: &KeysConfig<Keys>) -> Vec<KeyConflict> {
+    let scopes = keys.scopes();
+    let mut conflicts = vec![];
+    for group in SCOPES_USED_TOGETHER {
+        let mut bound: Vec<(KeyBinding, &'static str, &'static str)> = vec![];
+        for (scope, actions) in scopes.iter().filter(|(s, _)| group.contains(s)) {
+            for (action, _, action_keys) in actions {
+                for key in &action_keys.0 {
+                    match bound.iter().find(|(k, _, _)| k == key) {
+                        Some((_, first_scope, first_action))
+                            if (*first_scope, *first_action) != (*scope, *action) =>
+                        {
+                            let conflict = KeyConflict {
+                                key: *key,
+                                first: (first_scope, first_action),
+                                second: (scope, action),
+                            };
+                            // The groups share scopes, so a conflict can be
+                            // found more than once.
+                            if !conflicts.iter().any(|c: &KeyConflict| {
+                                c.first == conflict.first && c.second == conflict.second
+                            }) {
+                                conflicts.push(conflict);
+                            }
+                        }
+                        Some(_) => {}
+                        None => bound.push((*key, scope, action)),
+                    }
+                }
+            }
+        }
+    }
+    conflicts
+}
@@ -1,37 +1,73 @@
 //! Configuration should not be more than three levels deep, e.g.,
 //! `config.one.two.three`.
 
-use std::str::FromStr;
+use std::{io::ErrorKind, str::FromStr};
 
-use color_eyre::Result;
-use pltx_utils::dirs;
+use color_eyre::{eyre::eyre, Result};
+use pltx_utils::{dirs, DateTimeFormat, Weekday};
 use ratatui::style::Color;
 
-const COLOR_PRESETS: [&str; 1] = ["default"];
+/// The number of automatic backups kept for a user profile that doesn't set it.
+const DEFAULT_BACKUPS: i32 = 5;
+/// The number of seconds without a key press after which the user is idle, for
+/// a user profile that doesn't set it.
+const DEFAULT_IDLE_TIMEOUT: i32 = 300;
+/// The number of seconds between background syncs, for a user profile that
+/// doesn't set it.
+const DEFAULT_SYNC_INTERVAL: i32 = 60;
+/// The config file that defines the profiles, which is read for every profile.
+pub const MAIN_CONFIG_FILE: &str = "config.toml";
+/// The default config with its comments, which document the values.
+const DEFAULT_CONFIG: &str = include_str!("../../../docs/config.toml");
 
+mod color_mode;
 mod config;
+mod diagnostics;
+mod edit;
+mod env;
+mod keys;
+mod profiles;
+mod schema;
+mod themes;
+mod watch;
 
 include!("generated_config.rs");
 
+pub use color_mode::{ColorMode, COLOR_MODES};
 pub use config::*;
+pub use diagnostics::{ConfigError, LOG_LEVELS};
+pub use edit::{
+    config_file_value, config_key_file, config_to_toml, config_value, set_config_values,
+};
+pub use keys::{KeyBinding, Keys};
+pub use profiles::{copy_profile, create_profile, delete_profile, rename_profile, ProfileFiles};
+pub use schema::config_schema;
 use serde::{Deserialize, Serialize};
+pub use themes::{color_names, preview_colors, theme_colors, theme_names, UserColors};
+pub use watch::ConfigWatcher;
 
 /// The main base/merged config.
 #[derive(Clone, Deserialize, Serialize)]
 pub struct DefaultConfig {
     pub log_level: &'static str,
+    pub color_mode: &'static str,
     pub default_profile: &'static str,
     pub colors: ColorsConfig<&'static str, &'static str>,
+    pub datetime: DateTimeConfig<&'static str>,
     pub modules: ModulesConfig<&'static str>,
+    pub keys: KeysConfig<&'static str>,
     pub profiles: [ProfileConfig<&'static str>; 2],
 }
 
 #[derive(Clone, Deserialize, Serialize)]
 pub struct Config {
     pub log_level: String,
+    pub color_mode: String,
     pub default_profile: String,
     pub colors: ColorsConfig,
+    pub datetime: DateTimeConfig,
     pub modules: ModulesConfig,
+    pub keys: KeysConfig<Keys>,
     pub profiles: Vec<ProfileConfig>,
 }
 
@@ -42,100 +78,177 @@ impl From<DefaultConfig> for Config {
     }
 }
 
-fn read_config_file(filename: &str) -> Result<Option<ConfigFile>> {
-    let config_file = dirs::config_dir().join(filename);
-    let config_contents: Option<String> = std::fs::read_to_string(config_file).ok();
-    let config_toml: Option<ConfigFile> = match config_contents {
-        Some(contents) => toml::from_str(&contents).expect("the config is invalid"),
-        None => None,
-    };
-    Ok(config_toml)
+impl DateTimeConfig {
+    /// The format that dates and times are displayed and entered with.
+    pub fn format(&self) -> DateTimeFormat {
+        DateTimeFormat {
+            date: self.date_format.clone(),
+            twelve_hour: self.clock == "12h",
+            first_day_of_week: self.first_day_of_week.parse().unwrap_or(Weekday::Mon),
+            relative: self.relative_dates,
+            // The local timezone isn't a timezone name, so it's `None`.
+            timezone: self.timezone.parse().ok(),
+        }
+    }
 }
 
-/// Get a ratatui compatible color from a hex color.
-fn get_color(color: &str) -> Color {
-    Color::from_str(color).expect("failed to get color from string")
+/// The default config with its comments, as it can be copied into a config
+/// file.
+pub fn default_config_text() -> &'static str {
+    // The first paragraph is about the location of the file in the repository.
+    DEFAULT_CONFIG
+        .split_once("\n\n")
+        .map(|(_, rest)| rest)
+        .unwrap_or(DEFAULT_CONFIG)
 }
 
-/// Call the `get_color()` function if a color is provided (from user config),
-/// otherwise return the base config value.
+/// Read a config file from the config directory. The problems in the file are
+/// added to `errors`, and the values with problems are left out. Returns
+/// `None` if the file doesn't exist or cannot be used at all.
+fn read_config_file(filename: &str, errors: &mut Vec<ConfigError>) -> Option<ConfigFile> {
+    let config_file = dirs::config_dir().join(filename);
+    match std::fs::read_to_string(config_file) {
+        Ok(contents) => diagnostics::parse_config_file(filename, &contents, errors),
+        Err(err) if err.kind() == ErrorKind::NotFound => None,
+        Err(err) => {
+            errors.push(ConfigError {
+                file: filename.to_string(),
+                line: None,
+                key: String::new(),
+                message: format!("failed to read the file: {}", err),
+            });
+            None
+        }
+    }
+}
+
+/// Get a ratatui compatible color from a hex color or color name if a color is
+/// provided (from user config), otherwise return the base config value. The
+/// colors are validated when the config is read, so an invalid color only
+/// falls back to the base config value.
 fn color_op(color_op: Option<String>, base_config_color: Color) -> Color {
-    match color_op {
-        Some(color) => get_color(&color),
-        None => base_config_color,
+    color_op
+        .and_then(|color| Color::from_str(&color).ok())
+        .unwrap_or(base_config_color)
+}
+
+/// Merge the user colors with the base colors. The preset of the base colors
+/// is kept, since the preset is applied to the base colors first.
+fn merge_colors(a: ColorsConfigFile, b: ColorsConfig) -> ColorsConfig {
+    ColorsConfig {
+        preset: b.preset,
+        fg: color_op(a.fg, b.fg),
+        secondary_fg: color_op(a.secondary_fg, b.secondary_fg),
+        tertiary_fg: color_op(a.tertiary_fg, b.tertiary_fg),
+        highlight_fg: color_op(a.highlight_fg, b.highlight_fg),
+        bg: color_op(a.bg, b.bg),
+        primary: color_op(a.primary, b.primary),
+        success: color_op(a.success, b.success),
+        warning: color_op(a.warning, b.warning),
+        danger: color_op(a.danger, b.danger),
+        date_fg: color_op(a.date_fg, b.date_fg),
+        time_fg: color_op(a.time_fg, b.time_fg),
+        input_fg: color_op(a.input_fg, b.input_fg),
+        input_bg: color_op(a.input_bg, b.input_bg),
+        input_focus_fg: color_op(a.input_focus_fg, b.input_focus_fg),
+        input_focus_bg: color_op(a.input_focus_bg, b.input_focus_bg),
+        input_cursor_fg: color_op(a.input_cursor_fg, b.input_cursor_fg),
+        input_cursor_bg: color_op(a.input_cursor_bg, b.input_cursor_bg),
+        input_cursor_insert_fg: color_op(a.input_cursor_insert_fg, b.input_cursor_insert_fg),
+        input_cursor_insert_bg: color_op(a.input_cursor_insert_bg, b.input_cursor_insert_bg),
+        active_fg: color_op(a.active_fg, b.active_fg),
+        active_bg: color_op(a.active_bg, b.active_bg),
+        border: color_op(a.border, b.border),
+        border_active: color_op(a.border_active, b.border_active),
+        border_insert: color_op(a.border_insert, b.border_insert),
+        popup_bg: color_op(a.popup_bg, b.popup_bg),
+        popup_border: color_op(a.popup_border, b.popup_border),
+        keybind_key: color_op(a.keybind_key, b.keybind_key),
+        keybind_fg: color_op(a.keybind_fg, b.keybind_fg),
+        title_bar_bg: color_op(a.title_bar_bg, b.title_bar_bg),
+        title_bar_fg: color_op(a.title_bar_fg, b.title_bar_fg),
+        tab_fg: color_op(a.tab_fg, b.tab_fg),
+        tab_active_fg: color_op(a.tab_active_fg, b.tab_active_fg),
+        tab_border: color_op(a.tab_border, b.tab_border),
+        status_bar_bg: color_op(a.status_bar_bg, b.status_bar_bg),
+        status_bar_fg: color_op(a.status_bar_fg, b.status_bar_fg),
+        status_bar_normal_mode_bg: color_op(
+            a.status_bar_normal_mode_bg,
+            b.status_bar_normal_mode_bg,
+        ),
+        status_bar_normal_mode_fg: color_op(
+            a.status_bar_normal_mode_fg,
+            b.status_bar_normal_mode_fg,
+        ),
+        status_bar_insert_mode_bg: color_op(
+            a.status_bar_insert_mode_bg,
+            b.status_bar_insert_mode_bg,
+        ),
+        status_bar_insert_mode_fg: color_op(
+            a.status_bar_insert_mode_fg,
+            b.status_bar_insert_mode_fg,
+        ),
+        status_bar_delete_mode_bg: color_op(
+            a.status_bar_delete_mode_bg,
+            b.status_bar_delete_mode_bg,
+        ),
+        status_bar_delete_mode_fg: color_op(
+            a.status_bar_delete_mode_fg,
+            b.status_bar_delete_mode_fg,
+        ),
+    }
+}
+
+/// Merge the user keybindings with the base keybindings. The keys are
+/// validated when the config is read, so invalid keys only fall back to the
+/// base keys.
+pub(crate) fn merge_keys(
+    user_keys: KeysConfigFile,
+    base_keys: KeysConfig<Keys>,
+) -> KeysConfig<Keys> {
+    let (Ok(toml::Value::Table(mut keys)), Ok(toml::Value::Table(user_keys))) = (
+        toml::Value::try_from(&base_keys),
+        toml::Value::try_from(user_keys),
+    ) else {
+        return base_keys;
+    };
+    for (scope, actions) in user_keys {
+        if let (Some(toml::Value::Table(base_actions)), toml::Value::Table(actions)) =
+            (keys.get_mut(&scope), actions)
+        {
+            base_actions.extend(actions);
+        }
     }
+    toml::Value::Table(keys).try_into().unwrap_or(base_keys)
+}
+
+/// Apply the colors of a config file. The preset is only applied again if it's
+/// set in the file, so the colors of config.toml are kept by a profile config
+/// file.
+fn apply_colors(a: ColorsConfigFile, b: ColorsConfig) -> ColorsConfig {
+    let b = match a.preset.clone().filter(|p| theme_names().contains(p)) {
+        Some(preset) => themes::preset_colors(&preset, b.clone()).unwrap_or(b),
+        None => b,
+    };
+    merge_colors(a, b)
 }
 
 // TODO: Optimisation. There is lots of clones to reduce the level of nesting.
 // Try to not nest too deeply to keep the code easier to read and maintain.
 /// Merge the user config with the base config.
 fn merge_config(user_config: ConfigFile, base_config: Config) -> Config {
-    let colors = user_config.colors.map(|a| {
-        let b = base_config.colors.clone();
-        ColorsConfig {
-            preset: a
-                .preset
-                .filter(|p| COLOR_PRESETS.iter().any(|&cp| cp == p))
-                .unwrap_or(b.preset),
-            fg: color_op(a.fg, b.fg),
-            secondary_fg: color_op(a.secondary_fg, b.secondary_fg),
-            tertiary_fg: color_op(a.tertiary_fg, b.tertiary_fg),
-            highlight_fg: color_op(a.highlight_fg, b.highlight_fg),
-            bg: color_op(a.bg, b.bg),
-            primary: color_op(a.primary, b.primary),
-            success: color_op(a.success, b.success),
-            warning: color_op(a.warning, b.warning),
-            danger: color_op(a.danger, b.danger),
-            date_fg: color_op(a.date_fg, b.date_fg),
-            time_fg: color_op(a.time_fg, b.time_fg),
-            input_fg: color_op(a.input_fg, b.input_fg),
-            input_bg: color_op(a.input_bg, b.input_bg),
-            input_focus_fg: color_op(a.input_focus_fg, b.input_focus_fg),
-            input_focus_bg: color_op(a.input_focus_bg, b.input_focus_bg),
-            input_cursor_fg: color_op(a.input_cursor_fg, b.input_cursor_fg),
-            input_cursor_bg: color_op(a.input_cursor_bg, b.input_cursor_bg),
-            input_cursor_insert_fg: color_op(a.input_cursor_insert_fg, b.input_cursor_insert_fg),
-            input_cursor_insert_bg: color_op(a.input_cursor_insert_bg, b.input_cursor_insert_bg),
-            active_fg: color_op(a.active_fg, b.active_fg),
-            active_bg: color_op(a.active_bg, b.active_bg),
-            border: color_op(a.border, b.border),
-            border_active: color_op(a.border_active, b.border_active),
-            border_insert: color_op(a.border_insert, b.border_insert),
-            popup_bg: color_op(a.popup_bg, b.popup_bg),
-            popup_border: color_op(a.popup_border, b.popup_border),
-            keybind_key: color_op(a.keybind_key, b.keybind_key),
-            keybind_fg: color_op(a.keybind_fg, b.keybind_fg),
-            title_bar_bg: color_op(a.title_bar_bg, b.title_bar_bg),
-            title_bar_fg: color_op(a.title_bar_fg, b.title_bar_fg),
-            tab_fg: color_op(a.tab_fg, b.tab_fg),
-            tab_active_fg: color_op(a.tab_active_fg, b.tab_active_fg),
-            tab_border: color_op(a.tab_border, b.tab_border),
-            status_bar_bg: color_op(a.status_bar_bg, b.status_bar_bg),
-            status_bar_fg: color_op(a.status_bar_fg, b.status_bar_fg),
-            status_bar_normal_mode_bg: color_op(
-                a.status_bar_normal_mode_bg,
-                b.status_bar_normal_mode_bg,
-            ),
-            status_bar_normal_mode_fg: color_op(
-                a.status_bar_normal_mode_fg,
-                b.status_bar_normal_mode_fg,
-            ),
-            status_bar_insert_mode_bg: color_op(
-                a.status_bar_insert_mode_bg,
-                b.status_bar_insert_mode_bg,
-            ),
-            status_bar_insert_mode_fg: color_op(
-                a.status_bar_insert_mode_fg,
-                b.status_bar_insert_mode_fg,
-            ),
-            status_bar_delete_mode_bg: color_op(
-                a.status_bar_delete_mode_bg,
-                b.status_bar_delete_mode_bg,
-            ),
-            status_bar_delete_mode_fg: color_op(
-                a.status_bar_delete_mode_fg,
-                b.status_bar_delete_mode_fg,
-            ),
+    let colors = user_config
+        .colors
+        .map(|a| apply_colors(a, base_config.colors.clone()));
+
+    let datetime = user_config.datetime.map(|a| {
+        let b = base_config.datetime.clone();
+        DateTimeConfig {
+            date_format: a.date_format.unwrap_or(b.date_format),
+            clock: a.clock.unwrap_or(b.clock),
+            first_day_of_week: a.first_day_of_week.unwrap_or(b.first_day_of_week),
+            relative_dates: a.relative_dates.unwrap_or(b.relative_dates),
+            timezone: a.timezone.unwrap_or(b.timezone),
         }
     });
 
@@ -155,6 +268,7 @@ fn merge_config(user_config: ConfigFile, base_config: Config) -> Config {
             ProjectManagementModule {
                 max_lists: a.max_lists.unwrap_or(b.max_lists),
                 due_soon_days: a.due_soon_days.unwrap_or(b.due_soon_days),
+                archive_purge_days: a.archive_purge_days.unwrap_or(b.archive_purge_days),
                 completed_char: a.completed_char.unwrap_or(b.completed_char),
                 overdue_char: a.overdue_char.unwrap_or(b.overdue_char),
                 due_soon_char: a.due_soon_char.unwrap_or(b.due_soon_char),
@@ -170,77 +284,122 @@ fn merge_config(user_config: ConfigFile, base_config: Config) -> Config {
         }
     });
 
+    // The profiles without a name have already been left out. A profile with
+    // the name of a base profile overrides its values, and the values that
+    // aren't set fall back to the base profile, or to files named after the
+    // profile.
     let profiles = user_config.profiles.map(|a| {
-        a.iter()
-            .map(|profile| ProfileConfig {
-                name: profile
-                    .name
-                    .as_ref()
-                    .expect("profile name not provided")
-                    .clone(),
-                config_file: profile
-                    .config_file
-                    .as_ref()
-                    .expect("profile config file not provided")
-                    .clone(),
-                db_file: profile
-                    .db_file
-                    .as_ref()
-                    .expect("profile db file not provided")
-                    .clone(),
-                log_file: profile
-                    .log_file
-                    .as_ref()
-                    .expect("profile log file not provided")
-                    .clone(),
-            })
-            .collect()
+        let mut profiles = base_config.profiles.clone();
+        for profile in a {
+            let Some(name) = profile.name else {
+                continue;
+            };
+            let base = profiles.iter().position(|p| p.name == name);
+            let base_profile = base.map(|i| profiles[i].clone());
+            let file = |value: Option<String>, base_value: Option<&String>, extension| {
+                value
+                    .or(base_value.cloned())
+                    .unwrap_or(format!("{}.{}", name, extension))
+            };
+            let b = base_profile.as_ref();
+            let merged = ProfileConfig {
+                config_file: file(profile.config_file, b.map(|p| &p.config_file), "toml"),
+                db_file: file(profile.db_file, b.map(|p| &p.db_file), "db"),
+                log_file: file(profile.log_file, b.map(|p| &p.log_file), "log"),
+                encrypted: profile
+                    .encrypted
+                    .or(b.map(|p| p.encrypted))
+                    .unwrap_or(false),
+                backups: profile
+                    .backups
+                    .or(b.map(|p| p.backups))
+                    .unwrap_or(DEFAULT_BACKUPS),
+                idle_timeout: profile
+                    .idle_timeout
+                    .or(b.map(|p| p.idle_timeout))
+                    .unwrap_or(DEFAULT_IDLE_TIMEOUT),
+                sync_server: profile
+                    .sync_server
+                    .or(b.map(|p| p.sync_server.clone()))
+                    .unwrap_or_default(),
+                sync_interval: profile
+                    .sync_interval
+                    .or(b.map(|p| p.sync_interval))
+                    .unwrap_or(DEFAULT_SYNC_INTERVAL),
+                name,
+            };
+            match base {
+                Some(i) => profiles[i] = merged,
+                None => profiles.push(merged),
+            }
+        }
+        profiles
     });
 
     Config {
         log_level: user_config.log_level.unwrap_or(base_config.log_level),
+        color_mode: user_config.color_mode.unwrap_or(base_config.color_mode),
         default_profile: user_config
             .default_profile
             .unwrap_or(base_config.default_profile),
         colors: colors.unwrap_or(base_config.colors),
+        datetime: datetime.unwrap_or(base_config.datetime),
         modules: modules.unwrap_or(base_config.modules),
+        keys: match user_config.keys {
+            Some(keys) => merge_keys(keys, base_config.keys),
+            None => base_config.keys,
+        },
         profiles: profiles.unwrap_or(base_config.profiles),
     }
 }
 
-/// Read, parse, and marge the configuration.
-pub fn init_config(profile: Option<String>) -> Result<(Config, ProfileConfig)> {
-    let mut base_config = base_config();
-    let default_profile_name = base_config.default_profile;
-    let default_profile = base_config
+/// Read, parse, and merge the configuration. The problems found in the
+/// config files are returned with it, and the values with problems fall back
+/// to their defaults.
+///
+/// The profiles are defined in `config.toml`, which is read for every
+/// profile. The profile is the one that is passed, or else `default_profile`.
+/// If the profile has its own config file, it's merged on top of
+/// `config.toml`. The `PLTX_*` environment variables are merged last.
+pub fn init_config(profile: Option<String>) -> Result<(Config, ProfileConfig, Vec<ConfigError>)> {
+    let mut errors = vec![];
+    let main_config = read_config_file(MAIN_CONFIG_FILE, &mut errors);
+
+    let mut config: Config = base_config().into();
+    if let Some(main_config) = main_config {
+        config = merge_config(main_config, config);
+    }
+
+    let profile_name = profile.unwrap_or(config.default_profile.clone());
+    let profile = config
         .profiles
-        .as_ref()
         .iter()
-        .find(|p| p.name == default_profile_name)
-        .expect("failed to get default profile")
-        .clone();
-
-    if profile.as_ref().is_some_and(|p| p.as_str() == "dev") {
-        base_config.log_level = "debug";
-        base_config.modules.home.dashboard_title = "DEVELOPER PROFILE ENABLED";
-        base_config.modules.home.dashboard_message = "All data is separate from the main profile.";
+        .find(|p| p.name == profile_name)
+        .cloned()
+        .ok_or_else(|| {
+            eyre!(
+                "no profile \"{}\", run `pltx profile list` to see the profiles",
+                profile_name
+            )
+        })?;
+
+    if profile.name == "dev" {
+        config.log_level = "debug".to_string();
+        config.modules.home.dashboard_title = "DEVELOPER PROFILE ENABLED".to_string();
+        config.modules.home.dashboard_message =
+            "All data is separate from the main profile.".to_string();
     }
 
-    if let Some(profile_name) = profile {
-        let profile = base_config
-            .profiles
-            .iter()
-            .find(|p| p.name == profile_name)
-            .unwrap_or_else(|| panic!("no profile \"{}\" in config.toml", profile_name))
-            .to_owned();
-
-        let profile_config_file = read_config_file(profile.config_file);
-        let profile_config = match profile_config_file? {
-            Some(user_config) => merge_config(user_config, base_config.into()),
-            None => base_config.into(),
-        };
-        Ok((profile_config, profile.into()))
-    } else {
-        Ok((base_config.into(), (default_profile).into()))
+    if profile.config_file != MAIN_CONFIG_FILE {
+        // The profiles are left out of the profile config file when it's read.
+        if let Some(profile_config) = read_config_file(&profile.config_file, &mut errors) {
+            config = merge_config(profile_config, config);
+        }
     }
+
+    if let Some(env_config) = env::read_env_config(&mut errors) {
+        config = merge_config(env_config, config);
+    }
+
+    Ok((config, profile, errors))
 }
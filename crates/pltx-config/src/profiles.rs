@@ -0,0 +1,349 @@
+//! Create, delete, rename, and copy profiles. The profiles are defined in
+//! `config.toml`, which is edited in place so its comments and formatting are
+//! kept. The config, database, and log files of a profile are changed with
+//! it.
+
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::eyre, Result};
+use pltx_utils::dirs;
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+use crate::{base_config, edit::read_config_document, Config, ProfileConfig, MAIN_CONFIG_FILE};
+
+/// The files of a profile that are on disk.
+pub struct ProfileFiles {
+    pub config_file: Option<PathBuf>,
+    pub db_file: Option<PathBuf>,
+    pub log_file: Option<PathBuf>,
+}
+
+impl ProfileFiles {
+    /// Find the files of a profile.
+    pub fn of(profile: &ProfileConfig) -> ProfileFiles {
+        let existing = |path: PathBuf| path.is_file().then_some(path);
+        ProfileFiles {
+            config_file: existing(dirs::config_dir().join(&profile.config_file)),
+            db_file: existing(dirs::data_dir().join(&profile.db_file)),
+            log_file: existing(dirs::cache_dir().join(&profile.log_file)),
+        }
+    }
+}
+
+/// Whether a profile is included by default, and can't be deleted or renamed.
+fn is_built_in_profile(name: &str) -> bool {
+    base_config().profiles.iter().any(|p| p.name == name)
+}
+
+/// Create a profile with files named after it and the default values of a
+/// profile. Its config file is created with a comment, and its database is
+/// created when it's first opened.
+pub fn create_profile(config: &Config, name: &str) -> Result<ProfileConfig> {
+    check_new_name(config, name)?;
+    let profile = ProfileConfig {
+        name: name.to_string(),
+        config_file: format!("{}.toml", name),
+        db_file: format!("{}.db", name),
+        log_file: format!("{}.log", name),
+        ..ProfileConfig::from(base_config().profiles[0].clone())
+    };
+    check_files_unused(&profile)?;
+
+    let mut doc = read_main_config()?;
+    profiles_mut(&mut doc)?.push(profile_table(&profile));
+    write_main_config(&doc)?;
+    write_profile_config_file(&profile)?;
+
+    Ok(profile)
+}
+
+/// Delete a profile and its config, database, and log files. The backups of
+/// the database are kept. The files that another profile uses are kept too.
+pub fn delete_profile(config: &Config, name: &str) -> Result<ProfileConfig> {
+    let profile = find_profile(config, name)?;
+    if is_built_in_profile(name) {
+        return Err(eyre!(
+            "the \"{}\" profile is built in and cannot be deleted",
+            name
+        ));
+    }
+    if config.default_profile == name {
+        return Err(eyre!(
+            "the \"{}\" profile is the default profile, set another default_profile in {} first",
+            name,
+            MAIN_CONFIG_FILE
+        ));
+    }
+
+    let mut doc = read_main_config()?;
+    profiles_mut(&mut doc)?.retain(|table| table_name(table) != Some(name));
+    write_main_config(&doc)?;
+
+    let shared = |file: fn(&ProfileConfig) -> &String| {
+        config
+            .profiles
+            .iter()
+            .any(|p| p.name != name && file(p) == file(&profile))
+    };
+    let files = ProfileFiles::of(&profile);
+    if !shared(|p| &p.config_file) && profile.config_file != MAIN_CONFIG_FILE {
+        remove_file(files.config_file)?;
+    }
+    if !shared(|p| &p.db_file) {
+        // The rollback journal only exists if a transaction was interrupted.
+        let journal = files.db_file.as_ref().map(|path| {
+            let mut journal = path.clone().into_os_string();
+            journal.push("-journal");
+            PathBuf::from(journal)
+        });
+        remove_file(files.db_file)?;
+        remove_file(journal)?;
+    }
+    if !shared(|p| &p.log_file) {
+        remove_file(files.log_file)?;
+    }
+
+    Ok(profile)
+}
+
+/// Rename a profile. The files that are named after the profile are renamed
+/// too, along with the backup directory of its database.
+pub fn rename_profile(config: &Config, name: &str, new_name: &str) -> Result<ProfileConfig> {
+    let profile = find_profile(config, name)?;
+    if is_built_in_profile(name) {
+        return Err(eyre!(
+            "the \"{}\" profile is built in and cannot be renamed, copy it instead",
+            name
+        ));
+    }
+    check_new_name(config, new_name)?;
+
+    let renamed_file = |file: &str, extension: &str| {
+        if file == format!("{}.{}", name, extension) {
+            format!("{}.{}", new_name, extension)
+        } else {
+            file.to_string()
+        }
+    };
+    let renamed = ProfileConfig {
+        name: new_name.to_string(),
+        config_file: renamed_file(&profile.config_file, "toml"),
+        db_file: renamed_file(&profile.db_file, "db"),
+        log_file: renamed_file(&profile.log_file, "log"),
+        ..profile.clone()
+    };
+    check_files_unused(&ProfileConfig {
+        config_file: changed_or_empty(&renamed.config_file, &profile.config_file),
+        db_file: changed_or_empty(&renamed.db_file, &profile.db_file),
+        log_file: changed_or_empty(&renamed.log_file, &profile.log_file),
+        ..renamed.clone()
+    })?;
+
+    let mut doc = read_main_config()?;
+    for table in profiles_mut(&mut doc)?.iter_mut() {
+        if table_name(table) == Some(name) {
+            table["name"] = value(new_name);
+            table["config_file"] = value(&renamed.config_file);
+            table["db_file"] = value(&renamed.db_file);
+            table["log_file"] = value(&renamed.log_file);
+        }
+    }
+    if config.default_profile == name {
+        doc["default_profile"] = value(new_name);
+    }
+    write_main_config(&doc)?;
+
+    let files = ProfileFiles::of(&profile);
+    rename_file(
+        files.config_file,
+        &dirs::config_dir().join(&renamed.config_file),
+    )?;
+    rename_file(files.log_file, &dirs::cache_dir().join(&renamed.log_file))?;
+    let old_backup_dir = backup_dir(&profile.db_file);
+    if old_backup_dir.is_dir() {
+        rename_file(Some(old_backup_dir), &backup_dir(&renamed.db_file))?;
+    }
+    rename_file(files.db_file, &dirs::data_dir().join(&renamed.db_file))?;
+
+    Ok(renamed)
+}
+
+/// Copy a profile and its config file to a new profile with files named after
+/// it. The sync server isn't copied, so the copy doesn't sync with the devices
+/// of the profile. The database isn't copied here, since it may be in use and
+/// should be copied with `Database::backup_to` of `pltx_database`.
+pub fn copy_profile(config: &Config, name: &str, new_name: &str) -> Result<ProfileConfig> {
+    let profile = find_profile(config, name)?;
+    check_new_name(config, new_name)?;
+    let copy = ProfileConfig {
+        name: new_name.to_string(),
+        config_file: format!("{}.toml", new_name),
+        db_file: format!("{}.db", new_name),
+        log_file: format!("{}.log", new_name),
+        sync_server: String::new(),
+        ..profile.clone()
+    };
+    check_files_unused(&copy)?;
+
+    let mut doc = read_main_config()?;
+    profiles_mut(&mut doc)?.push(profile_table(&copy));
+    write_main_config(&doc)?;
+
+    // config.toml defines the profiles, so a copy of it would define them
+    // again.
+    let files = ProfileFiles::of(&profile);
+    match files.config_file {
+        Some(path) if profile.config_file != MAIN_CONFIG_FILE => {
+            fs::copy(path, dirs::config_dir().join(&copy.config_file))?;
+        }
+        _ => write_profile_config_file(&copy)?,
+    }
+
+    Ok(copy)
+}
+
+/// Find a profile by its name.
+fn find_profile(config: &Config, name: &str) -> Result<ProfileConfig> {
+    config
+        .profiles
+        .iter()
+        .find(|p| p.name == name)
+        .cloned()
+        .ok_or_else(|| eyre!("no profile \"{}\"", name))
+}
+
+/// Check that a new profile name is valid and isn't used yet. The name is
+/// used in file names, so only letters, numbers, `-`, and `_` are allowed.
+fn check_new_name(config: &Config, name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(eyre!(
+            "invalid profile name \"{}\", only letters, numbers, - and _ are allowed",
+            name
+        ));
+    }
+    if config.profiles.iter().any(|p| p.name == name) {
+        return Err(eyre!("the \"{}\" profile already exists", name));
+    }
+    Ok(())
+}
+
+/// Check that the files of a new profile don't exist yet, so the files of
+/// another profile or old files aren't used by accident. Empty file names are
+/// skipped.
+fn check_files_unused(profile: &ProfileConfig) -> Result<()> {
+    for path in [
+        (!profile.config_file.is_empty()).then(|| dirs::config_dir().join(&profile.config_file)),
+        (!profile.db_file.is_empty()).then(|| dirs::data_dir().join(&profile.db_file)),
+        (!profile.log_file.is_empty()).then(|| dirs::cache_dir().join(&profile.log_file)),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if path.exists() {
+            return Err(eyre!(
+                "{} already exists, move or delete it first",
+                path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The new file name if it has changed, otherwise an empty string.
+fn changed_or_empty(new: &str, old: &str) -> String {
+    if new == old {
+        String::new()
+    } else {
+        new.to_string()
+    }
+}
+
+/// The directory of the backups of a database, which is named after the
+/// database file.
+fn backup_dir(db_file: &str) -> PathBuf {
+    let stem = Path::new(db_file)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| db_file.to_string());
+    dirs::data_dir().join("backups").join(stem)
+}
+
+/// Read config.toml to be edited. It's empty if it doesn't exist.
+fn read_main_config() -> Result<DocumentMut> {
+    read_config_document(MAIN_CONFIG_FILE)
+}
+
+fn write_main_config(doc: &DocumentMut) -> Result<()> {
+    fs::write(dirs::config_dir().join(MAIN_CONFIG_FILE), doc.to_string())?;
+    Ok(())
+}
+
+/// The `[[profiles]]` of config.toml, which are added if there are none.
+fn profiles_mut(doc: &mut DocumentMut) -> Result<&mut ArrayOfTables> {
+    doc.entry("profiles")
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or_else(|| eyre!("profiles in {} is not a list of tables", MAIN_CONFIG_FILE))
+}
+
+fn table_name(table: &Table) -> Option<&str> {
+    table.get("name").and_then(|name| name.as_str())
+}
+
+/// The `[[profiles]]` entry of a profile. The sync server is only written if
+/// it's set.
+fn profile_table(profile: &ProfileConfig) -> Table {
+    let mut table = Table::new();
+    table["name"] = value(&profile.name);
+    table["config_file"] = value(&profile.config_file);
+    table["db_file"] = value(&profile.db_file);
+    table["log_file"] = value(&profile.log_file);
+    table["encrypted"] = value(profile.encrypted);
+    table["backups"] = value(profile.backups as i64);
+    table["idle_timeout"] = value(profile.idle_timeout as i64);
+    if !profile.sync_server.is_empty() {
+        table["sync_server"] = value(&profile.sync_server);
+    }
+    table["sync_interval"] = value(profile.sync_interval as i64);
+    table
+}
+
+/// Create the config file of a new profile, which only overrides config.toml.
+fn write_profile_config_file(profile: &ProfileConfig) -> Result<()> {
+    fs::write(
+        dirs::config_dir().join(&profile.config_file),
+        format!(
+            "# The config of the \"{}\" profile. It is merged on top of {}, so only the values \
+             that differ need to be set.\n",
+            profile.name, MAIN_CONFIG_FILE
+        ),
+    )?;
+    Ok(())
+}
+
+fn remove_file(path: Option<PathBuf>) -> Result<()> {
+    match path {
+        Some(path) => match fs::remove_file(path) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        },
+        None => Ok(()),
+    }
+}
+
+fn rename_file(path: Option<PathBuf>, new_path: &Path) -> Result<()> {
+    if let Some(path) = path {
+        if path != new_path {
+            fs::rename(path, new_path)?;
+        }
+    }
+    Ok(())
+}
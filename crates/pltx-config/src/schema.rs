@@ -0,0 +1,122 @@
+//! A JSON schema of the config for editors that complete and check TOML files,
+//! e.g., with `#:schema ./config.schema.json` at the top of a config file. It's
+//! generated from the default config, so the descriptions are its comments.
+
+use pltx_utils::{CLOCKS, WEEKDAYS};
+use serde_json::{json, Map, Value};
+use toml_edit::{Decor, DocumentMut, Item, Table};
+
+use crate::{COLOR_MODES, DEFAULT_CONFIG, LOG_LEVELS};
+
+/// The comments right before a key or table, joined into one description.
+fn description(decor: &Decor) -> Option<String> {
+    let mut comments = vec![];
+    for line in decor
+        .prefix()
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or_default()
+        .lines()
+    {
+        match line.trim().strip_prefix('#') {
+            Some(comment) => comments.push(comment.trim()),
+            // A blank line ends the comments of something else.
+            None => comments.clear(),
+        }
+    }
+    (!comments.is_empty()).then(|| comments.join(" "))
+}
+
+fn value_schema(path: &[&str], value: &toml_edit::Value, defaults: bool) -> Value {
+    let mut schema = match value {
+        toml_edit::Value::String(s) => {
+            let mut schema = json!({ "type": "string" });
+            if defaults {
+                schema["default"] = json!(s.value());
+            }
+            schema
+        }
+        toml_edit::Value::Integer(i) => {
+            let mut schema = json!({ "type": "integer" });
+            if defaults {
+                schema["default"] = json!(i.value());
+            }
+            schema
+        }
+        toml_edit::Value::Boolean(b) => {
+            let mut schema = json!({ "type": "boolean" });
+            if defaults {
+                schema["default"] = json!(b.value());
+            }
+            schema
+        }
+        _ => json!({}),
+    };
+    match path {
+        ["log_level"] => schema["enum"] = json!(LOG_LEVELS),
+        ["color_mode"] => schema["enum"] = json!(COLOR_MODES),
+        ["datetime", "clock"] => schema["enum"] = json!(CLOCKS),
+        ["datetime", "first_day_of_week"] => schema["enum"] = json!(WEEKDAYS),
+        ["colors", name] if *name != "preset" => {
+            schema["description"] = json!("A hex color like \"#ff0000\" or a color name.")
+        }
+        ["keys", ..] => {
+            schema["description"] = json!(
+                "Keys separated by spaces, e.g., \"l right\", with optional ctrl+ and alt+ \
+                 modifiers."
+            )
+        }
+        _ => {}
+    }
+    schema
+}
+
+fn table_schema(path: &mut Vec<String>, table: &Table, defaults: bool) -> Value {
+    let mut properties = Map::new();
+    for (key, item) in table.iter() {
+        path.push(key.to_string());
+        let (mut schema, decor) = match item {
+            Item::Value(value) => {
+                let segments = path.iter().map(String::as_str).collect::<Vec<&str>>();
+                let decor = table.key(key).map(|k| k.leaf_decor().clone());
+                (value_schema(&segments, value, defaults), decor)
+            }
+            Item::Table(table) => (
+                table_schema(path, table, defaults),
+                Some(table.decor().clone()),
+            ),
+            Item::ArrayOfTables(tables) => match tables.get(0) {
+                Some(first) => {
+                    let mut items = table_schema(path, first, false);
+                    items["required"] = json!(["name"]);
+                    (
+                        json!({ "type": "array", "items": items }),
+                        Some(first.decor().clone()),
+                    )
+                }
+                None => (json!({ "type": "array" }), None),
+            },
+            Item::None => (json!({}), None),
+        };
+        if let Some(description) = decor.as_ref().and_then(description) {
+            schema["description"] = json!(description);
+        }
+        properties.insert(key.to_string(), schema);
+        path.pop();
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    })
+}
+
+/// Generate the JSON schema of the config.
+pub fn config_schema() -> String {
+    let doc = DEFAULT_CONFIG
+        .parse::<DocumentMut>()
+        .expect("failed to parse the default config");
+    let mut schema = table_schema(&mut vec![], doc.as_table(), true);
+    schema["$schema"] = json!("http://json-schema.org/draft-07/schema#");
+    schema["title"] = json!("pltx config");
+    serde_json::to_string_pretty(&schema).expect("failed to serialize the config schema")
+}
@@ -0,0 +1,176 @@
+use std::{fs, str::FromStr};
+
+use color_eyre::{eyre::eyre, Result};
+use pltx_utils::dirs;
+use ratatui::style::Color;
+
+use crate::{
+    apply_colors, base_config, merge_colors, read_config_file, ColorsConfig, ColorsConfigFile,
+    Config, ProfileConfig, MAIN_CONFIG_FILE,
+};
+
+/// The built-in color presets. The default preset is the colors of the base
+/// config, so it doesn't change any colors.
+const COLOR_PRESETS: [(&str, &str); 5] = [
+    ("default", ""),
+    ("light", include_str!("../themes/light.toml")),
+    ("gruvbox", include_str!("../themes/gruvbox.toml")),
+    ("nord", include_str!("../themes/nord.toml")),
+    ("solarized", include_str!("../themes/solarized.toml")),
+];
+
+/// The names of the built-in presets, followed by the theme files in the
+/// `themes` directory of the config directory.
+pub fn theme_names() -> Vec<String> {
+    let mut names = COLOR_PRESETS
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .collect::<Vec<String>>();
+
+    let mut files = fs::read_dir(dirs::config_dir().join("themes"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.extension().is_some_and(|e| e == "toml"))
+                .filter_map(|path| path.file_stem().map(|s| s.to_string_lossy().to_string()))
+                .filter(|name| !names.contains(name))
+                .collect::<Vec<String>>()
+        })
+        .unwrap_or_default();
+    files.sort();
+    names.extend(files);
+
+    names
+}
+
+/// Read the colors of a theme. A theme file with the same name as a built-in
+/// preset replaces the preset.
+pub(crate) fn read_theme(name: &str) -> Result<ColorsConfigFile> {
+    let path = dirs::config_dir()
+        .join("themes")
+        .join(format!("{}.toml", name));
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => COLOR_PRESETS
+            .iter()
+            .find(|(preset, _)| *preset == name)
+            .map(|(_, contents)| contents.to_string())
+            .ok_or_else(|| eyre!("no theme named \"{}\"", name))?,
+    };
+    let table = toml::from_str::<toml::Table>(&contents)
+        .map_err(|e| eyre!("the \"{}\" theme is invalid: {}", name, e.message()))?;
+    for (key, value) in &table {
+        if let Some(color) = value.as_str().filter(|_| key != "preset") {
+            if Color::from_str(color).is_err() {
+                return Err(eyre!(
+                    "the \"{}\" theme has an invalid color for {}: {}",
+                    name,
+                    key,
+                    color
+                ));
+            }
+        }
+    }
+    table
+        .try_into()
+        .map_err(|e: toml::de::Error| eyre!("the \"{}\" theme is invalid: {}", name, e.message()))
+}
+
+/// Apply a preset to the base colors. The user colors are merged on top of
+/// them.
+pub(crate) fn preset_colors(name: &str, base_colors: ColorsConfig) -> Result<ColorsConfig> {
+    let theme = read_theme(name)?;
+    Ok(ColorsConfig {
+        preset: name.to_string(),
+        ..merge_colors(theme, base_colors)
+    })
+}
+
+/// The colors that are set in config.toml and the config file of a profile.
+/// They are read once when a preview starts, so the files aren't read again
+/// on every key press.
+#[derive(Clone, Default)]
+pub struct UserColors {
+    /// The config file of the profile.
+    profile_file: String,
+    /// The colors of each config file, in the order they are merged.
+    files: Vec<(String, Option<ColorsConfigFile>)>,
+}
+
+impl UserColors {
+    /// Read the colors of config.toml and the config file of the profile.
+    pub fn read(profile: &ProfileConfig) -> UserColors {
+        let mut files = vec![MAIN_CONFIG_FILE];
+        if profile.config_file != MAIN_CONFIG_FILE {
+            files.push(&profile.config_file);
+        }
+        UserColors {
+            profile_file: profile.config_file.clone(),
+            files: files
+                .into_iter()
+                .map(|file| {
+                    let colors = read_config_file(file, &mut vec![]).and_then(|c| c.colors);
+                    (file.to_string(), colors)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Get the colors of a profile with another preset, e.g., to preview a theme.
+/// The colors that are set in config.toml and the config file of the profile
+/// still override the preset.
+pub fn theme_colors(user_colors: &UserColors, name: &str) -> Result<ColorsConfig> {
+    let colors = preset_colors(name, Config::from(base_config()).colors)?;
+    Ok(user_colors
+        .files
+        .iter()
+        .filter_map(|(_, colors)| colors.clone())
+        .fold(colors, |colors, user_colors| {
+            merge_colors(user_colors, colors)
+        }))
+}
+
+/// The names of the colors, without the preset, in the order of the config.
+pub fn color_names() -> Vec<String> {
+    // A TOML table is sorted, so the order is taken from the serialized text.
+    toml::to_string(&base_config().colors)
+        .ok()
+        .and_then(|text| text.parse::<toml_edit::DocumentMut>().ok())
+        .map(|doc| {
+            doc.iter()
+                .map(|(name, _)| name.to_string())
+                .filter(|name| name != "preset")
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Get the colors of a profile with the colors of its config file replaced,
+/// e.g., to preview them before they are saved. `colors` are the names and
+/// values of the colors that are set, including the preset. Invalid values are
+/// left out.
+pub fn preview_colors(user_colors: &UserColors, colors: &[(String, String)]) -> ColorsConfig {
+    let edited = toml::Value::Table(
+        colors
+            .iter()
+            .map(|(name, value)| (name.clone(), toml::Value::String(value.clone())))
+            .collect(),
+    )
+    .try_into::<ColorsConfigFile>()
+    .ok();
+
+    user_colors
+        .files
+        .iter()
+        .filter_map(|(file, colors)| {
+            if *file == user_colors.profile_file {
+                edited.clone()
+            } else {
+                colors.clone()
+            }
+        })
+        .fold(Config::from(base_config()).colors, |colors, user_colors| {
+            apply_colors(user_colors, colors)
+        })
+}
@@ -0,0 +1,70 @@
+//! Detects changes to the config files of a profile while pltx is running, so
+//! the config can be reloaded without a restart. The modification time of the
+//! file is cheap enough to check on every tick.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use color_eyre::Result;
+use pltx_utils::dirs;
+use tracing::info;
+
+use crate::{init_config, Config, ConfigError, ProfileConfig, MAIN_CONFIG_FILE};
+
+/// Watches config.toml and the config file of the profile that pltx was
+/// started with.
+pub struct ConfigWatcher {
+    /// The profile that is passed to [`init_config`] when the config is
+    /// reloaded, so a change to `default_profile` doesn't switch profiles.
+    profile: String,
+    /// The files and when they were last modified.
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl ConfigWatcher {
+    /// Start watching the config files of a profile.
+    pub fn new(profile_config: &ProfileConfig) -> ConfigWatcher {
+        let mut filenames = vec![MAIN_CONFIG_FILE];
+        if profile_config.config_file != MAIN_CONFIG_FILE {
+            filenames.push(&profile_config.config_file);
+        }
+        ConfigWatcher {
+            profile: profile_config.name.clone(),
+            files: filenames
+                .into_iter()
+                .map(|filename| {
+                    let path = dirs::config_dir().join(filename);
+                    let modified = Self::modified(&path);
+                    (path, modified)
+                })
+                .collect(),
+        }
+    }
+
+    /// When the file was last modified, or `None` if it doesn't exist.
+    fn modified(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    /// Read and merge the config again if a file has changed since the last
+    /// call. Returns `None` if none have changed.
+    pub fn poll(&mut self) -> Option<Result<(Config, Vec<ConfigError>)>> {
+        let mut changed = false;
+        for (path, last_modified) in &mut self.files {
+            let modified = Self::modified(path);
+            if modified != *last_modified {
+                info!("the config file has changed: {}", path.display());
+                *last_modified = modified;
+                changed = true;
+            }
+        }
+        if !changed {
+            return None;
+        }
+
+        Some(init_config(Some(self.profile.clone())).map(|(config, _, errors)| (config, errors)))
+    }
+}
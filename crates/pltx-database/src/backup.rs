@@ -0,0 +1,214 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use color_eyre::{eyre::eyre, Result};
+use pltx_utils::{dirs, DateTime};
+use rusqlite::{backup, Connection, OpenFlags};
+use tracing::info;
+
+use crate::Database;
+
+/// The suffix of the backups that are taken when a session is started. Only
+/// these backups are rotated.
+const AUTOMATIC_SUFFIX: &str = "-auto";
+
+/// A snapshot of the database in the backup directory.
+pub struct BackupFile {
+    pub path: PathBuf,
+    /// Whether the backup was taken when a session was started.
+    pub automatic: bool,
+    /// The size of the backup file in bytes.
+    pub size: u64,
+}
+
+impl BackupFile {
+    /// The file name of the backup, which starts with the time it was taken.
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl Database {
+    /// The directory that the backups of the database are stored in.
+    pub fn backup_dir(&self) -> PathBuf {
+        let stem = Path::new(&self.filename)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.filename.clone());
+        dirs::data_dir().join("backups").join(stem)
+    }
+
+    /// Set the number of automatic backups to keep. No automatic backups are
+    /// taken if it's 0.
+    pub fn backup_retention(mut self, retention: usize) -> Self {
+        self.backup_retention = retention;
+        self
+    }
+
+    /// Take a backup of the database in the backup directory. Returns the path
+    /// of the backup.
+    pub fn backup(&self) -> Result<PathBuf> {
+        let path = self.new_backup_path("")?;
+        self.backup_to(&path)?;
+        Ok(path)
+    }
+
+    /// Take an automatic backup and delete the oldest automatic backups that
+    /// exceed the retention.
+    pub(crate) fn backup_automatic(&self) -> Result<()> {
+        if self.backup_retention == 0 {
+            return Ok(());
+        }
+
+        let path = self.new_backup_path(AUTOMATIC_SUFFIX)?;
+        self.backup_to(&path)?;
+
+        let automatic_backups = self
+            .backups()?
+            .into_iter()
+            .filter(|b| b.automatic)
+            .collect::<Vec<BackupFile>>();
+        let excess = automatic_backups
+            .len()
+            .saturating_sub(self.backup_retention);
+        for backup in automatic_backups.iter().take(excess) {
+            fs::remove_file(&backup.path)?;
+            info!("removed the backup {}", backup.path.display());
+        }
+
+        Ok(())
+    }
+
+    /// Copy the database to a file with the online backup API, so the database
+    /// can be backed up while it's in use. The backup is encrypted with the
+    /// same passphrase as the database.
+    pub fn backup_to(&self, path: &Path) -> Result<()> {
+        let start = Instant::now();
+
+        if path == self.path() {
+            return Err(eyre!("cannot back up {} to itself", self.filename));
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if self.is_locked() {
+            // The pages cannot be read without the passphrase, so the encrypted
+            // file is copied as it is.
+            fs::copy(self.path(), path)?;
+        } else {
+            let mut dest = Connection::open(path)?;
+            if let Some(passphrase) = &self.passphrase {
+                dest.pragma_update(None, "key", passphrase)?;
+            }
+            backup::Backup::new(&self.conn(), &mut dest)?.run_to_completion(
+                100,
+                Duration::from_millis(10),
+                None,
+            )?;
+        }
+
+        info!(
+            "backed up {} to {} in {:?}",
+            self.filename,
+            path.display(),
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// The backups in the backup directory, from oldest to newest.
+    pub fn backups(&self) -> Result<Vec<BackupFile>> {
+        let backup_dir = self.backup_dir();
+        if !backup_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut backups = vec![];
+        for entry in fs::read_dir(backup_dir)? {
+            let path = entry?.path();
+            if !path.is_file() || path.extension().is_some_and(|e| e != "db") {
+                continue;
+            }
+            let automatic = path
+                .file_stem()
+                .is_some_and(|s| s.to_string_lossy().ends_with(AUTOMATIC_SUFFIX));
+            let size = fs::metadata(&path)?.len();
+            backups.push(BackupFile {
+                path,
+                automatic,
+                size,
+            });
+        }
+        // The file names start with the time that the backup was taken.
+        backups.sort_by_key(|b| b.name());
+
+        Ok(backups)
+    }
+
+    /// Find a backup by its file name in the backup directory, or by its path.
+    pub fn find_backup(&self, snapshot: &str) -> Result<PathBuf> {
+        let in_backup_dir = self.backup_dir().join(snapshot);
+        if in_backup_dir.is_file() {
+            Ok(in_backup_dir)
+        } else if Path::new(snapshot).is_file() {
+            Ok(PathBuf::from(snapshot))
+        } else {
+            Err(eyre!("no backup named {}", snapshot))
+        }
+    }
+
+    /// Replace the data in the database with a backup. The backup must be
+    /// encrypted with the same passphrase as the database.
+    pub fn restore(&self, path: &Path) -> Result<()> {
+        let start = Instant::now();
+
+        if self.session.is_some() {
+            return Err(eyre!(
+                "{} cannot be restored while a session is running",
+                self.filename
+            ));
+        }
+
+        let source = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        if let Some(passphrase) = &self.passphrase {
+            source.pragma_update(None, "key", passphrase)?;
+        }
+        source
+            .query_row("SELECT count(*) FROM sqlite_master", (), |_| Ok(()))
+            .map_err(|_| {
+                eyre!(
+                    "failed to read {}, it may be encrypted with a different passphrase",
+                    path.display()
+                )
+            })?;
+
+        let mut conn = self.conn();
+        backup::Backup::new(&source, &mut conn)?.run_to_completion(
+            100,
+            Duration::from_millis(10),
+            None,
+        )?;
+
+        info!(
+            "restored {} from {} in {:?}",
+            self.filename,
+            path.display(),
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    fn new_backup_path(&self, suffix: &str) -> Result<PathBuf> {
+        let timestamp = DateTime::new().datetime.format("%Y%m%dT%H%M%S%.3fZ");
+        let backup_dir = self.backup_dir();
+        fs::create_dir_all(&backup_dir)?;
+        Ok(backup_dir.join(format!("{}{}.db", timestamp, suffix)))
+    }
+}
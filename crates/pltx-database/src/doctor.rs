@@ -0,0 +1,281 @@
+use std::{fmt, fs, time::Instant};
+
+use color_eyre::Result;
+use pltx_utils::DateTime;
+use rusqlite::Connection;
+use tracing::info;
+
+use crate::{Database, OrderedTable};
+
+/// A row that references a row in another table that doesn't exist.
+pub struct Orphan {
+    pub table: String,
+    pub rowid: i64,
+    pub parent: String,
+}
+
+/// A group of rows where the positions are not numbered from 0 without gaps
+/// or duplicates.
+pub struct PositionGap {
+    pub table: OrderedTable,
+    pub parent_id: Option<i32>,
+}
+
+/// A DATETIME value that cannot be parsed by [`DateTime`].
+pub struct BrokenDateTime {
+    pub table: String,
+    pub column: String,
+    pub rowid: i64,
+    pub value: String,
+    pub not_null: bool,
+}
+
+/// The result of checking the integrity of the database.
+pub struct DoctorReport {
+    pub orphans: Vec<Orphan>,
+    pub position_gaps: Vec<PositionGap>,
+    pub broken_datetimes: Vec<BrokenDateTime>,
+    /// The size of the database file in bytes.
+    pub size: u64,
+    /// The number of bytes that can be reclaimed with [`Database::vacuum`].
+    pub free_space: u64,
+}
+
+impl DoctorReport {
+    /// The total number of problems that were found.
+    pub fn problems(&self) -> usize {
+        self.orphans.len() + self.position_gaps.len() + self.broken_datetimes.len()
+    }
+
+    /// The size of the database and the space that can be reclaimed.
+    pub fn size_summary(&self) -> String {
+        format!(
+            "Size: {} ({} can be reclaimed)",
+            format_size(self.size),
+            format_size(self.free_space)
+        )
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.size_summary())?;
+
+        writeln!(f, "Orphaned rows: {}", self.orphans.len())?;
+        for orphan in &self.orphans {
+            writeln!(
+                f,
+                "  {} {} references a missing {}",
+                orphan.table, orphan.rowid, orphan.parent
+            )?;
+        }
+
+        writeln!(f, "Non-contiguous positions: {}", self.position_gaps.len())?;
+        for gap in &self.position_gaps {
+            match gap.table.parent_column().zip(gap.parent_id) {
+                Some((column, id)) => {
+                    writeln!(f, "  {} where {} = {}", gap.table.table(), column, id)?
+                }
+                None => writeln!(f, "  {}", gap.table.table())?,
+            }
+        }
+
+        write!(f, "Broken datetimes: {}", self.broken_datetimes.len())?;
+        for datetime in &self.broken_datetimes {
+            write!(
+                f,
+                "\n  {}.{} of {} is {:?}",
+                datetime.table, datetime.column, datetime.rowid, datetime.value
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Format a number of bytes to be human readable.
+fn format_size(bytes: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < units.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, units[unit])
+    } else {
+        format!("{:.1} {}", size, units[unit])
+    }
+}
+
+impl Database {
+    /// Check the database for orphaned rows, positions that are not
+    /// contiguous, and datetimes that cannot be parsed.
+    pub fn check(&self) -> Result<DoctorReport> {
+        let start = Instant::now();
+        let conn = self.conn();
+
+        let report = DoctorReport {
+            orphans: check_orphans(&conn)?,
+            position_gaps: check_positions(&conn)?,
+            broken_datetimes: check_datetimes(&conn)?,
+            size: fs::metadata(self.path()).map(|m| m.len()).unwrap_or(0),
+            free_space: free_space(&conn)?,
+        };
+
+        info!("checked the database in {:?}", start.elapsed());
+        Ok(report)
+    }
+
+    /// Repair the problems that [`Database::check`] detects. Orphaned rows are
+    /// deleted, positions are renumbered in their current order, and broken
+    /// datetimes are set to the current datetime, or cleared if the column is
+    /// nullable.
+    pub fn repair(&self) -> Result<()> {
+        let start = Instant::now();
+        self.transaction(|tx| {
+            // Deleting an orphan cascades to the rows that reference it.
+            for orphan in check_orphans(tx.conn())? {
+                let query = format!("DELETE FROM {} WHERE rowid = ?1", orphan.table);
+                tx.execute(&query, [orphan.rowid])?;
+            }
+
+            for datetime in check_datetimes(tx.conn())? {
+                let query = format!(
+                    "UPDATE {} SET {} = ?1 WHERE rowid = ?2",
+                    datetime.table, datetime.column
+                );
+                let value = datetime.not_null.then(DateTime::now);
+                tx.execute(&query, (value, datetime.rowid))?;
+            }
+
+            for gap in check_positions(tx.conn())? {
+                gap.table.renormalize(tx.conn(), gap.parent_id)?;
+            }
+
+            Ok(())
+        })?;
+
+        info!("repaired the database in {:?}", start.elapsed());
+        Ok(())
+    }
+
+    /// Rebuild the database file to reclaim unused space.
+    pub fn vacuum(&self) -> Result<()> {
+        let start = Instant::now();
+        self.conn().execute("VACUUM", ())?;
+        info!("vacuumed the database in {:?}", start.elapsed());
+        Ok(())
+    }
+}
+
+fn tables(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )?;
+    let tables = stmt
+        .query_map([], |r| r.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(tables)
+}
+
+fn check_orphans(conn: &Connection) -> Result<Vec<Orphan>> {
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let orphans = stmt
+        .query_map([], |r| {
+            Ok(Orphan {
+                table: r.get(0)?,
+                rowid: r.get(1)?,
+                parent: r.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<Orphan>>>()?;
+    Ok(orphans)
+}
+
+fn check_positions(conn: &Connection) -> Result<Vec<PositionGap>> {
+    let tables = tables(conn)?;
+    let mut position_gaps = vec![];
+
+    for table in OrderedTable::ALL {
+        if !tables.iter().any(|t| t == table.table()) {
+            continue;
+        }
+
+        let query = format!(
+            "SELECT {scope}, COUNT(*), MIN(position), MAX(position), COUNT(DISTINCT position) \
+             FROM {table} WHERE archived = 0 GROUP BY {scope}",
+            table = table.table(),
+            scope = table.parent_column().unwrap_or("NULL"),
+        );
+        let mut stmt = conn.prepare(&query)?;
+        let groups = stmt.query_map([], |r| {
+            Ok((
+                r.get::<_, Option<i32>>(0)?,
+                r.get::<_, i64>(1)?,
+                r.get::<_, i64>(2)?,
+                r.get::<_, i64>(3)?,
+                r.get::<_, i64>(4)?,
+            ))
+        })?;
+
+        for group in groups {
+            let (parent_id, count, min, max, distinct) = group?;
+            if min != 0 || max != count - 1 || distinct != count {
+                position_gaps.push(PositionGap { table, parent_id });
+            }
+        }
+    }
+
+    Ok(position_gaps)
+}
+
+fn check_datetimes(conn: &Connection) -> Result<Vec<BrokenDateTime>> {
+    let mut broken_datetimes = vec![];
+
+    for table in tables(conn)? {
+        let mut stmt = conn.prepare("SELECT name, type, \"notnull\" FROM pragma_table_info(?1)")?;
+        let columns = stmt
+            .query_map([&table], |r| {
+                Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?, r.get(2)?))
+            })?
+            .collect::<rusqlite::Result<Vec<(String, String, bool)>>>()?;
+
+        for (column, _, not_null) in columns
+            .into_iter()
+            .filter(|(_, data_type, _)| data_type.eq_ignore_ascii_case("DATETIME"))
+        {
+            let query = format!(
+                "SELECT rowid, CAST({column} AS TEXT) FROM {table} WHERE {column} IS NOT NULL OR \
+                 {not_null}",
+                not_null = not_null as i32
+            );
+            let mut stmt = conn.prepare(&query)?;
+            let values = stmt.query_map([], |r| {
+                Ok((r.get::<_, i64>(0)?, r.get::<_, Option<String>>(1)?))
+            })?;
+
+            for value in values {
+                let (rowid, value) = value?;
+                if !value.as_deref().is_some_and(DateTime::is_valid_db) {
+                    broken_datetimes.push(BrokenDateTime {
+                        table: table.clone(),
+                        column: column.clone(),
+                        rowid,
+                        value: value.unwrap_or_default(),
+                        not_null,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(broken_datetimes)
+}
+
+fn free_space(conn: &Connection) -> Result<u64> {
+    let page_size: i64 = conn.pragma_query_value(None, "page_size", |r| r.get(0))?;
+    let freelist_count: i64 = conn.pragma_query_value(None, "freelist_count", |r| r.get(0))?;
+    Ok((page_size * freelist_count) as u64)
+}
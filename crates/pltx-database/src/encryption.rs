@@ -0,0 +1,152 @@
+use std::{
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{eyre::eyre, Result};
+use r2d2::Pool;
+use r2d2_sqlite::SqliteConnectionManager;
+use tracing::info;
+
+use crate::{create_pool, Database};
+
+/// The first 16 bytes of every unencrypted SQLite database file.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Check whether a database file is encrypted by reading its header. A file
+/// that doesn't exist yet or is empty is not encrypted.
+pub fn is_encrypted_file(path: &Path) -> bool {
+    let mut header = [0; 16];
+    match File::open(path).and_then(|mut file| file.read_exact(&mut header)) {
+        Ok(()) => &header != SQLITE_HEADER,
+        Err(_) => false,
+    }
+}
+
+/// The files that SQLite keeps next to a database, which belong to the file
+/// they were created for.
+fn sidecar_files(path: &Path) -> Vec<PathBuf> {
+    ["-journal", "-wal", "-shm"]
+        .iter()
+        .map(|suffix| {
+            let mut sidecar = path.as_os_str().to_owned();
+            sidecar.push(suffix);
+            PathBuf::from(sidecar)
+        })
+        .collect()
+}
+
+impl Database {
+    /// Whether the database file is encrypted with a passphrase.
+    pub fn is_encrypted(&self) -> bool {
+        self.encrypted
+    }
+
+    /// Whether the database is encrypted and has not been unlocked yet. No
+    /// queries can be executed while the database is locked.
+    pub fn is_locked(&self) -> bool {
+        self.encrypted && self.passphrase.is_none()
+    }
+
+    /// Unlock an encrypted database with its passphrase. Returns an error if
+    /// the passphrase is incorrect.
+    pub fn unlock(&mut self, passphrase: &str) -> Result<()> {
+        let pool = create_pool(&self.path(), Some(passphrase.to_string()))?;
+        pool.get()?
+            .query_row("SELECT count(*) FROM sqlite_master", (), |_| Ok(()))
+            .map_err(|_| eyre!("incorrect passphrase for {}", self.filename))?;
+        self.pool = pool;
+        self.watch = None;
+        self.passphrase = Some(passphrase.to_string());
+        info!("unlocked {}", self.filename);
+        Ok(())
+    }
+
+    /// Encrypt an unencrypted database in place.
+    pub fn encrypt(&mut self, passphrase: &str) -> Result<()> {
+        if self.encrypted {
+            return Err(eyre!("{} is already encrypted", self.filename));
+        }
+        self.export(Some(passphrase))?;
+        info!("encrypted {}", self.filename);
+        Ok(())
+    }
+
+    /// Decrypt an unlocked database in place.
+    pub fn decrypt(&mut self) -> Result<()> {
+        if !self.encrypted {
+            return Err(eyre!("{} is not encrypted", self.filename));
+        }
+        if self.is_locked() {
+            return Err(eyre!("{} must be unlocked to be decrypted", self.filename));
+        }
+        self.export(None)?;
+        info!("decrypted {}", self.filename);
+        Ok(())
+    }
+
+    /// Change the passphrase of an unlocked database.
+    pub fn change_passphrase(&mut self, passphrase: &str) -> Result<()> {
+        if !self.encrypted {
+            return Err(eyre!("{} is not encrypted", self.filename));
+        }
+        if self.is_locked() {
+            return Err(eyre!(
+                "{} must be unlocked to change the passphrase",
+                self.filename
+            ));
+        }
+        self.conn().pragma_update(None, "rekey", passphrase)?;
+        // The existing connections were keyed with the old passphrase.
+        self.pool = create_pool(&self.path(), Some(passphrase.to_string()))?;
+        self.watch = None;
+        self.passphrase = Some(passphrase.to_string());
+        info!("changed the passphrase of {}", self.filename);
+        Ok(())
+    }
+
+    /// Export the database to a copy that is encrypted with the passphrase, or
+    /// unencrypted if no passphrase is provided, then replace the database file
+    /// with the copy. The connections to the old file are closed first, and its
+    /// journal files are deleted so they aren't applied to the copy.
+    fn export(&mut self, passphrase: Option<&str>) -> Result<()> {
+        if self.session.is_some() {
+            return Err(eyre!(
+                "the encryption of {} cannot be changed while a session is running",
+                self.filename
+            ));
+        }
+
+        let path = self.path();
+        let export_path = path.with_file_name(format!("{}.export", self.filename));
+        if export_path.exists() {
+            fs::remove_file(&export_path)?;
+        }
+
+        let conn = self.conn();
+        conn.execute(
+            "ATTACH DATABASE ?1 AS export KEY ?2",
+            (export_path.to_string_lossy(), passphrase.unwrap_or("")),
+        )?;
+        conn.query_row("SELECT sqlcipher_export('export')", (), |_| Ok(()))?;
+        conn.execute("DETACH DATABASE export", ())?;
+        drop(conn);
+
+        // The watched connection is pooled too, so it's dropped before the pool
+        // is replaced, which closes the connections to the old file.
+        self.watch = None;
+        self.pool = Pool::new(SqliteConnectionManager::memory())?;
+
+        fs::rename(&export_path, &path)?;
+        for sidecar in sidecar_files(&path) {
+            if sidecar.exists() {
+                fs::remove_file(&sidecar)?;
+            }
+        }
+        self.pool = create_pool(&path, passphrase.map(String::from))?;
+        self.passphrase = passphrase.map(String::from);
+        self.encrypted = passphrase.is_some();
+        Ok(())
+    }
+}
@@ -1,20 +1,56 @@
 use std::{
+    ffi::OsStr,
     fs::{self, File},
     io::{self, Write},
     path::PathBuf,
 };
 
+const MIGRATIONS_DIR: &str = "migrations";
+
+/// Generate `src/generated_sql.rs` from the `.sql` files in a module crate's
+/// `migrations` directory. Files must be named `<version>_<name>.sql`, e.g.,
+/// `0001_init.sql`, and are ordered by their version. Used in build scripts.
 pub fn sql_init() -> io::Result<()> {
-    println!("cargo:rerun-if-changed=init.sql");
+    println!("cargo:rerun-if-changed={}", MIGRATIONS_DIR);
+
+    let mut migrations = vec![];
+    for file in fs::read_dir(MIGRATIONS_DIR).expect("failed to read the migrations directory") {
+        let path = file.expect("failed to get the file path").path();
+
+        if !path.is_file() || path.extension() != Some(OsStr::new("sql")) {
+            continue;
+        }
+
+        let filename = path
+            .file_stem()
+            .and_then(|f| f.to_str())
+            .expect("invalid migration filename");
+        let (version, name) = filename
+            .split_once('_')
+            .unwrap_or_else(|| panic!("migration {} must be named <version>_<name>", filename));
+        let version: i32 = version
+            .parse()
+            .unwrap_or_else(|_| panic!("invalid version for migration {}", filename));
 
-    let file_path = PathBuf::from("init.sql");
-    let file_contents =
-        fs::read_to_string(file_path).expect("failed to find init.sql in crate directory");
+        migrations.push((version, name.to_string(), fs::read_to_string(&path)?));
+    }
+    migrations.sort_by_key(|m| m.0);
 
     let dest_path = PathBuf::from("src/generated_sql.rs");
     let mut dest_file = File::create(dest_path).expect("failed to create generated_sql.rs");
 
-    write!(dest_file, "const SQL: &str = \"{}\";", file_contents)?;
+    writeln!(
+        dest_file,
+        "#[rustfmt::skip]\nconst MIGRATIONS: &[pltx_database::Migration] = &["
+    )?;
+    for (version, name, sql) in migrations {
+        writeln!(
+            dest_file,
+            "pltx_database::Migration {{ version: {}, name: {:?}, sql: {:?} }},",
+            version, name, sql
+        )?;
+    }
+    writeln!(dest_file, "];")?;
 
     Ok(())
 }
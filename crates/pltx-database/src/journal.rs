@@ -0,0 +1,273 @@
+//! A journal of the changes made to the tracked tables, so they can be undone
+//! and redone. Triggers record an image of each row before and after it's
+//! changed while a journal entry is being recorded. Undoing an entry restores
+//! the rows to their images before the change, and redoing it restores the
+//! images after the change.
+
+use std::time::Instant;
+
+use color_eyre::Result;
+use pltx_utils::DateTime;
+use rusqlite::{Connection, OptionalExtension};
+use tracing::info;
+
+use crate::{sync, watch::Watch, Database, Transaction};
+
+/// The number of journal entries that are kept. The oldest entries are deleted
+/// once there are more.
+const JOURNAL_LIMIT: i32 = 100;
+
+impl Database {
+    /// Record the changes made to the tables in the journal, so they can be
+    /// undone, count them in the change counter, so other instances can
+    /// reload them, and record them for syncing with other devices. The
+    /// tables must have an `id` column. The triggers are
+    /// recreated each time, so columns that were added by a migration are
+    /// included.
+    pub fn track_changes(&self, tables: &[&str]) -> Result<()> {
+        let start = Instant::now();
+
+        self.transaction(|tx| {
+            for table in tables {
+                let columns = table_columns(tx.conn(), table)?;
+                let image = |row: &str| {
+                    let pairs = columns
+                        .iter()
+                        .map(|c| format!("'{c}', {row}.\"{c}\""))
+                        .collect::<Vec<String>>()
+                        .join(", ");
+                    format!("json_object({pairs})")
+                };
+
+                for (event, row, before, after) in [
+                    ("INSERT", "NEW", "NULL".to_string(), image("NEW")),
+                    ("UPDATE", "NEW", image("OLD"), image("NEW")),
+                    ("DELETE", "OLD", image("OLD"), "NULL".to_string()),
+                ] {
+                    let name = format!("journal_{}_{}", table, event.to_lowercase());
+                    tx.execute(&format!("DROP TRIGGER IF EXISTS {name}"), ())?;
+                    tx.execute(
+                        &format!(
+                            "CREATE TRIGGER {name} AFTER {event} ON {table} WHEN EXISTS (SELECT 1 \
+                             FROM journal WHERE recording = 1) BEGIN INSERT INTO journal_change \
+                             (journal_id, table_name, row_id, before, after) VALUES ((SELECT id \
+                             FROM journal WHERE recording = 1), '{table}', {row}.id, {before}, \
+                             {after}); END"
+                        ),
+                        (),
+                    )?;
+
+                    let name = format!("change_counter_{}_{}", table, event.to_lowercase());
+                    tx.execute(&format!("DROP TRIGGER IF EXISTS {name}"), ())?;
+                    tx.execute(
+                        &format!(
+                            "CREATE TRIGGER {name} AFTER {event} ON {table} BEGIN UPDATE \
+                             change_counter SET changes = changes + 1; END"
+                        ),
+                        (),
+                    )?;
+                }
+
+                sync::track_sync_changes(tx, table)?;
+            }
+            Ok(())
+        })?;
+
+        info!(
+            "created journal triggers for {} tables in {:?}",
+            tables.len(),
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Execute multiple statements as a single atomic operation, like
+    /// [`Database::transaction`], and record the changes in the journal so
+    /// they can be undone. The description is shown when the changes are
+    /// undone or redone, e.g., "archive card \"Groceries\"".
+    pub fn undoable<T, F>(&self, description: &str, f: F) -> Result<T>
+    where
+        F: FnOnce(&Transaction) -> Result<T>,
+    {
+        let (value, own_changes) = self.transaction(|tx| {
+            tx.execute(
+                "INSERT INTO journal (description, recording, undone, created_at) VALUES (?1, 1, \
+                 0, ?2)",
+                (description, DateTime::now()),
+            )?;
+            let journal_id = tx.last_row_id("journal")?;
+            // The insert has locked the database, so only this transaction can
+            // change the counter until it's committed.
+            let changes = Watch::changes(tx.conn())?;
+
+            let value = f(tx)?;
+
+            tx.execute(
+                "UPDATE journal SET recording = 0 WHERE id = ?1",
+                [journal_id],
+            )?;
+            let changed = tx.conn().query_row(
+                "SELECT EXISTS (SELECT 1 FROM journal_change WHERE journal_id = ?1)",
+                [journal_id],
+                |r| r.get::<_, bool>(0),
+            )?;
+            if changed {
+                // The undone entries can no longer be redone after a new change.
+                tx.execute("DELETE FROM journal WHERE undone = 1", ())?;
+                tx.execute(
+                    "DELETE FROM journal WHERE id <= (SELECT id FROM journal ORDER BY id DESC \
+                     LIMIT 1 OFFSET ?1)",
+                    [JOURNAL_LIMIT],
+                )?;
+            } else {
+                tx.execute("DELETE FROM journal WHERE id = ?1", [journal_id])?;
+            }
+
+            Ok((value, Watch::changes(tx.conn())? - changes))
+        })?;
+        self.record_own_changes(own_changes);
+
+        Ok(value)
+    }
+
+    /// Undo the most recent journal entry that hasn't been undone. Returns the
+    /// description of the entry, or `None` if there is nothing to undo.
+    pub fn undo(&self) -> Result<Option<String>> {
+        let start = Instant::now();
+
+        let (description, own_changes) = self.transaction(|tx| {
+            let Some((journal_id, description)) = tx
+                .conn()
+                .query_row(
+                    "SELECT id, description FROM journal WHERE undone = 0 ORDER BY id DESC LIMIT 1",
+                    (),
+                    |r| Ok((r.get::<_, i32>(0)?, r.get::<_, String>(1)?)),
+                )
+                .optional()?
+            else {
+                return Ok((None, 0));
+            };
+
+            let changes = Watch::changes(tx.conn())?;
+            apply_changes(tx.conn(), journal_id, true)?;
+            tx.execute("UPDATE journal SET undone = 1 WHERE id = ?1", [journal_id])?;
+            Ok((Some(description), Watch::changes(tx.conn())? - changes))
+        })?;
+        self.record_own_changes(own_changes);
+
+        info!("undo query executed in {:?}", start.elapsed());
+        Ok(description)
+    }
+
+    /// Redo the journal entry that was undone most recently. Returns the
+    /// description of the entry, or `None` if there is nothing to redo.
+    pub fn redo(&self) -> Result<Option<String>> {
+        let start = Instant::now();
+
+        let (description, own_changes) = self.transaction(|tx| {
+            let Some((journal_id, description)) = tx
+                .conn()
+                .query_row(
+                    "SELECT id, description FROM journal WHERE undone = 1 ORDER BY id LIMIT 1",
+                    (),
+                    |r| Ok((r.get::<_, i32>(0)?, r.get::<_, String>(1)?)),
+                )
+                .optional()?
+            else {
+                return Ok((None, 0));
+            };
+
+            let changes = Watch::changes(tx.conn())?;
+            apply_changes(tx.conn(), journal_id, false)?;
+            tx.execute("UPDATE journal SET undone = 0 WHERE id = ?1", [journal_id])?;
+            Ok((Some(description), Watch::changes(tx.conn())? - changes))
+        })?;
+        self.record_own_changes(own_changes);
+
+        info!("redo query executed in {:?}", start.elapsed());
+        Ok(description)
+    }
+}
+
+pub(crate) fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let columns = stmt
+        .query_map([table], |r| r.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(columns)
+}
+
+/// Restore the rows changed by a journal entry to their images before the
+/// change when undoing, or after the change when redoing. The changes are
+/// undone in the reverse order they were made.
+fn apply_changes(conn: &Connection, journal_id: i32, undo: bool) -> Result<()> {
+    // Rows are restored one at a time, so a child may be restored before its
+    // parent.
+    conn.pragma_update(None, "defer_foreign_keys", "ON")?;
+
+    let query = if undo {
+        "SELECT table_name, row_id, before FROM journal_change WHERE journal_id = ?1 ORDER BY id \
+         DESC"
+    } else {
+        "SELECT table_name, row_id, after FROM journal_change WHERE journal_id = ?1 ORDER BY id"
+    };
+    let mut stmt = conn.prepare(query)?;
+    let changes = stmt
+        .query_map([journal_id], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))?
+        .collect::<rusqlite::Result<Vec<(String, i32, Option<String>)>>>()?;
+
+    for (table, row_id, image) in changes {
+        restore_row(conn, &table, row_id, image)?;
+    }
+
+    Ok(())
+}
+
+/// Restore a row to an image, or delete it if there is no image because the
+/// row didn't exist.
+fn restore_row(conn: &Connection, table: &str, row_id: i32, image: Option<String>) -> Result<()> {
+    let Some(image) = image else {
+        conn.execute(&format!("DELETE FROM {table} WHERE id = ?1"), [row_id])?;
+        return Ok(());
+    };
+
+    let mut stmt = conn.prepare("SELECT key FROM json_each(?1)")?;
+    let columns = stmt
+        .query_map([&image], |r| r.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    let value = |c: &String| format!("json_extract(?1, '$.{c}')");
+
+    let exists = conn.query_row(
+        &format!("SELECT EXISTS (SELECT 1 FROM {table} WHERE id = ?1)"),
+        [row_id],
+        |r| r.get::<_, bool>(0),
+    )?;
+    if exists {
+        let assignments = columns
+            .iter()
+            .map(|c| format!("\"{c}\" = {}", value(c)))
+            .collect::<Vec<String>>()
+            .join(", ");
+        conn.execute(
+            &format!("UPDATE {table} SET {assignments} WHERE id = ?2"),
+            (&image, row_id),
+        )?;
+    } else {
+        let names = columns
+            .iter()
+            .map(|c| format!("\"{c}\""))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let values = columns
+            .iter()
+            .map(value)
+            .collect::<Vec<String>>()
+            .join(", ");
+        conn.execute(
+            &format!("INSERT INTO {table} ({names}) VALUES ({values})"),
+            [&image],
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use std::{collections::HashMap, fmt, fs, path::Path, time::Instant};
+
+use color_eyre::{eyre::eyre, Result};
+use rusqlite::{params_from_iter, types::Value, Connection, OpenFlags, OptionalExtension};
+use tracing::info;
+
+use crate::{Database, OrderedTable};
+
+/// The tables that are merged, with parents before their children, and the
+/// columns that identify a duplicate row. The parent IDs are compared after
+/// they're mapped to the rows in the database that is merged into.
+const MERGE_TABLES: [(&str, &[&str]); 9] = [
+    ("project", &["title", "created_at"]),
+    ("project_label", &["project_id", "title", "created_at"]),
+    ("project_list", &["project_id", "title", "created_at"]),
+    ("project_card", &["list_id", "title", "created_at"]),
+    ("card_label", &["card_id", "label_id"]),
+    ("card_subtask", &["card_id", "value", "created_at"]),
+    (
+        "card_activity",
+        &["card_id", "action", "field", "created_at"],
+    ),
+    ("session", &["started"]),
+    ("session_segment", &["session_id", "started"]),
+];
+
+/// The rows of a table that are merged.
+pub struct MergeCount {
+    pub table: &'static str,
+    /// The rows that don't exist yet and are imported.
+    pub imported: usize,
+    /// The rows that already exist. Their children are merged into the
+    /// existing rows.
+    pub duplicates: usize,
+    /// The rows that reference a missing parent and are left out.
+    pub orphans: usize,
+}
+
+/// The result of merging another database.
+pub struct MergeReport {
+    pub tables: Vec<MergeCount>,
+}
+
+impl MergeReport {
+    /// The total number of rows that are imported.
+    pub fn imported(&self) -> usize {
+        self.tables.iter().map(|t| t.imported).sum()
+    }
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<16} {:>8} {:>10} {:>8}",
+            "Table", "Imported", "Duplicates", "Orphans"
+        )?;
+        for count in &self.tables {
+            write!(
+                f,
+                "\n{:<16} {:>8} {:>10} {:>8}",
+                count.table, count.imported, count.duplicates, count.orphans
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Database {
+    /// Import the projects and sessions of another database. The IDs are
+    /// remapped, the ordered rows are placed after the existing rows in their
+    /// scope, and the rows that already exist are skipped. Nothing is changed
+    /// if `dry_run` is true, but the report is the same.
+    pub fn merge(
+        &self,
+        path: &Path,
+        passphrase: Option<&str>,
+        dry_run: bool,
+    ) -> Result<MergeReport> {
+        let start = Instant::now();
+
+        if fs::canonicalize(path).ok() == fs::canonicalize(self.path()).ok() {
+            return Err(eyre!("cannot merge {} into itself", self.filename));
+        }
+
+        let source = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        if let Some(passphrase) = passphrase {
+            source.pragma_update(None, "key", passphrase)?;
+        }
+        source
+            .query_row("SELECT count(*) FROM sqlite_master", (), |_| Ok(()))
+            .map_err(|_| {
+                eyre!(
+                    "failed to read {}, it may be encrypted with a different passphrase",
+                    path.display()
+                )
+            })?;
+
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let mut ids: HashMap<&str, HashMap<i64, i64>> = HashMap::new();
+        let mut tables = vec![];
+        for (table, key) in MERGE_TABLES {
+            tables.push(merge_table(&source, &tx, table, key, &mut ids)?);
+        }
+        let report = MergeReport { tables };
+
+        if dry_run {
+            tx.rollback()?;
+        } else {
+            tx.commit()?;
+        }
+
+        info!(
+            "merged {} rows of {} into {} in {:?} (dry run: {})",
+            report.imported(),
+            path.display(),
+            self.filename,
+            start.elapsed(),
+            dry_run
+        );
+        Ok(report)
+    }
+}
+
+/// The columns of a table, which is empty if the table doesn't exist.
+fn columns(conn: &Connection, table: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM pragma_table_info(?1)")?;
+    let columns = stmt
+        .query_map([table], |r| r.get(0))?
+        .collect::<rusqlite::Result<Vec<String>>>()?;
+    Ok(columns)
+}
+
+/// Copy the rows of a table that don't exist yet and record the IDs that the
+/// rows of the source are mapped to.
+fn merge_table(
+    source: &Connection,
+    conn: &Connection,
+    table: &'static str,
+    key: &[&str],
+    ids: &mut HashMap<&'static str, HashMap<i64, i64>>,
+) -> Result<MergeCount> {
+    let mut count = MergeCount {
+        table,
+        imported: 0,
+        duplicates: 0,
+        orphans: 0,
+    };
+
+    let target_columns = columns(conn, table)?;
+    let columns = columns(source, table)?
+        .into_iter()
+        .filter(|c| c != "id" && target_columns.contains(c))
+        .collect::<Vec<String>>();
+    if columns.is_empty() {
+        return Ok(count);
+    }
+
+    let mut stmt = conn.prepare("SELECT \"from\", \"table\" FROM pragma_foreign_key_list(?1)")?;
+    let foreign_keys = stmt
+        .query_map([table], |r| {
+            Ok((r.get::<_, String>(0)?, r.get::<_, String>(1)?))
+        })?
+        .collect::<rusqlite::Result<HashMap<String, String>>>()?;
+
+    let ordered_table = OrderedTable::ALL.into_iter().find(|t| t.table() == table);
+    // The rows are placed in the order of their positions, so the order is kept
+    // within each scope.
+    let order = if ordered_table.is_some() {
+        "position, id"
+    } else {
+        "id"
+    };
+    let mut stmt = source.prepare(&format!(
+        "SELECT id, {} FROM {} ORDER BY {}",
+        columns.join(", "),
+        table,
+        order
+    ))?;
+    let rows = stmt
+        .query_map([], |r| {
+            (0..=columns.len())
+                .map(|i| r.get::<_, Value>(i))
+                .collect::<rusqlite::Result<Vec<Value>>>()
+        })?
+        .collect::<rusqlite::Result<Vec<Vec<Value>>>>()?;
+
+    let duplicate_query = format!(
+        "SELECT id FROM {} WHERE {} LIMIT 1",
+        table,
+        key.iter()
+            .enumerate()
+            .map(|(i, column)| format!("{} IS ?{}", column, i + 1))
+            .collect::<Vec<String>>()
+            .join(" AND ")
+    );
+    let placeholders = (1..=columns.len())
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let insert_query = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table,
+        columns.join(", "),
+        placeholders
+    );
+
+    let mut table_ids = HashMap::new();
+    'rows: for mut row in rows {
+        let Value::Integer(id) = row.remove(0) else {
+            continue;
+        };
+
+        for (column, value) in columns.iter().zip(row.iter_mut()) {
+            let (Some(parent), Value::Integer(parent_id)) = (foreign_keys.get(column), &*value)
+            else {
+                continue;
+            };
+            match ids.get(parent.as_str()).and_then(|p| p.get(parent_id)) {
+                Some(new_id) => *value = Value::Integer(*new_id),
+                None => {
+                    count.orphans += 1;
+                    continue 'rows;
+                }
+            }
+        }
+
+        let value = |column: &str| {
+            columns
+                .iter()
+                .position(|c| c == column)
+                .map_or(Value::Null, |i| row[i].clone())
+        };
+
+        let duplicate = conn
+            .query_row(
+                &duplicate_query,
+                params_from_iter(key.iter().map(|c| value(c))),
+                |r| r.get::<_, i64>(0),
+            )
+            .optional()?;
+        if let Some(existing_id) = duplicate {
+            table_ids.insert(id, existing_id);
+            count.duplicates += 1;
+            continue;
+        }
+
+        if let Some(ordered_table) = ordered_table {
+            let archived = matches!(value("archived"), Value::Integer(1));
+            if let (false, Some(position)) =
+                (archived, columns.iter().position(|c| c == "position"))
+            {
+                let parent_id = match ordered_table.parent_column().map(value) {
+                    Some(Value::Integer(parent_id)) => Some(parent_id as i32),
+                    _ => None,
+                };
+                row[position] =
+                    Value::Integer(ordered_table.next_position(conn, parent_id)? as i64);
+            }
+        }
+
+        conn.execute(&insert_query, params_from_iter(row.iter()))?;
+        table_ids.insert(id, conn.last_insert_rowid());
+        count.imported += 1;
+    }
+    ids.insert(table, table_ids);
+
+    Ok(count)
+}
@@ -0,0 +1,213 @@
+use color_eyre::{eyre::eyre, Result};
+use pltx_utils::DateTime;
+use rusqlite::OptionalExtension;
+use tracing::info;
+
+use crate::Database;
+
+/// A single versioned schema change. Migrations are registered per module and
+/// applied in order of their version, starting from 1.
+#[derive(Debug)]
+pub struct Migration {
+    /// The version the schema is at after this migration has been applied.
+    pub version: i32,
+    /// A short name describing the migration.
+    pub name: &'static str,
+    /// The SQL to execute. Must not contain its own `BEGIN`/`COMMIT`, as each
+    /// migration is applied inside a transaction.
+    pub sql: &'static str,
+}
+
+/// The migrations for the tables that are managed by the database crate
+/// itself, rather than by a module.
+const CORE_MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "session",
+        sql: "CREATE TABLE IF NOT EXISTS session (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            started DATETIME NOT NULL,
+            ended DATETIME NOT NULL
+        );",
+    },
+    Migration {
+        version: 2,
+        name: "journal",
+        sql: "CREATE TABLE IF NOT EXISTS journal (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            description TEXT NOT NULL,
+            recording BOOLEAN NOT NULL CHECK (recording IN (0, 1)),
+            undone BOOLEAN NOT NULL CHECK (undone IN (0, 1)),
+            created_at DATETIME NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS journal_change (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            journal_id INTEGER NOT NULL,
+            table_name TEXT NOT NULL,
+            row_id INTEGER NOT NULL,
+            before TEXT,
+            after TEXT,
+            FOREIGN KEY (journal_id)
+                REFERENCES journal (id)
+                    ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_journal_change_journal_id ON journal_change (journal_id);",
+    },
+    Migration {
+        version: 3,
+        name: "session_segment",
+        sql: "ALTER TABLE session ADD COLUMN status TEXT NOT NULL DEFAULT 'ended'
+            CHECK (status IN ('running', 'ended', 'crashed'));
+
+        CREATE TABLE IF NOT EXISTS session_segment (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            session_id INTEGER NOT NULL,
+            active BOOLEAN NOT NULL CHECK (active IN (0, 1)),
+            started DATETIME NOT NULL,
+            ended DATETIME NOT NULL,
+            FOREIGN KEY (session_id)
+                REFERENCES session (id)
+                    ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_session_segment_session_id ON session_segment (session_id);
+
+        -- Idle time wasn't tracked before, so the previous sessions were active throughout.
+        INSERT INTO session_segment (session_id, active, started, ended)
+            SELECT id, 1, started, ended FROM session;",
+    },
+    Migration {
+        version: 4,
+        name: "change_counter",
+        sql: "CREATE TABLE IF NOT EXISTS change_counter (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            changes INTEGER NOT NULL
+        );
+
+        INSERT OR IGNORE INTO change_counter (id, changes) VALUES (1, 0);",
+    },
+    Migration {
+        version: 5,
+        name: "sync",
+        sql: "CREATE TABLE IF NOT EXISTS sync_state (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            device_id TEXT NOT NULL,
+            clock INTEGER NOT NULL,
+            applying BOOLEAN NOT NULL CHECK (applying IN (0, 1)),
+            key TEXT,
+            cursor INTEGER NOT NULL,
+            synced_at DATETIME
+        );
+
+        INSERT OR IGNORE INTO sync_state (id, device_id, clock, applying, cursor)
+            VALUES (1, lower(hex(randomblob(16))), 0, 0, 0);
+
+        -- The row ID is NULL for rows that were deleted on another device before they were synced
+        -- to this one.
+        CREATE TABLE IF NOT EXISTS sync_row (
+            uuid TEXT PRIMARY KEY,
+            table_name TEXT NOT NULL,
+            row_id INTEGER,
+            clock INTEGER NOT NULL,
+            device_id TEXT NOT NULL,
+            deleted BOOLEAN NOT NULL CHECK (deleted IN (0, 1)),
+            pending BOOLEAN NOT NULL CHECK (pending IN (0, 1)),
+            UNIQUE (table_name, row_id)
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_sync_row_pending ON sync_row (pending);",
+    },
+    Migration {
+        version: 6,
+        name: "sync_deferred",
+        sql: "-- The changes from other devices that reference rows that haven't arrived yet.
+        CREATE TABLE IF NOT EXISTS sync_deferred (
+            uuid TEXT PRIMARY KEY,
+            table_name TEXT NOT NULL,
+            clock INTEGER NOT NULL,
+            device_id TEXT NOT NULL,
+            row TEXT
+        );",
+    },
+];
+
+impl Database {
+    /// Apply any pending migrations of the tables that every profile has,
+    /// e.g., the sessions and the journal. Done when a session starts, and
+    /// by commands that need them without starting a session.
+    pub fn migrate_core(&self) -> Result<()> {
+        self.migrate("core", CORE_MIGRATIONS)
+    }
+
+    /// Apply any pending migrations for a module. The current version of each
+    /// module is stored in the `schema_version` table. Each migration is
+    /// applied in its own transaction, so a failed migration leaves the schema
+    /// at the last successful version.
+    ///
+    /// Returns an error if the database has been migrated to a newer version
+    /// than this binary knows about.
+    pub fn migrate(&self, module: &str, migrations: &[Migration]) -> Result<()> {
+        let latest_version = migrations.last().map(|m| m.version).unwrap_or(0);
+        for (i, migration) in migrations.iter().enumerate() {
+            if migration.version != i as i32 + 1 {
+                return Err(eyre!(
+                    "migrations for {} must be ordered from version 1 without gaps, found version \
+                     {} at position {}",
+                    module,
+                    migration.version,
+                    i + 1
+                ));
+            }
+        }
+
+        let mut conn = self.conn();
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                module TEXT PRIMARY KEY,
+                version INTEGER NOT NULL,
+                updated_at DATETIME NOT NULL
+            )",
+            (),
+        )?;
+
+        let current_version: i32 = conn
+            .query_row(
+                "SELECT version FROM schema_version WHERE module = ?1",
+                [module],
+                |r| r.get(0),
+            )
+            .optional()?
+            .unwrap_or(0);
+
+        if current_version > latest_version {
+            return Err(eyre!(
+                "the {} schema of {} is at version {}, but this version of pltx only supports up \
+                 to version {}; please upgrade pltx",
+                module,
+                self.filename,
+                current_version,
+                latest_version
+            ));
+        }
+
+        for migration in migrations.iter().filter(|m| m.version > current_version) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration.sql)?;
+            tx.execute(
+                "INSERT INTO schema_version (module, version, updated_at) VALUES (?1, ?2, ?3) ON \
+                 CONFLICT (module) DO UPDATE SET version = excluded.version, updated_at = \
+                 excluded.updated_at",
+                (module, migration.version, DateTime::now()),
+            )?;
+            tx.commit()?;
+            info!(
+                "applied {} migration {} ({})",
+                module, migration.version, migration.name
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+use std::cmp::Ordering;
+
+use color_eyre::{eyre::eyre, Result};
+use pltx_utils::DateTime;
+use rusqlite::{Connection, OptionalExtension};
+
+/// The tables that are ordered by a `position` column. Positions are numbered
+/// from 0 without gaps within the scope of the parent column, e.g., the cards
+/// of a list are ordered separately from the cards of other lists. Archived
+/// rows are taken out of the ordering until they are unarchived.
+///
+/// The methods take a [`Connection`] so they can be used with
+/// [`Database::conn`](crate::Database::conn) or inside a
+/// [`Database::transaction`](crate::Database::transaction) with
+/// [`Transaction::conn`](crate::Transaction::conn). Operations that update
+/// more than one row should be executed inside a transaction.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OrderedTable {
+    Project,
+    ProjectLabel,
+    ProjectList,
+    ProjectCard,
+    CardSubtask,
+}
+
+impl OrderedTable {
+    /// Every ordered table.
+    pub const ALL: [OrderedTable; 5] = [
+        OrderedTable::Project,
+        OrderedTable::ProjectLabel,
+        OrderedTable::ProjectList,
+        OrderedTable::ProjectCard,
+        OrderedTable::CardSubtask,
+    ];
+
+    /// The name of the table.
+    pub fn table(&self) -> &'static str {
+        match self {
+            OrderedTable::Project => "project",
+            OrderedTable::ProjectLabel => "project_label",
+            OrderedTable::ProjectList => "project_list",
+            OrderedTable::ProjectCard => "project_card",
+            OrderedTable::CardSubtask => "card_subtask",
+        }
+    }
+
+    /// The column that the positions are scoped by, if any.
+    pub fn parent_column(&self) -> Option<&'static str> {
+        match self {
+            OrderedTable::Project => None,
+            OrderedTable::ProjectLabel => Some("project_id"),
+            OrderedTable::ProjectList => Some("project_id"),
+            OrderedTable::ProjectCard => Some("list_id"),
+            OrderedTable::CardSubtask => Some("card_id"),
+        }
+    }
+
+    /// The parent column, or `NULL` for tables that aren't scoped, so that
+    /// `{parent} IS ?` matches every row of an unscoped table when the parent
+    /// is `None`.
+    fn parent(&self) -> &'static str {
+        self.parent_column().unwrap_or("NULL")
+    }
+
+    /// The condition that matches the ordered rows in the scope of the parent
+    /// that is bound to `?{param}`.
+    fn scope(&self, param: usize) -> String {
+        format!("{} IS ?{} AND archived = 0", self.parent(), param)
+    }
+
+    /// Get the number of rows in the scope of a parent.
+    pub fn count(&self, conn: &Connection, parent_id: Option<i32>) -> Result<i32> {
+        let query = format!(
+            "SELECT COUNT(*) FROM {} WHERE {}",
+            self.table(),
+            self.scope(1)
+        );
+        Ok(conn.query_row(&query, [parent_id], |r| r.get(0))?)
+    }
+
+    /// Get the position that a new row should be inserted at to be placed
+    /// last in the scope of a parent.
+    pub fn next_position(&self, conn: &Connection, parent_id: Option<i32>) -> Result<i32> {
+        let query = format!(
+            "SELECT COALESCE(MAX(position), -1) + 1 FROM {} WHERE {}",
+            self.table(),
+            self.scope(1)
+        );
+        Ok(conn.query_row(&query, [parent_id], |r| r.get(0))?)
+    }
+
+    /// Get the parent and the position of a row.
+    pub fn get(&self, conn: &Connection, id: i32) -> Result<(Option<i32>, i32)> {
+        let query = format!(
+            "SELECT {}, position FROM {} WHERE id = ?1",
+            self.parent(),
+            self.table()
+        );
+        conn.query_row(&query, [id], |r| Ok((r.get(0)?, r.get(1)?)))
+            .optional()?
+            .ok_or_else(|| eyre!("no row in {} with the id {}", self.table(), id))
+    }
+
+    /// Move a row to an index within its scope. The rows in between are
+    /// shifted to make space. The index is clamped to the rows in the scope.
+    /// Archived rows aren't part of the ordering, so they aren't moved.
+    pub fn move_to(&self, conn: &Connection, id: i32, index: i32) -> Result<()> {
+        if self.is_archived(conn, id)? {
+            return Ok(());
+        }
+
+        let (parent_id, position) = self.get(conn, id)?;
+        let index = index.clamp(0, self.count(conn, parent_id)? - 1);
+
+        match index.cmp(&position) {
+            Ordering::Less => self.shift(conn, parent_id, index, position - 1, 1)?,
+            Ordering::Greater => self.shift(conn, parent_id, position + 1, index, -1)?,
+            Ordering::Equal => return Ok(()),
+        }
+
+        self.set_position(conn, id, index)
+    }
+
+    /// Move a row up or down by an offset within its scope, e.g., `-1` to swap
+    /// it with the previous row.
+    pub fn move_by(&self, conn: &Connection, id: i32, offset: i32) -> Result<()> {
+        let (_, position) = self.get(conn, id)?;
+        self.move_to(conn, id, position + offset)
+    }
+
+    /// Move a row to another parent, e.g., a card to another list. The row is
+    /// placed at the index in the new scope, or last if no index is provided.
+    /// An archived row only changes its parent, and is placed last in the new
+    /// scope when it's unarchived.
+    pub fn move_to_parent(
+        &self,
+        conn: &Connection,
+        id: i32,
+        parent_id: i32,
+        index: Option<i32>,
+    ) -> Result<()> {
+        let parent_column = self
+            .parent_column()
+            .ok_or_else(|| eyre!("{} has no parent to move to", self.table()))?;
+        let (old_parent_id, position) = self.get(conn, id)?;
+        if old_parent_id == Some(parent_id) {
+            return match index {
+                Some(index) => self.move_to(conn, id, index),
+                None => Ok(()),
+            };
+        }
+
+        if self.is_archived(conn, id)? {
+            let query = format!(
+                "UPDATE {} SET {} = ?1, updated_at = ?2 WHERE id = ?3",
+                self.table(),
+                parent_column
+            );
+            conn.execute(&query, (parent_id, DateTime::now(), id))?;
+            return Ok(());
+        }
+
+        let next_position = self.next_position(conn, Some(parent_id))?;
+        let index = index.map_or(next_position, |i| i.clamp(0, next_position));
+
+        self.shift(conn, old_parent_id, position + 1, i32::MAX, -1)?;
+        self.shift(conn, Some(parent_id), index, i32::MAX, 1)?;
+
+        let query = format!(
+            "UPDATE {} SET {} = ?1, position = ?2, updated_at = ?3 WHERE id = ?4",
+            self.table(),
+            parent_column
+        );
+        conn.execute(&query, (parent_id, index, DateTime::now(), id))?;
+        Ok(())
+    }
+
+    /// Whether a row is archived.
+    pub fn is_archived(&self, conn: &Connection, id: i32) -> Result<bool> {
+        let query = format!("SELECT archived FROM {} WHERE id = ?1", self.table());
+        Ok(conn.query_row(&query, [id], |r| r.get(0))?)
+    }
+
+    /// Archive a row and close the gap it leaves in its scope.
+    pub fn archive(&self, conn: &Connection, id: i32) -> Result<()> {
+        if self.is_archived(conn, id)? {
+            return Ok(());
+        }
+
+        let (parent_id, position) = self.get(conn, id)?;
+        let query = format!(
+            "UPDATE {} SET archived = 1, archived_at = ?1, updated_at = ?1 WHERE id = ?2",
+            self.table()
+        );
+        conn.execute(&query, (DateTime::now(), id))?;
+        self.shift(conn, parent_id, position + 1, i32::MAX, -1)
+    }
+
+    /// Unarchive a row. It's placed last in its scope.
+    pub fn unarchive(&self, conn: &Connection, id: i32) -> Result<()> {
+        if !self.is_archived(conn, id)? {
+            return Ok(());
+        }
+
+        let (parent_id, _) = self.get(conn, id)?;
+        let position = self.next_position(conn, parent_id)?;
+        let query = format!(
+            "UPDATE {} SET archived = 0, archived_at = NULL, position = ?1, updated_at = ?2 WHERE \
+             id = ?3",
+            self.table()
+        );
+        conn.execute(&query, (position, DateTime::now(), id))?;
+        Ok(())
+    }
+
+    /// Delete a row and close the gap it leaves in its scope, unless it was
+    /// archived.
+    pub fn delete(&self, conn: &Connection, id: i32) -> Result<()> {
+        let archived = self.is_archived(conn, id)?;
+        let (parent_id, position) = self.get(conn, id)?;
+        let query = format!("DELETE FROM {} WHERE id = ?1", self.table());
+        conn.execute(&query, [id])?;
+        if archived {
+            return Ok(());
+        }
+        self.shift(conn, parent_id, position + 1, i32::MAX, -1)
+    }
+
+    /// Delete the rows that were archived before a datetime. Returns the
+    /// number of deleted rows.
+    pub fn purge_archived(&self, conn: &Connection, before: &str) -> Result<usize> {
+        let query = format!(
+            "DELETE FROM {} WHERE archived = 1 AND archived_at < ?1",
+            self.table()
+        );
+        Ok(conn.execute(&query, [before])?)
+    }
+
+    /// Renumber the positions in the scope of a parent from 0 without gaps or
+    /// duplicates, keeping their current order.
+    pub fn renormalize(&self, conn: &Connection, parent_id: Option<i32>) -> Result<()> {
+        let query = format!(
+            "SELECT id FROM {} WHERE {} ORDER BY position, id",
+            self.table(),
+            self.scope(1)
+        );
+        let ids = conn
+            .prepare(&query)?
+            .query_map([parent_id], |r| r.get(0))?
+            .collect::<rusqlite::Result<Vec<i32>>>()?;
+
+        for (index, id) in ids.into_iter().enumerate() {
+            self.set_position(conn, id, index as i32)?;
+        }
+
+        Ok(())
+    }
+
+    /// Add an amount to the positions from `start` to `end` (inclusive) in the
+    /// scope of a parent.
+    fn shift(
+        &self,
+        conn: &Connection,
+        parent_id: Option<i32>,
+        start: i32,
+        end: i32,
+        amount: i32,
+    ) -> Result<()> {
+        let query = format!(
+            "UPDATE {} SET position = position + ?1, updated_at = ?2 WHERE {} AND position \
+             BETWEEN ?4 AND ?5",
+            self.table(),
+            self.scope(3)
+        );
+        conn.execute(&query, (amount, DateTime::now(), parent_id, start, end))?;
+        Ok(())
+    }
+
+    fn set_position(&self, conn: &Connection, id: i32, position: i32) -> Result<()> {
+        let query = format!(
+            "UPDATE {} SET position = ?1, updated_at = ?2 WHERE id = ?3",
+            self.table()
+        );
+        conn.execute(&query, (position, DateTime::now(), id))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::OrderedTable;
+
+    const CARDS: OrderedTable = OrderedTable::ProjectCard;
+
+    /// An in-memory database with two lists of cards, `1..=4` in list 1 and
+    /// `5..=6` in list 2.
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE project_card (
+                id INTEGER PRIMARY KEY,
+                list_id INTEGER NOT NULL,
+                position INTEGER NOT NULL,
+                archived INTEGER NOT NULL DEFAULT 0,
+                archived_at TEXT,
+                updated_at TEXT
+            );
+            INSERT INTO project_card (id, list_id, position) VALUES
+                (1, 1, 0), (2, 1, 1), (3, 1, 2), (4, 1, 3), (5, 2, 0), (6, 2, 1);",
+        )
+        .unwrap();
+        conn
+    }
+
+    /// The ids of the ordered cards of a list.
+    fn order(conn: &Connection, list_id: i32) -> Vec<i32> {
+        conn.prepare(
+            "SELECT id FROM project_card WHERE list_id = ?1 AND archived = 0 ORDER BY position",
+        )
+        .unwrap()
+        .query_map([list_id], |r| r.get(0))
+        .unwrap()
+        .collect::<rusqlite::Result<Vec<i32>>>()
+        .unwrap()
+    }
+
+    /// The positions of the ordered cards of a list, which should always be
+    /// numbered from 0 without gaps.
+    fn positions(conn: &Connection, list_id: i32) -> Vec<i32> {
+        order(conn, list_id)
+            .into_iter()
+            .map(|id| CARDS.get(conn, id).unwrap().1)
+            .collect()
+    }
+
+    #[test]
+    fn move_to_first_and_last_index() {
+        let conn = setup();
+
+        CARDS.move_to(&conn, 3, 0).unwrap();
+        assert_eq!(order(&conn, 1), [3, 1, 2, 4]);
+
+        CARDS.move_to(&conn, 3, 3).unwrap();
+        assert_eq!(order(&conn, 1), [1, 2, 4, 3]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn move_to_past_the_end_is_clamped() {
+        let conn = setup();
+
+        CARDS.move_to(&conn, 1, 10).unwrap();
+        assert_eq!(order(&conn, 1), [2, 3, 4, 1]);
+
+        CARDS.move_to(&conn, 1, -10).unwrap();
+        assert_eq!(order(&conn, 1), [1, 2, 3, 4]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2, 3]);
+        assert_eq!(order(&conn, 2), [5, 6]);
+    }
+
+    #[test]
+    fn move_by_offset() {
+        let conn = setup();
+
+        CARDS.move_by(&conn, 2, 1).unwrap();
+        assert_eq!(order(&conn, 1), [1, 3, 2, 4]);
+
+        CARDS.move_by(&conn, 4, -2).unwrap();
+        assert_eq!(order(&conn, 1), [1, 4, 3, 2]);
+
+        CARDS.move_by(&conn, 1, -1).unwrap();
+        assert_eq!(order(&conn, 1), [1, 4, 3, 2]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn move_to_parent_with_index() {
+        let conn = setup();
+
+        CARDS.move_to_parent(&conn, 2, 2, Some(1)).unwrap();
+        assert_eq!(order(&conn, 1), [1, 3, 4]);
+        assert_eq!(order(&conn, 2), [5, 2, 6]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2]);
+        assert_eq!(positions(&conn, 2), [0, 1, 2]);
+    }
+
+    #[test]
+    fn move_to_parent_without_index() {
+        let conn = setup();
+
+        CARDS.move_to_parent(&conn, 1, 2, None).unwrap();
+        assert_eq!(order(&conn, 1), [2, 3, 4]);
+        assert_eq!(order(&conn, 2), [5, 6, 1]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2]);
+        assert_eq!(positions(&conn, 2), [0, 1, 2]);
+    }
+
+    #[test]
+    fn delete_and_renormalize() {
+        let conn = setup();
+
+        CARDS.delete(&conn, 2).unwrap();
+        assert_eq!(order(&conn, 1), [1, 3, 4]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2]);
+
+        // A gap and a duplicate, as left by rows that were edited elsewhere.
+        conn.execute("UPDATE project_card SET position = 5 WHERE id = 1", ())
+            .unwrap();
+        conn.execute("UPDATE project_card SET position = 2 WHERE id = 3", ())
+            .unwrap();
+        CARDS.renormalize(&conn, Some(1)).unwrap();
+        assert_eq!(order(&conn, 1), [3, 4, 1]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2]);
+    }
+
+    #[test]
+    fn archived_rows_are_taken_out_of_the_ordering() {
+        let conn = setup();
+
+        CARDS.archive(&conn, 2).unwrap();
+        assert!(CARDS.is_archived(&conn, 2).unwrap());
+        assert_eq!(order(&conn, 1), [1, 3, 4]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2]);
+        assert_eq!(CARDS.count(&conn, Some(1)).unwrap(), 3);
+        assert_eq!(CARDS.next_position(&conn, Some(1)).unwrap(), 3);
+
+        CARDS.move_to(&conn, 1, 10).unwrap();
+        assert_eq!(order(&conn, 1), [3, 4, 1]);
+
+        CARDS.unarchive(&conn, 2).unwrap();
+        assert_eq!(order(&conn, 1), [3, 4, 1, 2]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2, 3]);
+
+        CARDS.archive(&conn, 4).unwrap();
+        CARDS.delete(&conn, 4).unwrap();
+        assert_eq!(order(&conn, 1), [3, 1, 2]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2]);
+    }
+
+    #[test]
+    fn archived_rows_are_not_moved() {
+        let conn = setup();
+
+        // The only card of its list, so the scope is empty once it's archived.
+        CARDS.move_to_parent(&conn, 6, 3, None).unwrap();
+        CARDS.archive(&conn, 6).unwrap();
+        assert_eq!(CARDS.count(&conn, Some(3)).unwrap(), 0);
+        CARDS.move_to(&conn, 6, 1).unwrap();
+        CARDS.move_by(&conn, 6, -1).unwrap();
+        assert_eq!(CARDS.get(&conn, 6).unwrap(), (Some(3), 0));
+
+        CARDS.archive(&conn, 2).unwrap();
+        CARDS.move_to_parent(&conn, 2, 2, Some(0)).unwrap();
+        assert_eq!(CARDS.get(&conn, 2).unwrap().0, Some(2));
+        assert_eq!(order(&conn, 1), [1, 3, 4]);
+        assert_eq!(positions(&conn, 1), [0, 1, 2]);
+        assert_eq!(order(&conn, 2), [5]);
+
+        CARDS.unarchive(&conn, 2).unwrap();
+        assert_eq!(order(&conn, 2), [5, 2]);
+        assert_eq!(positions(&conn, 2), [0, 1]);
+    }
+}
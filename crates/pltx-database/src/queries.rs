@@ -0,0 +1,21 @@
+//! Query helpers that are shared by [`Database`](crate::Database) and
+//! [`Transaction`](crate::Transaction), so they can be executed on a pooled
+//! connection or as part of a transaction.
+
+use color_eyre::Result;
+use rusqlite::{Connection, Params};
+
+pub(crate) fn execute<P: Params>(conn: &Connection, query: &str, params: P) -> Result<usize> {
+    let mut stmt = conn.prepare(query)?;
+    Ok(stmt.execute(params)?)
+}
+
+pub(crate) fn last_row_id(conn: &Connection, table: &str) -> Result<i32> {
+    let query = format!(
+        "SELECT id from {} WHERE id = (SELECT MAX(id) FROM {})",
+        table, table
+    );
+    let mut stmt = conn.prepare(&query)?;
+    let recent_id: i32 = stmt.query_row((), |r| r.get(0))?;
+    Ok(recent_id)
+}
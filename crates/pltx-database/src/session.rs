@@ -0,0 +1,274 @@
+//! Tracks how long the application is used. A session is split into segments
+//! where the user is either active or idle, so only the active segments count
+//! as usage. The user becomes idle when the terminal loses focus or no key has
+//! been pressed for the idle timeout, and active again on the next key press.
+//!
+//! The end of the session is updated by a heartbeat from the tick loop. A
+//! session that is still running when the next one starts, without a recent
+//! heartbeat, ended without being closed and is marked as crashed.
+
+use std::time::{Duration, Instant};
+
+use color_eyre::Result;
+use pltx_utils::{DateTime, DurationSince};
+use tracing::info;
+
+use crate::Database;
+
+/// How often the end of the running session is updated.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of seconds without a heartbeat after which a running session is
+/// assumed to have crashed.
+const CRASH_THRESHOLD: i64 = 10;
+
+/// How a session ended.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SessionStatus {
+    Running,
+    Ended,
+    Crashed,
+}
+
+impl SessionStatus {
+    fn from_db(status: &str) -> Self {
+        match status {
+            "running" => SessionStatus::Running,
+            "crashed" => SessionStatus::Crashed,
+            _ => SessionStatus::Ended,
+        }
+    }
+}
+
+/// A session that has been recorded in the database.
+pub struct SessionSummary {
+    pub id: i32,
+    pub started: DateTime,
+    pub ended: DateTime,
+    pub status: SessionStatus,
+    /// The time the user was active during the session.
+    pub active: DurationSince,
+}
+
+/// The state of the running session.
+pub(crate) struct Session {
+    id: i32,
+    segment_id: i32,
+    segment_started: DateTime,
+    active: bool,
+    /// The active time of the previous segments in milliseconds.
+    active_millis: i64,
+    last_activity: DateTime,
+    last_heartbeat: Instant,
+}
+
+impl Database {
+    /// Set the number of seconds without a key press after which the user is
+    /// idle. The user is never idle because of inactivity if it's 0.
+    pub fn idle_timeout(mut self, seconds: u64) -> Self {
+        self.idle_timeout = seconds;
+        self
+    }
+
+    /// Start a new session, which starts out active. Sessions that are still
+    /// running without a recent heartbeat are marked as crashed.
+    pub fn start_session(&mut self) -> Result<()> {
+        let start = Instant::now();
+        self.backup_automatic()?;
+        // Modules apply their own migrations when they are initialized.
+        self.migrate_core()?;
+
+        let started = DateTime::new();
+        let (session_id, segment_id) = self.transaction(|tx| {
+            let crashed = tx.execute(
+                "UPDATE session SET status = 'crashed' WHERE status = 'running' AND \
+                 julianday(ended) < julianday(?1, ?2)",
+                (started.into_db(), format!("-{} seconds", CRASH_THRESHOLD)),
+            )?;
+            if crashed > 0 {
+                info!("marked {} sessions as crashed", crashed);
+            }
+
+            tx.execute(
+                "INSERT INTO session (started, ended, status) VALUES (?1, ?1, 'running')",
+                [started.into_db()],
+            )?;
+            let session_id = tx.last_row_id("session")?;
+            tx.execute(
+                "INSERT INTO session_segment (session_id, active, started, ended) VALUES (?1, 1, \
+                 ?2, ?2)",
+                (session_id, started.into_db()),
+            )?;
+            Ok((session_id, tx.last_row_id("session_segment")?))
+        })?;
+
+        self.session = Some(Session {
+            id: session_id,
+            segment_id,
+            segment_started: started.clone(),
+            active: true,
+            active_millis: 0,
+            last_activity: started,
+            last_heartbeat: Instant::now(),
+        });
+        info!("started session in {:?}", start.elapsed());
+        Ok(())
+    }
+
+    /// Record that the user did something, e.g., pressed a key. Resumes the
+    /// session if the user was idle.
+    pub fn record_activity(&mut self) -> Result<()> {
+        let now = DateTime::new();
+        let Some(session) = self.session.as_mut() else {
+            return Ok(());
+        };
+        session.last_activity = now.clone();
+        if !session.active {
+            self.switch_segment(true, now)?;
+        }
+        Ok(())
+    }
+
+    /// Pause the session until the next activity, e.g., when the terminal
+    /// loses focus.
+    pub fn pause_session(&mut self) -> Result<()> {
+        if self.session.as_ref().is_some_and(|s| s.active) {
+            self.switch_segment(false, DateTime::new())?;
+        }
+        Ok(())
+    }
+
+    /// Update the end of the running session, at most once per heartbeat
+    /// interval. The user becomes idle from their last activity once the idle
+    /// timeout has passed.
+    pub fn sync_session(&mut self) -> Result<()> {
+        let idle_timeout = self.idle_timeout as i64;
+        let Some(session) = self.session.as_mut() else {
+            return Ok(());
+        };
+        if session.last_heartbeat.elapsed() < HEARTBEAT_INTERVAL {
+            return Ok(());
+        }
+        session.last_heartbeat = Instant::now();
+
+        let now = DateTime::new();
+        let inactive = now.datetime - session.last_activity.datetime;
+        if session.active && idle_timeout > 0 && inactive.num_seconds() >= idle_timeout {
+            let last_activity = session.last_activity.clone();
+            self.switch_segment(false, last_activity)?;
+        }
+
+        self.update_session_end(&now, None)
+    }
+
+    /// Close the running session when the application exits.
+    pub fn end_session(&mut self) -> Result<()> {
+        self.update_session_end(&DateTime::new(), Some("ended"))?;
+        if let Some(session) = self.session.take() {
+            info!("ended session {}", session.id);
+        }
+        Ok(())
+    }
+
+    /// The ID of the running session.
+    pub fn session_id(&self) -> Option<i32> {
+        self.session.as_ref().map(|s| s.id)
+    }
+
+    /// Whether the user is idle in the running session.
+    pub fn is_idle(&self) -> bool {
+        self.session.as_ref().is_some_and(|s| !s.active)
+    }
+
+    /// The time the user has been active in the running session.
+    pub fn session_duration(&self) -> Option<DurationSince> {
+        self.session.as_ref().map(|s| {
+            let current = if s.active {
+                (DateTime::new().datetime - s.segment_started.datetime).num_milliseconds()
+            } else {
+                0
+            };
+            DurationSince::from_seconds((s.active_millis + current) / 1000)
+        })
+    }
+
+    /// The most recent sessions, from newest to oldest.
+    pub fn sessions(&self, limit: i32) -> Result<Vec<SessionSummary>> {
+        let start = Instant::now();
+        let conn = self.conn();
+        let mut stmt = conn.prepare(
+            "SELECT s.id, s.started, s.ended, s.status, (SELECT \
+             CAST(ROUND(TOTAL(julianday(g.ended) - julianday(g.started)) * 86400) AS INTEGER) \
+             FROM session_segment g WHERE g.session_id = s.id AND g.active = 1) FROM session s \
+             ORDER BY s.started DESC LIMIT ?1",
+        )?;
+        let sessions = stmt
+            .query_map([limit], |r| {
+                Ok(SessionSummary {
+                    id: r.get(0)?,
+                    started: DateTime::from_db(r.get(1)?),
+                    ended: DateTime::from_db(r.get(2)?),
+                    status: SessionStatus::from_db(&r.get::<_, String>(3)?),
+                    active: DurationSince::from_seconds(r.get(4)?),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<SessionSummary>>>()?;
+        info!("get sessions query executed in {:?}", start.elapsed());
+        Ok(sessions)
+    }
+
+    /// End the current segment at a datetime and start a new one from it.
+    fn switch_segment(&mut self, active: bool, at: DateTime) -> Result<()> {
+        let Some(session) = self.session.as_ref() else {
+            return Ok(());
+        };
+        let (session_id, segment_id) = (session.id, session.segment_id);
+
+        let new_segment_id = self.transaction(|tx| {
+            tx.execute(
+                "UPDATE session_segment SET ended = ?1 WHERE id = ?2",
+                (at.into_db(), segment_id),
+            )?;
+            tx.execute(
+                "INSERT INTO session_segment (session_id, active, started, ended) VALUES (?1, ?2, \
+                 ?3, ?3)",
+                (session_id, active, at.into_db()),
+            )?;
+            tx.last_row_id("session_segment")
+        })?;
+
+        if let Some(session) = self.session.as_mut() {
+            if session.active {
+                session.active_millis +=
+                    (at.datetime - session.segment_started.datetime).num_milliseconds();
+            }
+            session.segment_id = new_segment_id;
+            session.segment_started = at;
+            session.active = active;
+        }
+        info!(
+            "session {} is {}",
+            session_id,
+            if active { "active" } else { "idle" }
+        );
+        Ok(())
+    }
+
+    /// Update the end of the running session and its current segment, and
+    /// optionally its status.
+    fn update_session_end(&self, now: &DateTime, status: Option<&str>) -> Result<()> {
+        let Some(session) = self.session.as_ref() else {
+            return Ok(());
+        };
+        self.transaction(|tx| {
+            tx.execute(
+                "UPDATE session_segment SET ended = ?1 WHERE id = ?2",
+                (now.into_db(), session.segment_id),
+            )?;
+            tx.execute(
+                "UPDATE session SET ended = ?1, status = COALESCE(?2, status) WHERE id = ?3",
+                (now.into_db(), status, session.id),
+            )?;
+            Ok(())
+        })
+    }
+}
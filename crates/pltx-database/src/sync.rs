@@ -0,0 +1,467 @@
+//! Records the changes made to the tracked tables as row-level changes, so
+//! they can be synced with other devices. Every row has a UUID that is the
+//! same on all devices, and a version made of a logical clock and the ID of
+//! the device that changed it last. Triggers bump the clock of this device and
+//! mark the row as pending whenever it changes, and the pending rows are sent
+//! as a change set on the next sync.
+//!
+//! A change from another device is only applied if its version is newer than
+//! the version of the row, so concurrent edits of the same card resolve the
+//! same way on every device: the higher clock wins, and the higher device ID
+//! breaks a tie. Applying a change moves the clock of this device past the
+//! clock of the change, so the next local edit wins over everything that has
+//! been seen.
+//!
+//! A change that references a row which hasn't been received yet, e.g., a card
+//! that arrives before its list, is kept and applied again with the next
+//! change set, so the cursor can move past it without losing it.
+
+use std::{collections::HashMap, time::Instant};
+
+use color_eyre::Result;
+use pltx_utils::DateTime;
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{journal::table_columns, Database, Transaction};
+
+/// A change to a single row of a tracked table.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RowChange {
+    pub table: String,
+    pub uuid: String,
+    pub clock: i64,
+    pub device_id: String,
+    /// The row as a JSON object without the `id` column, where the foreign
+    /// keys are the UUIDs of the rows they reference. `None` if the row was
+    /// deleted.
+    pub row: Option<String>,
+}
+
+/// The sync state of this device.
+pub struct SyncState {
+    pub device_id: String,
+    /// The key of the sync space, if syncing has been set up.
+    pub key: Option<String>,
+    /// The sequence number of the last change set that has been received.
+    pub cursor: i64,
+    pub synced_at: Option<DateTime>,
+    /// The number of rows with changes that haven't been sent.
+    pub pending: usize,
+}
+
+/// What happened to a change from another device.
+enum Outcome {
+    Applied,
+    /// The row has a newer version, or the change is for a table that isn't
+    /// tracked on this device.
+    Ignored,
+    /// A row that the change references doesn't exist yet.
+    Deferred,
+}
+
+/// Create the triggers that record the changes to a table for syncing, and
+/// register the rows that don't have a UUID yet, e.g., the rows that existed
+/// before the table was tracked.
+pub(crate) fn track_sync_changes(tx: &Transaction, table: &str) -> Result<()> {
+    for (event, row, deleted) in [
+        ("INSERT", "NEW", 0),
+        ("UPDATE", "NEW", 0),
+        ("DELETE", "OLD", 1),
+    ] {
+        let name = format!("sync_{}_{}", table, event.to_lowercase());
+        tx.execute(&format!("DROP TRIGGER IF EXISTS {name}"), ())?;
+        tx.execute(
+            &format!(
+                "CREATE TRIGGER {name} AFTER {event} ON {table} WHEN (SELECT applying FROM \
+                 sync_state) = 0 BEGIN UPDATE sync_state SET clock = clock + 1; INSERT INTO \
+                 sync_row (uuid, table_name, row_id, clock, device_id, deleted, pending) VALUES \
+                 (lower(hex(randomblob(16))), '{table}', {row}.id, (SELECT clock FROM \
+                 sync_state), (SELECT device_id FROM sync_state), {deleted}, 1) ON CONFLICT \
+                 (table_name, row_id) DO UPDATE SET clock = excluded.clock, device_id = \
+                 excluded.device_id, deleted = excluded.deleted, pending = 1; END"
+            ),
+            (),
+        )?;
+    }
+
+    tx.execute(
+        &format!(
+            "INSERT INTO sync_row (uuid, table_name, row_id, clock, device_id, deleted, pending) \
+             SELECT lower(hex(randomblob(16))), '{table}', id, (SELECT clock FROM sync_state), \
+             (SELECT device_id FROM sync_state), 0, 1 FROM {table} WHERE id NOT IN (SELECT row_id \
+             FROM sync_row WHERE table_name = '{table}' AND row_id IS NOT NULL)"
+        ),
+        (),
+    )?;
+
+    Ok(())
+}
+
+impl Database {
+    pub fn sync_state(&self) -> Result<SyncState> {
+        let conn = self.conn();
+        let pending =
+            conn.query_row("SELECT count(*) FROM sync_row WHERE pending = 1", (), |r| {
+                r.get(0)
+            })?;
+        let state = conn.query_row(
+            "SELECT device_id, key, cursor, synced_at FROM sync_state",
+            (),
+            |r| {
+                Ok(SyncState {
+                    device_id: r.get(0)?,
+                    key: r.get(1)?,
+                    cursor: r.get(2)?,
+                    synced_at: DateTime::from_db_option(r.get(3)?),
+                    pending,
+                })
+            },
+        )?;
+        Ok(state)
+    }
+
+    /// Set the key of the sync space to join, or `None` to stop syncing. All
+    /// change sets are received again when the key changes.
+    pub fn set_sync_key(&self, key: Option<&str>) -> Result<()> {
+        self.execute(
+            "UPDATE sync_state SET cursor = CASE WHEN key IS ?1 THEN cursor ELSE 0 END, key = ?1",
+            [key],
+        )?;
+        Ok(())
+    }
+
+    /// The rows that have changed since they were last sent, in the order
+    /// they were changed.
+    pub fn pending_changes(&self) -> Result<Vec<RowChange>> {
+        let start = Instant::now();
+        let conn = self.conn();
+
+        let mut stmt = conn.prepare(
+            "SELECT table_name, row_id, uuid, clock, device_id, deleted FROM sync_row WHERE \
+             pending = 1 ORDER BY clock",
+        )?;
+        let rows = stmt
+            .query_map((), |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, Option<i32>>(1)?,
+                    r.get::<_, String>(2)?,
+                    r.get::<_, i64>(3)?,
+                    r.get::<_, String>(4)?,
+                    r.get::<_, bool>(5)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut image_queries = HashMap::new();
+        let mut changes = vec![];
+        for (table, row_id, uuid, clock, device_id, deleted) in rows {
+            let row = match row_id {
+                Some(row_id) if !deleted => {
+                    if !image_queries.contains_key(&table) {
+                        image_queries.insert(table.clone(), image_query(&conn, &table)?);
+                    }
+                    conn.query_row(&image_queries[&table], [row_id], |r| r.get(0))
+                        .optional()?
+                }
+                _ => None,
+            };
+            changes.push(RowChange {
+                table,
+                uuid,
+                clock,
+                device_id,
+                row,
+            });
+        }
+
+        info!(
+            "pending sync changes query executed in {:?}",
+            start.elapsed()
+        );
+        Ok(changes)
+    }
+
+    /// Mark the changes as sent. Rows that have changed again since keep
+    /// their newer version and stay pending.
+    pub fn mark_synced(&self, changes: &[RowChange]) -> Result<()> {
+        self.transaction(|tx| {
+            for change in changes {
+                tx.execute(
+                    "UPDATE sync_row SET pending = 0 WHERE uuid = ?1 AND clock = ?2 AND device_id \
+                     = ?3",
+                    (&change.uuid, change.clock, &change.device_id),
+                )?;
+            }
+            tx.execute("UPDATE sync_state SET synced_at = ?1", [DateTime::now()])?;
+            Ok(())
+        })
+    }
+
+    /// Apply a change set from another device and move the cursor to its
+    /// sequence number. The changes that were deferred by earlier change sets
+    /// are applied again first. Returns the number of rows that were changed.
+    pub fn apply_change_set(&self, seq: i64, changes: &[RowChange]) -> Result<usize> {
+        let start = Instant::now();
+
+        let applied = self.transaction(|tx| {
+            tx.execute("UPDATE sync_state SET applying = 1", ())?;
+
+            let deferred = deferred_changes(tx.conn())?;
+
+            // A row may be changed before the rows it references, so those
+            // changes are applied again once the rest have been applied.
+            let mut applied = 0;
+            let mut remaining = deferred.iter().chain(changes).collect::<Vec<&RowChange>>();
+            loop {
+                let count = remaining.len();
+                let mut deferred = vec![];
+                for change in remaining {
+                    match apply_change(tx.conn(), change)? {
+                        Outcome::Applied => applied += 1,
+                        Outcome::Ignored => {}
+                        Outcome::Deferred => deferred.push(change),
+                    }
+                }
+                remaining = deferred;
+                if remaining.is_empty() || remaining.len() == count {
+                    break;
+                }
+            }
+            defer_changes(tx.conn(), &remaining)?;
+            if !remaining.is_empty() {
+                info!(
+                    "deferred {} sync changes until the rows they reference are received",
+                    remaining.len()
+                );
+            }
+
+            let clock = changes.iter().map(|c| c.clock).max().unwrap_or(0);
+            tx.execute(
+                "UPDATE sync_state SET applying = 0, clock = max(clock, ?1), cursor = ?2",
+                (clock, seq),
+            )?;
+            Ok(applied)
+        })?;
+
+        info!(
+            "applied {} of {} sync changes in {:?}",
+            applied,
+            changes.len(),
+            start.elapsed()
+        );
+        Ok(applied)
+    }
+}
+
+/// The changes that couldn't be applied yet, in the order they were received.
+fn deferred_changes(conn: &Connection) -> Result<Vec<RowChange>> {
+    let mut stmt = conn.prepare(
+        "SELECT table_name, uuid, clock, device_id, row FROM sync_deferred ORDER BY rowid",
+    )?;
+    let changes = stmt
+        .query_map((), |r| {
+            Ok(RowChange {
+                table: r.get(0)?,
+                uuid: r.get(1)?,
+                clock: r.get(2)?,
+                device_id: r.get(3)?,
+                row: r.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<RowChange>>>()?;
+    Ok(changes)
+}
+
+/// Replace the deferred changes with the changes that still couldn't be
+/// applied. Only the newest change to a row is kept.
+fn defer_changes(conn: &Connection, changes: &[&RowChange]) -> Result<()> {
+    conn.execute("DELETE FROM sync_deferred", ())?;
+    for change in changes {
+        conn.execute(
+            "INSERT INTO sync_deferred (uuid, table_name, clock, device_id, row) VALUES (?1, ?2, \
+             ?3, ?4, ?5) ON CONFLICT (uuid) DO UPDATE SET table_name = excluded.table_name, clock \
+             = excluded.clock, device_id = excluded.device_id, row = excluded.row WHERE \
+             (excluded.clock, excluded.device_id) > (clock, device_id)",
+            (
+                &change.uuid,
+                &change.table,
+                change.clock,
+                &change.device_id,
+                &change.row,
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+/// The foreign keys of a table, as the column and the table it references.
+fn foreign_keys(conn: &Connection, table: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare("SELECT \"from\", \"table\" FROM pragma_foreign_key_list(?1)")?;
+    let keys = stmt
+        .query_map([table], |r| Ok((r.get(0)?, r.get(1)?)))?
+        .collect::<rusqlite::Result<Vec<(String, String)>>>()?;
+    Ok(keys)
+}
+
+/// A query for the image of a row that is sent to other devices.
+fn image_query(conn: &Connection, table: &str) -> Result<String> {
+    let foreign_keys = foreign_keys(conn, table)?;
+    let pairs = table_columns(conn, table)?
+        .iter()
+        .filter(|c| c.as_str() != "id")
+        .map(|c| match foreign_keys.iter().find(|(from, _)| from == c) {
+            Some((_, parent)) => format!(
+                "'{c}', (SELECT uuid FROM sync_row WHERE table_name = '{parent}' AND row_id = \
+                 t.\"{c}\")"
+            ),
+            None => format!("'{c}', t.\"{c}\""),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    Ok(format!(
+        "SELECT json_object({pairs}) FROM {table} t WHERE t.id = ?1"
+    ))
+}
+
+fn apply_change(conn: &Connection, change: &RowChange) -> Result<Outcome> {
+    let table = change.table.as_str();
+    let tracked = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'trigger' AND name = ?1)",
+        [format!("sync_{table}_insert")],
+        |r| r.get::<_, bool>(0),
+    )?;
+    if !tracked {
+        return Ok(Outcome::Ignored);
+    }
+
+    let local = conn
+        .query_row(
+            "SELECT row_id, clock, device_id FROM sync_row WHERE uuid = ?1",
+            [&change.uuid],
+            |r| {
+                Ok((
+                    r.get::<_, Option<i32>>(0)?,
+                    r.get::<_, i64>(1)?,
+                    r.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()?;
+    if let Some((_, clock, device_id)) = &local {
+        if (change.clock, &change.device_id) <= (*clock, device_id) {
+            return Ok(Outcome::Ignored);
+        }
+    }
+    let row_id = local.and_then(|(row_id, _, _)| row_id);
+
+    let row_id = match &change.row {
+        Some(image) => {
+            // The foreign keys are resolved to the rows on this device.
+            let foreign_keys = foreign_keys(conn, table)?;
+            let mut values = HashMap::new();
+            for (column, parent) in &foreign_keys {
+                let uuid: Option<String> = conn.query_row(
+                    "SELECT json_extract(?1, ?2)",
+                    (image, format!("$.\"{column}\"")),
+                    |r| r.get(0),
+                )?;
+                let Some(uuid) = uuid else {
+                    continue;
+                };
+                let parent_id: Option<i32> = conn
+                    .query_row(
+                        &format!(
+                            "SELECT s.row_id FROM sync_row s JOIN {parent} p ON p.id = s.row_id \
+                             WHERE s.uuid = ?1"
+                        ),
+                        [&uuid],
+                        |r| r.get(0),
+                    )
+                    .optional()?;
+                match parent_id {
+                    Some(parent_id) => values.insert(column.clone(), parent_id),
+                    None => return Ok(Outcome::Deferred),
+                };
+            }
+
+            // Columns that the other device doesn't have, e.g., because it
+            // hasn't been migrated yet, are left as they are.
+            let mut stmt = conn.prepare("SELECT key FROM json_each(?1)")?;
+            let keys = stmt
+                .query_map([image], |r| r.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+            let columns = table_columns(conn, table)?
+                .into_iter()
+                .filter(|c| c != "id" && keys.contains(c))
+                .collect::<Vec<String>>();
+            let value = |c: &String| match values.get(c) {
+                Some(id) => id.to_string(),
+                None => format!("json_extract(?1, '$.\"{c}\"')"),
+            };
+
+            let exists = match row_id {
+                Some(row_id) => conn.query_row(
+                    &format!("SELECT EXISTS (SELECT 1 FROM {table} WHERE id = ?1)"),
+                    [row_id],
+                    |r| r.get::<_, bool>(0),
+                )?,
+                None => false,
+            };
+            if exists {
+                let assignments = columns
+                    .iter()
+                    .map(|c| format!("\"{c}\" = {}", value(c)))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                conn.execute(
+                    &format!("UPDATE {table} SET {assignments} WHERE id = ?2"),
+                    (image, row_id),
+                )?;
+                row_id
+            } else {
+                // A row that was deleted on this device gets its old ID back.
+                let names = columns
+                    .iter()
+                    .map(|c| format!("\"{c}\""))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                let values = columns
+                    .iter()
+                    .map(value)
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                conn.execute(
+                    &format!("INSERT INTO {table} (id, {names}) VALUES (?2, {values})"),
+                    (image, row_id),
+                )?;
+                Some(conn.last_insert_rowid() as i32)
+            }
+        }
+        None => {
+            if let Some(row_id) = row_id {
+                conn.execute(&format!("DELETE FROM {table} WHERE id = ?1"), [row_id])?;
+            }
+            row_id
+        }
+    };
+
+    conn.execute(
+        "INSERT INTO sync_row (uuid, table_name, row_id, clock, device_id, deleted, pending) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0) ON CONFLICT (uuid) DO UPDATE SET row_id = \
+         excluded.row_id, clock = excluded.clock, device_id = excluded.device_id, deleted = \
+         excluded.deleted, pending = 0",
+        (
+            &change.uuid,
+            table,
+            row_id,
+            change.clock,
+            &change.device_id,
+            change.row.is_none(),
+        ),
+    )?;
+
+    Ok(Outcome::Applied)
+}
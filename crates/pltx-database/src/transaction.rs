@@ -0,0 +1,59 @@
+use color_eyre::Result;
+use rusqlite::{Connection, Params};
+
+use crate::{queries, Database};
+
+/// A transaction that is passed to the closure of [`Database::transaction`].
+/// Provides the same query helpers as [`Database`], except every query is
+/// executed as part of the transaction.
+pub struct Transaction<'conn> {
+    tx: rusqlite::Transaction<'conn>,
+}
+
+impl Database {
+    /// Execute multiple statements as a single atomic operation. The
+    /// transaction is committed if the closure returns `Ok`, otherwise it's
+    /// rolled back and the error is returned.
+    ///
+    /// ```ignore
+    /// db.transaction(|tx| {
+    ///     OrderedTable::ProjectCard.move_to_parent(tx.conn(), card_id, list_id, None)?;
+    ///     OrderedTable::ProjectList.delete(tx.conn(), old_list_id)
+    /// })?;
+    /// ```
+    pub fn transaction<T, F>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Transaction) -> Result<T>,
+    {
+        let mut conn = self.conn();
+        let tx = Transaction {
+            tx: conn.transaction()?,
+        };
+
+        match f(&tx) {
+            Ok(value) => {
+                tx.tx.commit()?;
+                Ok(value)
+            }
+            Err(err) => {
+                tx.tx.rollback()?;
+                Err(err)
+            }
+        }
+    }
+}
+
+impl Transaction<'_> {
+    /// Access the connection of the transaction.
+    pub fn conn(&self) -> &Connection {
+        &self.tx
+    }
+
+    pub fn execute<P: Params>(&self, query: &str, params: P) -> Result<usize> {
+        queries::execute(&self.tx, query, params)
+    }
+
+    pub fn last_row_id(&self, table: &str) -> Result<i32> {
+        queries::last_row_id(&self.tx, table)
+    }
+}
@@ -0,0 +1,76 @@
+//! Detects changes made to the tracked tables by another connection, e.g.,
+//! another instance of pltx with the same database open, so the screens can be
+//! reloaded. `PRAGMA data_version` of a dedicated connection changes whenever
+//! another connection commits, which is cheap enough to check on every tick.
+//! As the session heartbeats commit too, the change counter that the tracked
+//! tables increment is then read to see whether anything that's shown changed.
+//! The changes that this instance makes are subtracted, as the screens are
+//! reloaded after those already.
+
+use color_eyre::Result;
+use r2d2::PooledConnection;
+use r2d2_sqlite::SqliteConnectionManager;
+use tracing::info;
+
+use crate::Database;
+
+/// The connection that is watched for changes.
+pub(crate) struct Watch {
+    conn: PooledConnection<SqliteConnectionManager>,
+    data_version: i64,
+    changes: i64,
+}
+
+impl Watch {
+    fn data_version(conn: &rusqlite::Connection) -> Result<i64> {
+        Ok(conn.query_row("PRAGMA data_version", (), |r| r.get(0))?)
+    }
+
+    pub(crate) fn changes(conn: &rusqlite::Connection) -> Result<i64> {
+        Ok(conn.query_row("SELECT changes FROM change_counter", (), |r| r.get(0))?)
+    }
+}
+
+impl Database {
+    /// Count the changes that a transaction of this instance made to the
+    /// tracked tables, so they aren't reported by
+    /// [`poll_changes`](Database::poll_changes). The changes are counted
+    /// after the transaction has been committed.
+    pub(crate) fn record_own_changes(&self, changes: i64) {
+        self.own_changes.set(self.own_changes.get() + changes);
+    }
+
+    /// Whether the tracked tables have been changed by another connection
+    /// since the last call, e.g., by another instance or a sync. The changes
+    /// made with [`undoable`](Database::undoable), undo, and redo are not
+    /// included. The first call starts watching and always returns false.
+    pub fn poll_changes(&mut self) -> Result<bool> {
+        let Some(watch) = &mut self.watch else {
+            let conn = self.pool.get()?;
+            self.watch = Some(Watch {
+                data_version: Watch::data_version(&conn)?,
+                changes: Watch::changes(&conn)?,
+                conn,
+            });
+            self.own_changes.set(0);
+            return Ok(false);
+        };
+
+        let data_version = Watch::data_version(&watch.conn)?;
+        if data_version == watch.data_version {
+            return Ok(false);
+        }
+        watch.data_version = data_version;
+
+        let changes = Watch::changes(&watch.conn)?;
+        let own_changes = self.own_changes.replace(0);
+        let other_changes = changes - watch.changes - own_changes;
+        watch.changes = changes;
+        if other_changes == 0 {
+            return Ok(false);
+        }
+
+        info!("the database has changed");
+        Ok(true)
+    }
+}
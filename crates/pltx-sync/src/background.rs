@@ -0,0 +1,105 @@
+use std::{
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{eyre::eyre, Result};
+use pltx_database::Database;
+use tracing::warn;
+
+use crate::{exchange, finish, prepare, Incoming, Outgoing};
+
+/// The state of the background sync.
+#[derive(Clone, PartialEq)]
+pub enum SyncStatus {
+    /// No server has been configured, or syncing hasn't been set up.
+    Disabled,
+    /// The first sync hasn't finished yet.
+    Waiting,
+    /// The last sync succeeded.
+    Connected,
+    /// The last sync failed, e.g., because the server can't be reached.
+    Failed(String),
+}
+
+/// Syncs with the server periodically while the application is running. Only
+/// the network requests run on another thread; the changes are read and
+/// applied on the thread that ticks.
+pub struct BackgroundSync {
+    address: String,
+    interval: Duration,
+    last_sync: Option<Instant>,
+    /// Receives the response of the server when a sync is running.
+    running: Option<Receiver<(Outgoing, Result<Incoming>)>>,
+    pub status: SyncStatus,
+}
+
+impl BackgroundSync {
+    /// Sync with the server at the address every interval. Syncing is disabled
+    /// if the address is empty.
+    pub fn new(address: &str, interval: Duration) -> BackgroundSync {
+        BackgroundSync {
+            address: address.to_string(),
+            interval,
+            last_sync: None,
+            running: None,
+            status: SyncStatus::Disabled,
+        }
+    }
+
+    /// Start a sync when one is due, and finish the running sync when the
+    /// server has responded. A sync that fails is retried after the interval,
+    /// whether the server can't be reached or the changes can't be applied,
+    /// e.g., because another instance is writing to the database. Errors
+    /// reading the pending changes are returned. The screens are reloaded by
+    /// the change watch of the database when changes from other devices are
+    /// applied.
+    pub fn tick(&mut self, db: &Database) -> Result<()> {
+        if let Some(receiver) = &self.running {
+            let result = match receiver.try_recv() {
+                Ok((outgoing, incoming)) => incoming.map(|incoming| (outgoing, incoming)),
+                Err(TryRecvError::Empty) => return Ok(()),
+                Err(TryRecvError::Disconnected) => {
+                    Err(eyre!("the sync thread stopped unexpectedly"))
+                }
+            };
+            self.running = None;
+            match result.and_then(|(outgoing, incoming)| finish(db, outgoing, incoming)) {
+                Ok(_) => self.status = SyncStatus::Connected,
+                Err(err) => {
+                    warn!("background sync failed: {}", err);
+                    self.status = SyncStatus::Failed(err.to_string());
+                }
+            }
+            return Ok(());
+        }
+
+        if self.address.is_empty()
+            || self
+                .last_sync
+                .is_some_and(|last_sync| last_sync.elapsed() < self.interval)
+        {
+            return Ok(());
+        }
+        self.last_sync = Some(Instant::now());
+
+        let Some(outgoing) = prepare(db)? else {
+            self.status = SyncStatus::Disabled;
+            return Ok(());
+        };
+        if self.status == SyncStatus::Disabled {
+            self.status = SyncStatus::Waiting;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        let address = self.address.clone();
+        thread::spawn(move || {
+            let incoming = exchange(&address, &outgoing);
+            // The receiver is gone if the application has exited.
+            let _ = sender.send((outgoing, incoming));
+        });
+        self.running = Some(receiver);
+        Ok(())
+    }
+}
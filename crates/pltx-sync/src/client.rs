@@ -0,0 +1,104 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use color_eyre::{eyre::eyre, Result};
+
+use crate::protocol::{from_hex, to_hex, Request, Response, StoredChangeSet, PULL_LIMIT};
+
+/// How long to wait for the server before giving up.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A connection to a sync server.
+pub struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    pub fn connect(address: &str) -> Result<Client> {
+        let socket_address = address
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| eyre!("failed to resolve the sync server address {}", address))?;
+        let stream = TcpStream::connect_timeout(&socket_address, TIMEOUT)
+            .map_err(|e| eyre!("failed to connect to the sync server at {}: {}", address, e))?;
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        Ok(Client {
+            reader: BufReader::new(stream.try_clone()?),
+            writer: stream,
+        })
+    }
+
+    fn request(&mut self, request: &Request) -> Result<Response> {
+        let mut line = serde_json::to_string(request)?;
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(eyre!("the sync server closed the connection"));
+        }
+        match serde_json::from_str(&line)? {
+            Response::Error { message } => Err(eyre!("the sync server failed: {}", message)),
+            response => Ok(response),
+        }
+    }
+
+    /// Store an encrypted change set. Returns its sequence number.
+    pub fn push(&mut self, space: &str, device_id: &str, data: &[u8]) -> Result<i64> {
+        let request = Request::Push {
+            space: space.to_string(),
+            device_id: device_id.to_string(),
+            data: to_hex(data),
+        };
+        match self.request(&request)? {
+            Response::Pushed { seq } => Ok(seq),
+            response => Err(eyre!(
+                "unexpected response from the sync server: {:?}",
+                response
+            )),
+        }
+    }
+
+    /// Get all encrypted change sets of the other devices after a sequence
+    /// number, in order.
+    pub fn pull(
+        &mut self,
+        space: &str,
+        device_id: &str,
+        after: i64,
+    ) -> Result<Vec<(i64, Vec<u8>)>> {
+        let mut change_sets = vec![];
+        let mut after = after;
+        loop {
+            let request = Request::Pull {
+                space: space.to_string(),
+                device_id: device_id.to_string(),
+                after,
+            };
+            let page = match self.request(&request)? {
+                Response::Pulled { change_sets } => change_sets,
+                response => {
+                    return Err(eyre!(
+                        "unexpected response from the sync server: {:?}",
+                        response
+                    ))
+                }
+            };
+            let count = page.len() as i64;
+            for StoredChangeSet { seq, data } in page {
+                let data = from_hex(&data)
+                    .ok_or_else(|| eyre!("the sync server sent an invalid change set"))?;
+                after = seq;
+                change_sets.push((seq, data));
+            }
+            if count < PULL_LIMIT {
+                return Ok(change_sets);
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Key, XChaCha20Poly1305, XNonce,
+};
+use color_eyre::{eyre::eyre, Result};
+
+use crate::protocol::{from_hex, to_hex};
+
+/// The length of the ID of a space in bytes.
+const SPACE_LEN: usize = 16;
+/// The length of an XChaCha20-Poly1305 nonce in bytes.
+const NONCE_LEN: usize = 24;
+
+/// The key that is shared by the devices that sync with each other. It's made
+/// of the ID of the space on the server and the secret that the change sets
+/// are encrypted with. Only the ID of the space is sent to the server, so the
+/// server can't read the change sets.
+pub struct SyncKey {
+    space: String,
+    secret: Key,
+}
+
+impl SyncKey {
+    /// Generate a key for a new space.
+    pub fn generate() -> SyncKey {
+        let mut space = [0; SPACE_LEN];
+        OsRng.fill_bytes(&mut space);
+        SyncKey {
+            space: to_hex(&space),
+            secret: XChaCha20Poly1305::generate_key(&mut OsRng),
+        }
+    }
+
+    /// Parse a key in the format shown by [`SyncKey`]'s `Display`
+    /// implementation, e.g., from `pltx sync key`.
+    pub fn parse(key: &str) -> Result<SyncKey> {
+        let invalid = || eyre!("the sync key is invalid");
+        let (space, secret) = key.trim().split_once('-').ok_or_else(invalid)?;
+        let space_bytes = from_hex(space).ok_or_else(invalid)?;
+        let secret = from_hex(secret).ok_or_else(invalid)?;
+        if space_bytes.len() != SPACE_LEN || secret.len() != Key::default().len() {
+            return Err(invalid());
+        }
+        Ok(SyncKey {
+            space: space.to_lowercase(),
+            secret: *Key::from_slice(&secret),
+        })
+    }
+
+    /// The ID of the space on the server.
+    pub fn space(&self) -> &str {
+        &self.space
+    }
+
+    /// Encrypt a change set. The nonce is prepended to the ciphertext, and the
+    /// ID of the space is authenticated, so a change set can't be moved to
+    /// another space.
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let cipher = XChaCha20Poly1305::new(&self.secret);
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let payload = chacha20poly1305::aead::Payload {
+            msg: plaintext,
+            aad: self.space.as_bytes(),
+        };
+        let ciphertext = cipher
+            .encrypt(&nonce, payload)
+            .map_err(|_| eyre!("failed to encrypt the change set"))?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    /// Decrypt a change set that was encrypted with [`SyncKey::encrypt`].
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if data.len() < NONCE_LEN {
+            return Err(eyre!("the change set is too short"));
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let cipher = XChaCha20Poly1305::new(&self.secret);
+        let payload = chacha20poly1305::aead::Payload {
+            msg: ciphertext,
+            aad: self.space.as_bytes(),
+        };
+        cipher
+            .decrypt(XNonce::from_slice(nonce), payload)
+            .map_err(|_| eyre!("failed to decrypt a change set; the sync key may be wrong"))
+    }
+}
+
+impl fmt::Display for SyncKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.space, to_hex(&self.secret))
+    }
+}
@@ -0,0 +1,109 @@
+//! Syncs the data of a profile between devices through a sync server. The
+//! changes to the tracked tables are recorded by the database as row-level
+//! changes with a logical clock, see [`pltx_database::RowChange`]. A sync sends
+//! the pending changes as a change set that is encrypted with the
+//! [`SyncKey`], and applies the change sets that the other devices have sent
+//! since the last sync.
+
+use color_eyre::{eyre::eyre, Result};
+use pltx_database::{Database, RowChange};
+use tracing::info;
+
+mod background;
+mod client;
+mod key;
+pub mod protocol;
+pub mod server;
+
+pub use background::{BackgroundSync, SyncStatus};
+pub use client::Client;
+pub use key::SyncKey;
+use protocol::ChangeSet;
+
+/// The result of a sync.
+#[derive(Default)]
+pub struct SyncReport {
+    /// The number of rows that were sent.
+    pub sent: usize,
+    /// The number of change sets that were received.
+    pub received: usize,
+    /// The number of rows that were changed by the received change sets.
+    pub applied: usize,
+}
+
+/// The pending changes of this device, read before the server is contacted.
+struct Outgoing {
+    key: SyncKey,
+    device_id: String,
+    cursor: i64,
+    changes: Vec<RowChange>,
+}
+
+/// The change sets that were received from the server.
+struct Incoming {
+    change_sets: Vec<(i64, ChangeSet)>,
+}
+
+/// Read the pending changes. Returns `None` if syncing hasn't been set up.
+fn prepare(db: &Database) -> Result<Option<Outgoing>> {
+    let state = db.sync_state()?;
+    let Some(key) = state.key else {
+        return Ok(None);
+    };
+    Ok(Some(Outgoing {
+        key: SyncKey::parse(&key)?,
+        device_id: state.device_id,
+        cursor: state.cursor,
+        changes: db.pending_changes()?,
+    }))
+}
+
+/// Send the pending changes and receive the change sets of the other devices.
+/// Doesn't access the database, so it can run on another thread.
+fn exchange(address: &str, outgoing: &Outgoing) -> Result<Incoming> {
+    let mut client = Client::connect(address)?;
+    let space = outgoing.key.space();
+
+    if !outgoing.changes.is_empty() {
+        let change_set = ChangeSet {
+            device_id: outgoing.device_id.clone(),
+            changes: outgoing.changes.clone(),
+        };
+        let data = outgoing.key.encrypt(&serde_json::to_vec(&change_set)?)?;
+        client.push(space, &outgoing.device_id, &data)?;
+    }
+
+    let mut change_sets = vec![];
+    for (seq, data) in client.pull(space, &outgoing.device_id, outgoing.cursor)? {
+        let change_set: ChangeSet = serde_json::from_slice(&outgoing.key.decrypt(&data)?)?;
+        change_sets.push((seq, change_set));
+    }
+    Ok(Incoming { change_sets })
+}
+
+/// Mark the sent changes as synced and apply the received change sets.
+fn finish(db: &Database, outgoing: Outgoing, incoming: Incoming) -> Result<SyncReport> {
+    db.mark_synced(&outgoing.changes)?;
+    let mut report = SyncReport {
+        sent: outgoing.changes.len(),
+        received: incoming.change_sets.len(),
+        applied: 0,
+    };
+    for (seq, change_set) in incoming.change_sets {
+        report.applied += db.apply_change_set(seq, &change_set.changes)?;
+    }
+    info!(
+        "synced: sent {} changes, received {} change sets, applied {} changes",
+        report.sent, report.received, report.applied
+    );
+    Ok(report)
+}
+
+/// Sync with the server at the address.
+pub fn sync(db: &Database, address: &str) -> Result<SyncReport> {
+    let outgoing = prepare(db)?.ok_or_else(|| {
+        eyre!("syncing hasn't been set up; run `pltx sync key` or `pltx sync join`")
+    })?;
+    let incoming = exchange(address, &outgoing)?;
+    finish(db, outgoing, incoming)
+}
@@ -0,0 +1,33 @@
+//! The reference pltx sync server.
+use std::path::PathBuf;
+
+use clap::Parser;
+use color_eyre::Result;
+use pltx_sync::{protocol::DEFAULT_ADDRESS, server::Server};
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "pltx-sync-server",
+    version,
+    about = "Store the encrypted change sets of pltx devices so they can sync with each other."
+)]
+struct Cli {
+    /// The address to listen on
+    #[arg(short, long, default_value = DEFAULT_ADDRESS)]
+    address: String,
+    /// The database file to store the change sets in
+    #[arg(short, long, default_value = "pltx-sync.db")]
+    database: PathBuf,
+}
+
+fn main() -> Result<()> {
+    color_eyre::install()?;
+    let cli = Cli::parse();
+    let server = Server::open(&cli.database)?;
+    println!(
+        "Listening on {}, storing change sets in {}",
+        cli.address,
+        cli.database.display()
+    );
+    server.serve(&cli.address)
+}
@@ -0,0 +1,69 @@
+//! The messages exchanged by the client and the server. Each message is a
+//! line of JSON, and the server sends one response for each request.
+
+use std::fmt::Write;
+
+use pltx_database::RowChange;
+use serde::{Deserialize, Serialize};
+
+/// The address that the server listens on by default.
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7341";
+/// The maximum number of change sets that are sent in one response.
+pub const PULL_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Request {
+    /// Store an encrypted change set.
+    Push {
+        space: String,
+        device_id: String,
+        data: String,
+    },
+    /// Get the change sets of the other devices after a sequence number.
+    Pull {
+        space: String,
+        device_id: String,
+        after: i64,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    Pushed { seq: i64 },
+    Pulled { change_sets: Vec<StoredChangeSet> },
+    Error { message: String },
+}
+
+/// An encrypted change set as it's stored on the server, where the data is
+/// hex encoded.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StoredChangeSet {
+    pub seq: i64,
+    pub data: String,
+}
+
+/// The changes that a device sends in a single sync, before it's encrypted.
+#[derive(Deserialize, Serialize)]
+pub struct ChangeSet {
+    pub device_id: String,
+    pub changes: Vec<RowChange>,
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+pub(crate) fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
@@ -0,0 +1,132 @@
+//! The reference sync server. It stores the encrypted change sets of each
+//! space in a SQLite database and sends them to the other devices of the
+//! space. The server can't read the change sets, so conflicts are resolved by
+//! the devices when they apply them.
+
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use color_eyre::Result;
+use pltx_utils::DateTime;
+use rusqlite::Connection;
+use tracing::{info, warn};
+
+use crate::protocol::{from_hex, to_hex, Request, Response, StoredChangeSet, PULL_LIMIT};
+
+pub struct Server {
+    conn: Mutex<Connection>,
+}
+
+impl Server {
+    /// Open the database of the server, creating it if it doesn't exist.
+    pub fn open(path: &Path) -> Result<Server> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS change_set (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                space TEXT NOT NULL,
+                device_id TEXT NOT NULL,
+                data BLOB NOT NULL,
+                created_at DATETIME NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_change_set_space ON change_set (space, seq);",
+        )?;
+        Ok(Server {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Listen for connections until the process is stopped. Each connection
+    /// is handled on its own thread.
+    pub fn serve(self, address: &str) -> Result<()> {
+        self.serve_listener(TcpListener::bind(address)?)
+    }
+
+    /// Handle the connections of a listener that has already been bound, e.g.,
+    /// to port 0 to let the system pick a free port.
+    pub fn serve_listener(self, listener: TcpListener) -> Result<()> {
+        info!("listening on {}", listener.local_addr()?);
+        let server = Arc::new(self);
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = Arc::clone(&server);
+            thread::spawn(move || {
+                if let Err(err) = server.handle_connection(stream) {
+                    warn!("connection failed: {}", err);
+                }
+            });
+        }
+        Ok(())
+    }
+
+    fn handle_connection(&self, stream: TcpStream) -> Result<()> {
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let response = match serde_json::from_str::<Request>(&line?) {
+                Ok(request) => self.handle(request).unwrap_or_else(|err| Response::Error {
+                    message: err.to_string(),
+                }),
+                Err(err) => Response::Error {
+                    message: format!("invalid request: {}", err),
+                },
+            };
+            let mut line = serde_json::to_string(&response)?;
+            line.push('\n');
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    fn handle(&self, request: Request) -> Result<Response> {
+        let conn = self
+            .conn
+            .lock()
+            .expect("failed to lock the server database");
+        match request {
+            Request::Push {
+                space,
+                device_id,
+                data,
+            } => {
+                let Some(data) = from_hex(&data) else {
+                    return Ok(Response::Error {
+                        message: String::from("the change set isn't hex encoded"),
+                    });
+                };
+                conn.execute(
+                    "INSERT INTO change_set (space, device_id, data, created_at) VALUES (?1, ?2, \
+                     ?3, ?4)",
+                    (&space, &device_id, data, DateTime::now()),
+                )?;
+                let seq = conn.last_insert_rowid();
+                info!("stored change set {} of space {}", seq, space);
+                Ok(Response::Pushed { seq })
+            }
+            Request::Pull {
+                space,
+                device_id,
+                after,
+            } => {
+                let mut stmt = conn.prepare(
+                    "SELECT seq, data FROM change_set WHERE space = ?1 AND device_id != ?2 AND \
+                     seq > ?3 ORDER BY seq LIMIT ?4",
+                )?;
+                let change_sets = stmt
+                    .query_map((&space, &device_id, after, PULL_LIMIT), |r| {
+                        Ok(StoredChangeSet {
+                            seq: r.get(0)?,
+                            data: to_hex(&r.get::<_, Vec<u8>>(1)?),
+                        })
+                    })?
+                    .collect::<rusqlite::Result<Vec<StoredChangeSet>>>()?;
+                Ok(Response::Pulled { change_sets })
+            }
+        }
+    }
+}
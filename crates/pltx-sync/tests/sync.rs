@@ -0,0 +1,197 @@
+//! Syncs temporary databases with each other through the reference sync server
+//! on localhost.
+
+use std::{env, fs, net::TcpListener, path::PathBuf, process, sync::Once, thread};
+
+use pltx_database::{Database, Migration, RowChange};
+use pltx_sync::{protocol::ChangeSet, server::Server, Client, SyncKey};
+use pltx_utils::dirs;
+
+/// A list and a card that references it, like the tables of the project
+/// management module.
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    name: "lists_and_cards",
+    sql: "CREATE TABLE project_list (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        title TEXT NOT NULL
+    );
+
+    CREATE TABLE project_card (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        list_id INTEGER NOT NULL,
+        title TEXT NOT NULL,
+        FOREIGN KEY (list_id)
+            REFERENCES project_list (id)
+                ON DELETE CASCADE
+    );",
+}];
+
+static DATA_DIR: Once = Once::new();
+
+/// The temporary directory of the databases, which is used as the data
+/// directory so the devices can be opened by name.
+fn test_dir() -> PathBuf {
+    let dir = env::temp_dir().join(format!("pltx-sync-test-{}", process::id()));
+    DATA_DIR.call_once(|| {
+        fs::create_dir_all(&dir).unwrap();
+        dirs::set_data_dir(dir.clone());
+    });
+    dir
+}
+
+/// Start a sync server on a free port. Returns its address.
+fn start_server(name: &str) -> String {
+    let server = Server::open(&test_dir().join(format!("{}-server.db", name))).unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || server.serve_listener(listener));
+    address
+}
+
+/// Create the database of a device that syncs with the key.
+fn open_device(name: &str, key: &SyncKey) -> Database {
+    let filename = format!("{}.db", name);
+    let _ = fs::remove_file(test_dir().join(&filename));
+
+    let db = Database::init(filename);
+    db.migrate_core().unwrap();
+    db.migrate("test", MIGRATIONS).unwrap();
+    db.track_changes(&["project_list", "project_card"]).unwrap();
+    db.set_sync_key(Some(&key.to_string())).unwrap();
+    db
+}
+
+fn add_card(db: &Database, list: &str, card: &str) {
+    db.execute("INSERT INTO project_list (title) VALUES (?1)", [list])
+        .unwrap();
+    db.execute(
+        "INSERT INTO project_card (list_id, title) VALUES ((SELECT id FROM project_list WHERE \
+         title = ?1), ?2)",
+        [list, card],
+    )
+    .unwrap();
+}
+
+fn rename_cards(db: &Database, title: &str) {
+    db.execute("UPDATE project_card SET title = ?1", [title])
+        .unwrap();
+}
+
+/// The titles of the cards with the titles of their lists.
+fn cards(db: &Database) -> Vec<(String, String)> {
+    db.conn()
+        .prepare(
+            "SELECT l.title, c.title FROM project_card c JOIN project_list l ON l.id = c.list_id \
+             ORDER BY c.id",
+        )
+        .unwrap()
+        .query_map((), |r| Ok((r.get(0)?, r.get(1)?)))
+        .unwrap()
+        .collect::<rusqlite::Result<Vec<(String, String)>>>()
+        .unwrap()
+}
+
+fn card(list: &str, card: &str) -> (String, String) {
+    (list.to_string(), card.to_string())
+}
+
+#[test]
+fn push_and_pull_a_change_set() {
+    let key = SyncKey::generate();
+    let address = start_server("push_and_pull");
+    let a = open_device("push-and-pull-a", &key);
+    let b = open_device("push-and-pull-b", &key);
+
+    add_card(&a, "Todo", "Groceries");
+    let report = pltx_sync::sync(&a, &address).unwrap();
+    assert_eq!(report.sent, 2);
+    assert_eq!(a.sync_state().unwrap().pending, 0);
+
+    let report = pltx_sync::sync(&b, &address).unwrap();
+    assert_eq!(report.sent, 0);
+    assert_eq!(report.received, 1);
+    assert_eq!(report.applied, 2);
+    assert_eq!(cards(&b), [card("Todo", "Groceries")]);
+    // The received changes aren't sent back.
+    assert_eq!(b.sync_state().unwrap().pending, 0);
+
+    rename_cards(&b, "Shopping");
+    let report = pltx_sync::sync(&b, &address).unwrap();
+    assert_eq!(report.sent, 1);
+
+    let report = pltx_sync::sync(&a, &address).unwrap();
+    assert_eq!(report.received, 1);
+    assert_eq!(report.applied, 1);
+    assert_eq!(cards(&a), [card("Todo", "Shopping")]);
+}
+
+#[test]
+fn concurrent_edits_resolve_the_same_way() {
+    let key = SyncKey::generate();
+    let address = start_server("concurrent_edits");
+    let a = open_device("concurrent-edits-a", &key);
+    let b = open_device("concurrent-edits-b", &key);
+
+    add_card(&a, "Todo", "Groceries");
+    pltx_sync::sync(&a, &address).unwrap();
+    pltx_sync::sync(&b, &address).unwrap();
+
+    // Both edits have the same clock, so the higher device ID wins.
+    rename_cards(&a, "Edited on A");
+    rename_cards(&b, "Edited on B");
+    pltx_sync::sync(&a, &address).unwrap();
+    pltx_sync::sync(&b, &address).unwrap();
+    pltx_sync::sync(&a, &address).unwrap();
+
+    let winner = if a.sync_state().unwrap().device_id > b.sync_state().unwrap().device_id {
+        "Edited on A"
+    } else {
+        "Edited on B"
+    };
+    assert_eq!(cards(&a), [card("Todo", winner)]);
+    assert_eq!(cards(&b), [card("Todo", winner)]);
+}
+
+#[test]
+fn card_that_arrives_before_its_list() {
+    let key = SyncKey::generate();
+    let address = start_server("card_before_list");
+    let a = open_device("card-before-list-a", &key);
+    let b = open_device("card-before-list-b", &key);
+
+    add_card(&a, "Todo", "Groceries");
+    let (lists, cards_changes): (Vec<RowChange>, Vec<RowChange>) = a
+        .pending_changes()
+        .unwrap()
+        .into_iter()
+        .partition(|c| c.table == "project_list");
+    let device_id = a.sync_state().unwrap().device_id;
+    let push = |changes: Vec<RowChange>| {
+        let change_set = ChangeSet {
+            device_id: device_id.clone(),
+            changes,
+        };
+        let data = key
+            .encrypt(&serde_json::to_vec(&change_set).unwrap())
+            .unwrap();
+        Client::connect(&address)
+            .unwrap()
+            .push(key.space(), &device_id, &data)
+            .unwrap();
+    };
+
+    push(cards_changes);
+    let report = pltx_sync::sync(&b, &address).unwrap();
+    assert_eq!(report.received, 1);
+    assert_eq!(report.applied, 0);
+    assert!(cards(&b).is_empty());
+    // The cursor moves past the change set, but the card is kept.
+    assert_eq!(b.sync_state().unwrap().cursor, 1);
+
+    push(lists);
+    let report = pltx_sync::sync(&b, &address).unwrap();
+    assert_eq!(report.received, 1);
+    assert_eq!(report.applied, 2);
+    assert_eq!(cards(&b), [card("Todo", "Groceries")]);
+}
@@ -1,6 +1,191 @@
-use std::fmt;
+use std::{
+    fmt,
+    sync::{OnceLock, RwLock},
+};
 
-use chrono::{DateTime as ChronoDateTime, Duration, Local, Utc};
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime as ChronoDateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime,
+    SecondsFormat, TimeZone, Utc, Weekday,
+};
+use chrono_tz::Tz;
+
+/// The clocks that times can be displayed with.
+pub const CLOCKS: [&str; 2] = ["24h", "12h"];
+/// The days that a week can start on.
+pub const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+/// The timezone setting that uses the timezone of the system.
+pub const LOCAL_TIMEZONE: &str = "local";
+/// The date format that is always accepted as input, whatever the configured
+/// format is.
+const ISO_DATE_FORMAT: &str = "%Y-%m-%d";
+/// The number of days from today within which relative dates are shown, e.g.,
+/// "in 6 days".
+const RELATIVE_DAYS: i64 = 6;
+
+/// The format that dates and times are displayed and entered with.
+#[derive(Clone, Debug)]
+pub struct DateTimeFormat {
+    /// The date format, with the specifiers of [`chrono::format::strftime`].
+    pub date: String,
+    /// Whether times are shown with a 12-hour clock instead of a 24-hour clock.
+    pub twelve_hour: bool,
+    /// The first day of the week in calendars.
+    pub first_day_of_week: Weekday,
+    /// Whether the dates near today are shown relative to it, e.g.,
+    /// "yesterday" or "in 2 days".
+    pub relative: bool,
+    /// The timezone that dates are shown and entered in, or the timezone of
+    /// the system if it's `None`.
+    pub timezone: Option<Tz>,
+}
+
+impl Default for DateTimeFormat {
+    fn default() -> Self {
+        Self {
+            date: ISO_DATE_FORMAT.to_string(),
+            twelve_hour: false,
+            first_day_of_week: Weekday::Mon,
+            relative: false,
+            timezone: None,
+        }
+    }
+}
+
+impl DateTimeFormat {
+    /// Check that a date format only has known specifiers, and that a date
+    /// written in it can be read back, since dates are entered in it too.
+    /// ```
+    /// # use pltx_utils::DateTimeFormat;
+    /// assert!(DateTimeFormat::check_date_format("%d/%m/%Y").is_ok());
+    /// assert!(DateTimeFormat::check_date_format("%Q").is_err());
+    /// assert!(DateTimeFormat::check_date_format("%d %b").is_err());
+    /// ```
+    pub fn check_date_format(format: &str) -> Result<(), String> {
+        if format.trim().is_empty() {
+            return Err("the date format is empty".to_string());
+        }
+        if StrftimeItems::new(format).any(|item| item == Item::Error) {
+            return Err(format!(
+                "invalid date format \"{}\", expected strftime specifiers like \"%d/%m/%Y\"",
+                format
+            ));
+        }
+        // A day after the 12th, so a day and month that are swapped don't
+        // read back as the same date.
+        let sample = NaiveDate::from_ymd_opt(2024, 11, 28).expect("valid date");
+        let formatted = sample.format(format).to_string();
+        if NaiveDate::parse_from_str(&formatted, format).ok() != Some(sample) {
+            return Err(format!(
+                "the date format \"{}\" cannot be used to enter dates, it needs the year, month, \
+                 and day",
+                format
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check that a timezone is "local" or the name of a timezone, e.g.,
+    /// "Europe/Berlin".
+    /// ```
+    /// # use pltx_utils::DateTimeFormat;
+    /// assert!(DateTimeFormat::check_timezone("America/New_York").is_ok());
+    /// assert!(DateTimeFormat::check_timezone("Mars/Olympus").is_err());
+    /// ```
+    pub fn check_timezone(timezone: &str) -> Result<(), String> {
+        if timezone == LOCAL_TIMEZONE || timezone.parse::<Tz>().is_ok() {
+            return Ok(());
+        }
+        Err(format!(
+            "unknown timezone \"{}\", expected \"{}\" or a name like \"Europe/Berlin\"",
+            timezone, LOCAL_TIMEZONE
+        ))
+    }
+
+    /// Convert a datetime to the timezone.
+    fn in_timezone(&self, datetime: ChronoDateTime<Utc>) -> ChronoDateTime<FixedOffset> {
+        match self.timezone {
+            Some(timezone) => datetime.with_timezone(&timezone).fixed_offset(),
+            None => datetime.with_timezone(&Local).fixed_offset(),
+        }
+    }
+
+    /// Convert a time in the timezone to UTC. A time that is repeated when the
+    /// clocks go back is the first one, and a time that is skipped when the
+    /// clocks go forward is `None`.
+    fn to_utc(&self, datetime: NaiveDateTime) -> Option<ChronoDateTime<Utc>> {
+        match self.timezone {
+            Some(timezone) => timezone
+                .from_local_datetime(&datetime)
+                .earliest()
+                .map(|d| d.to_utc()),
+            None => Local
+                .from_local_datetime(&datetime)
+                .earliest()
+                .map(|d| d.to_utc()),
+        }
+    }
+
+    /// The start of a day in the timezone.
+    fn start_of_day(&self, date: NaiveDate) -> ChronoDateTime<Utc> {
+        let midnight = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+        // Midnight is skipped on the days that the clocks go forward at
+        // midnight, which is close enough to the start of the day.
+        self.to_utc(midnight)
+            .or_else(|| self.to_utc(midnight + Duration::hours(1)))
+            .unwrap_or(midnight.and_utc())
+    }
+
+    /// The current date in the timezone.
+    fn today(&self) -> NaiveDate {
+        self.in_timezone(Utc::now()).date_naive()
+    }
+
+    fn time(&self, seconds: bool) -> &'static str {
+        match (self.twelve_hour, seconds) {
+            (false, false) => "%H:%M",
+            (false, true) => "%H:%M:%S",
+            (true, false) => "%I:%M %p",
+            (true, true) => "%I:%M:%S %p",
+        }
+    }
+
+    /// The format of a date with a time, which is also the format of date
+    /// inputs.
+    fn date_time(&self, seconds: bool) -> String {
+        format!("{} {}", self.date, self.time(seconds))
+    }
+
+    /// The date relative to today, if it's near it.
+    fn relative_date(&self, date: NaiveDate) -> Option<String> {
+        if !self.relative {
+            return None;
+        }
+        match (date - self.today()).num_days() {
+            0 => Some("today".to_string()),
+            1 => Some("tomorrow".to_string()),
+            -1 => Some("yesterday".to_string()),
+            days if days.abs() > RELATIVE_DAYS => None,
+            days if days > 0 => Some(format!("in {} days", days)),
+            days => Some(format!("{} days ago", -days)),
+        }
+    }
+}
+
+/// The format that is set from the config, see [`DateTime::set_format`].
+static FORMAT: OnceLock<RwLock<DateTimeFormat>> = OnceLock::new();
+
+fn format_lock() -> &'static RwLock<DateTimeFormat> {
+    FORMAT.get_or_init(|| RwLock::new(DateTimeFormat::default()))
+}
 
 /// Custom struct around [`Chrono`](chrono) for managing datetime within the
 /// application. Provides convenience methods to reduce the need for repetitive
@@ -8,14 +193,37 @@ use chrono::{DateTime as ChronoDateTime, Duration, Local, Utc};
 #[derive(Clone)]
 pub struct DateTime {
     pub datetime: ChronoDateTime<Utc>,
+    /// Whether it's a whole day rather than a time, e.g., a due date without a
+    /// time. The datetime is the start of the day in the timezone, and it's
+    /// stored as just the date.
+    pub all_day: bool,
 }
 
+#[derive(Clone, Copy)]
 pub struct DurationSince {
     pub hours: i64,
     pub minutes: i64,
     pub seconds: i64,
 }
 
+impl DurationSince {
+    /// Split a number of seconds into hours, minutes and seconds. Negative
+    /// durations are treated as 0.
+    /// ```
+    /// # use pltx_utils::DurationSince;
+    /// let duration = DurationSince::from_seconds(90061);
+    /// assert_eq!(duration.to_string(), String::from("25:01:01"));
+    /// ```
+    pub fn from_seconds(seconds: i64) -> Self {
+        let seconds = seconds.max(0);
+        Self {
+            hours: seconds / 3600,
+            minutes: seconds % 3600 / 60,
+            seconds: seconds % 60,
+        }
+    }
+}
+
 impl fmt::Display for DurationSince {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let hour_prefix = if self.hours < 10 { "0" } else { "" };
@@ -42,110 +250,256 @@ impl fmt::Display for DurationSince {
 
 impl From<ChronoDateTime<Utc>> for DateTime {
     fn from(datetime: ChronoDateTime<Utc>) -> Self {
-        Self { datetime }
+        Self {
+            datetime,
+            all_day: false,
+        }
     }
 }
 
 impl From<&str> for DateTime {
     fn from(value: &str) -> Self {
-        let datetime = ChronoDateTime::parse_from_rfc3339(value)
-            .expect("failed to parse datetime")
-            .to_utc();
-        Self::from(datetime)
+        Self::parse_db(value).expect("failed to parse datetime")
     }
 }
 
 impl From<String> for DateTime {
     fn from(value: String) -> Self {
-        let datetime = ChronoDateTime::parse_from_rfc3339(&value)
-            .expect("failed to parse datetime")
-            .to_utc();
-        Self::from(datetime)
+        Self::parse_db(&value).expect("failed to parse datetime")
     }
 }
 
 impl DateTime {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self {
-            datetime: Utc::now(),
-        }
+        Self::from(Utc::now())
+    }
+
+    /// Set the format that dates and times are displayed and entered with.
+    pub fn set_format(format: DateTimeFormat) {
+        *format_lock()
+            .write()
+            .expect("failed to set the datetime format") = format;
     }
 
-    /// Parse a datetime in the format "%Y-%m-%d %H:%M" and return in rfc3999.
-    /// format "%Y-%m-%dT%H:%M:%S+%z" as a [`Option`](Option).
+    /// Get the format that dates and times are displayed and entered with.
+    pub fn format() -> DateTimeFormat {
+        format_lock()
+            .read()
+            .expect("failed to get the datetime format")
+            .clone()
+    }
+
+    /// Parse a datetime in the date format with a time, e.g., "%Y-%m-%d
+    /// %H:%M", in the timezone of the format, and return it in UTC in rfc3999
+    /// format "%Y-%m-%dT%H:%M:%SZ" as a [`Option`](Option). Both clocks and the
+    /// "%Y-%m-%d" date format are also accepted. A date without a time is an
+    /// all-day value, which is returned as just the date.
     /// ```
-    /// # use pltx_utils::DateTime;
+    /// # use pltx_utils::{DateTime, DateTimeFormat, Tz};
+    /// DateTime::set_format(DateTimeFormat {
+    ///     timezone: Some(Tz::UTC),
+    ///     ..Default::default()
+    /// });
     /// let datetime = DateTime::from_input(String::from("2000-01-01 00:00"));
-    /// assert_eq!(datetime, Some(String::from("2000-01-01T00:00:00+00:00")));
+    /// assert_eq!(datetime, Some(String::from("2000-01-01T00:00:00Z")));
+    ///
+    /// DateTime::set_format(DateTimeFormat {
+    ///     date: String::from("%m/%d/%Y"),
+    ///     twelve_hour: true,
+    ///     timezone: Some(Tz::Europe__Berlin),
+    ///     ..Default::default()
+    /// });
+    /// let datetime = DateTime::from_input(String::from("06/01/2024 09:30 PM"));
+    /// assert_eq!(datetime, Some(String::from("2024-06-01T19:30:00Z")));
+    /// let date = DateTime::from_input(String::from("06/01/2024"));
+    /// assert_eq!(date, Some(String::from("2024-06-01")));
     /// ```
     pub fn from_input(input: String) -> Option<String> {
-        let parsed_string_format = format!("{}:00+00:00", input.replacen(' ', "T", 1));
-        let input_result = match ChronoDateTime::parse_from_rfc3339(&parsed_string_format) {
-            Ok(datetime) => Ok(Self::from(datetime.to_utc())),
-            Err(err) => Err(err),
-        };
+        let format = Self::format();
+        let input = input.trim();
+        let dates = [format.date.as_str(), ISO_DATE_FORMAT];
 
-        match input_result {
-            Ok(result) => Some(result.datetime.to_rfc3339()),
-            Err(_) => None,
+        let datetime = dates
+            .iter()
+            .flat_map(|date| [false, true].map(|twelve_hour| (date, twelve_hour)))
+            .find_map(|(date, twelve_hour)| {
+                let format = DateTimeFormat {
+                    date: date.to_string(),
+                    twelve_hour,
+                    ..format.clone()
+                };
+                NaiveDateTime::parse_from_str(input, &format.date_time(false)).ok()
+            });
+        if let Some(datetime) = datetime {
+            return format
+                .to_utc(datetime)
+                .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Secs, true));
         }
+
+        dates
+            .iter()
+            .find_map(|date| NaiveDate::parse_from_str(input, date).ok())
+            .map(|date| date.format(ISO_DATE_FORMAT).to_string())
     }
 
-    /// Get the current local datetime.
+    /// Get the current datetime in the format of date inputs.
     pub fn display_now() -> String {
-        Local::now().format("%Y-%m-%d %H:%M").to_string()
+        let format = Self::format();
+        format
+            .in_timezone(Utc::now())
+            .format(&format.date_time(false))
+            .to_string()
     }
 
-    /// Get the current local datetime with seconds.
+    /// Get the current datetime with seconds.
     pub fn display_now_with_seconds() -> String {
-        Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+        let format = Self::format();
+        format
+            .in_timezone(Utc::now())
+            .format(&format.date_time(true))
+            .to_string()
     }
 
-    /// Convert and display the datetime in local format.
+    /// The date in the format, or relative to today if it's near it.
+    fn date(&self, format: &DateTimeFormat) -> String {
+        let datetime = format.in_timezone(self.datetime);
+        format
+            .relative_date(datetime.date_naive())
+            .unwrap_or_else(|| datetime.format(&format.date).to_string())
+    }
+
+    /// The date, and the time unless it's an all-day value.
+    fn date_with_time(&self, seconds: bool) -> String {
+        let format = Self::format();
+        if self.all_day {
+            return self.date(&format);
+        }
+        format!(
+            "{} {}",
+            self.date(&format),
+            format
+                .in_timezone(self.datetime)
+                .format(format.time(seconds))
+        )
+    }
+
+    /// Convert and display the datetime in the timezone.
     pub fn display(&self) -> String {
-        ChronoDateTime::<Local>::from(self.datetime)
-            .format("%Y-%m-%d %H:%M")
-            .to_string()
+        self.date_with_time(false)
     }
 
-    /// Convert and display the datetime in local format with seconds.
+    /// Convert and display the datetime in the timezone with seconds.
     pub fn display_with_seconds(&self) -> String {
-        ChronoDateTime::<Local>::from(self.datetime)
-            .format("%Y-%m-%d %H:%M:%S")
+        self.date_with_time(true)
+    }
+
+    /// Convert and display the datetime in the format of date inputs, which is
+    /// never relative.
+    pub fn display_input(&self) -> String {
+        let format = Self::format();
+        let input_format = if self.all_day {
+            format.date.clone()
+        } else {
+            format.date_time(false)
+        };
+        format
+            .in_timezone(self.datetime)
+            .format(&input_format)
             .to_string()
     }
 
-    /// Convert and display the datetime to just the date in local format.
+    /// Convert and display the datetime to just the date in the timezone.
     pub fn display_date(&self) -> String {
-        ChronoDateTime::<Local>::from(self.datetime)
-            .format("%Y-%m-%d")
-            .to_string()
+        self.date(&Self::format())
     }
 
-    /// Convert and display the datetime to just the time in local format.
+    /// Convert and display the datetime to just the time in the timezone.
     pub fn display_time(&self) -> String {
-        ChronoDateTime::<Local>::from(self.datetime)
-            .format("%H:%M:%S")
+        let format = Self::format();
+        format
+            .in_timezone(self.datetime)
+            .format(format.time(false))
             .to_string()
     }
 
-    /// Convert and display the timedate to just the time with seconds in local
-    /// format.
+    /// Convert and display the timedate to just the time with seconds in the
+    /// timezone.
     pub fn display_time_with_seconds(&self) -> String {
-        ChronoDateTime::<Local>::from(self.datetime)
-            .format("%H:%M:%S")
+        let format = Self::format();
+        format
+            .in_timezone(self.datetime)
+            .format(format.time(true))
             .to_string()
     }
 
-    /// Checks if the current datetime is past a datetime.
+    /// The day of the month of the current date.
+    pub fn today() -> u32 {
+        Self::format().today().day()
+    }
+
+    /// The days of the current month, split into weeks that start on the
+    /// first day of the week. The days of the other months are `None`.
+    pub fn month_weeks() -> Vec<[Option<u32>; 7]> {
+        let format = Self::format();
+        let first_day_of_week = format.first_day_of_week;
+        let today = format.today();
+        let mut weeks = vec![];
+        let mut week = [None; 7];
+        let mut day = today.with_day(1).expect("every month has a first day");
+        while day.month() == today.month() {
+            let i = day.weekday().days_since(first_day_of_week) as usize;
+            week[i] = Some(day.day());
+            if i == 6 {
+                weeks.push(week);
+                week = [None; 7];
+            }
+            day = day.succ_opt().expect("the date is in range");
+        }
+        if week.iter().any(|d| d.is_some()) {
+            weeks.push(week);
+        }
+        weeks
+    }
+
+    /// The short names of the days of the week, starting on the first day of
+    /// the week.
+    pub fn weekday_names() -> [String; 7] {
+        let mut weekday = Self::format().first_day_of_week;
+        [(); 7].map(|_| {
+            let name = weekday.to_string();
+            weekday = weekday.succ();
+            name
+        })
+    }
+
+    /// The end of the datetime, which is the end of the day for an all-day
+    /// value.
+    fn end(&self) -> ChronoDateTime<Utc> {
+        if self.all_day {
+            self.datetime + Duration::days(1)
+        } else {
+            self.datetime
+        }
+    }
+
+    /// Checks if the current datetime is past a datetime. An all-day value is
+    /// past once the day is over.
     /// ```
     /// # use pltx_utils::DateTime;
     /// let datetime = DateTime::from("2000-01-01T00:00:00+00:00");
     /// assert!(datetime.is_past());
+    /// assert!(DateTime::from("2000-01-01").is_past());
     /// ```
     pub fn is_past(&self) -> bool {
-        Utc::now().timestamp() > self.datetime.timestamp()
+        Utc::now().timestamp() > self.end().timestamp()
+    }
+
+    /// Checks if the current datetime is at or after the start of a datetime,
+    /// e.g., whether a start date has begun. An all-day value has started once
+    /// the day has begun.
+    pub fn has_started(&self) -> bool {
+        Utc::now().timestamp() >= self.datetime.timestamp()
     }
 
     /// Checks if the current datetime is past a datetime minus a specified
@@ -157,10 +511,11 @@ impl DateTime {
     /// assert!(datetime.is_past_days(100));
     /// ```
     pub fn is_past_days(&self, days: i32) -> bool {
-        Utc::now().timestamp() > (self.datetime - Duration::days(days as i64)).timestamp()
+        Utc::now().timestamp() > (self.end() - Duration::days(days as i64)).timestamp()
     }
 
-    /// Calculates the duration since a past date.
+    /// Calculates the duration since a past date. The hours are not limited to
+    /// a day, so durations over midnight or multiple days are correct.
     /// ```
     /// # use pltx_utils::DateTime;
     /// let later_datetime = DateTime::from("2024-05-25T23:56:30+00:00");
@@ -170,19 +525,13 @@ impl DateTime {
     /// assert_eq!(duration.minutes, 56);
     /// assert_eq!(duration.seconds, 36);
     /// assert_eq!(duration.to_string(), String::from("02:56:36"));
+    ///
+    /// let next_day = DateTime::from("2024-05-27T01:00:00+00:00");
+    /// let duration = next_day.duration_since(&later_datetime);
+    /// assert_eq!(duration.to_string(), String::from("25:03:30"));
     /// ```
     pub fn duration_since(&self, past: &DateTime) -> DurationSince {
-        let diff = self.datetime.time() - past.datetime.time();
-
-        let hours_overlap = diff.num_days() * 24;
-        let minutes_overlap = (diff.num_days() * 24 * 60) + (diff.num_hours() * 60);
-        let seconds_overlap = (diff.num_days() * 24 * 60 * 60) + (diff.num_minutes() * 60);
-
-        DurationSince {
-            hours: diff.num_hours() - hours_overlap,
-            minutes: diff.num_minutes() - minutes_overlap,
-            seconds: diff.num_seconds() - seconds_overlap,
-        }
+        DurationSince::from_seconds((self.datetime - past.datetime).num_seconds())
     }
 }
 
@@ -193,21 +542,65 @@ impl DateTime {
         Utc::now().to_rfc3339()
     }
 
+    /// Get the datetime a number of days before now in rfc3999 format.
+    pub fn days_ago(days: i32) -> String {
+        (Utc::now() - Duration::days(days as i64)).to_rfc3339()
+    }
+
     /// Create an instance from an database DATETIME field that doesn't have
     /// DEFAULT or NOT NULL.
     pub fn from_db_option(db_option: Option<String>) -> Option<Self> {
         db_option.map(Self::from)
     }
 
+    /// Parse a database DATETIME value, which is either in rfc3999 format or
+    /// a date for an all-day value.
+    fn parse_db(db_datetime: &str) -> Option<Self> {
+        if let Ok(datetime) = ChronoDateTime::parse_from_rfc3339(db_datetime) {
+            return Some(Self::from(datetime.to_utc()));
+        }
+        let date = NaiveDate::parse_from_str(db_datetime, ISO_DATE_FORMAT).ok()?;
+        Some(Self {
+            datetime: Self::format().start_of_day(date),
+            all_day: true,
+        })
+    }
+
+    /// Whether a database DATETIME value can be parsed without panicking.
+    pub fn is_valid_db(db_datetime: &str) -> bool {
+        Self::parse_db(db_datetime).is_some()
+    }
+
     /// Create an instance from a NOT NULL database field.
     pub fn from_db(db_datetime: Option<String>) -> Self {
         let db_datetime = db_datetime
             .expect("no datetime was provided when this function expects Some(db_datetime)");
-        Self::from(
-            ChronoDateTime::parse_from_rfc3339(&db_datetime)
-                .expect("failed to parse db datetime")
-                .to_utc(),
-        )
+        Self::parse_db(&db_datetime).expect("failed to parse db datetime")
+    }
+
+    /// Convert a date input that was stored before the timezone was applied to
+    /// it. The time that was typed was stored as UTC, so it's converted to UTC
+    /// from the timezone. Returns `None` for values without a time, which
+    /// can't have been stored that way. The caller decides which values were
+    /// stored before the timezone was applied, since the format alone doesn't
+    /// tell.
+    /// ```
+    /// # use pltx_utils::{DateTime, DateTimeFormat, Tz};
+    /// DateTime::set_format(DateTimeFormat {
+    ///     timezone: Some(Tz::America__New_York),
+    ///     ..Default::default()
+    /// });
+    /// let fixed = DateTime::fix_input_timezone("2024-06-01T09:00:00+00:00");
+    /// assert_eq!(fixed, Some(String::from("2024-06-01T13:00:00Z")));
+    /// assert_eq!(DateTime::fix_input_timezone("2024-06-01"), None);
+    /// ```
+    pub fn fix_input_timezone(db_datetime: &str) -> Option<String> {
+        let typed = ChronoDateTime::parse_from_rfc3339(db_datetime)
+            .ok()?
+            .naive_utc();
+        Self::format()
+            .to_utc(typed)
+            .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Secs, true))
     }
 
     /// Converts the current datetime to rfc3999 format for storing in the
@@ -218,6 +611,13 @@ impl DateTime {
     /// assert!(ChronoDateTime::parse_from_rfc3339(&datetime.datetime.
     /// to_rfc3339()).is_ok()); ```
     pub fn into_db(&self) -> String {
-        self.datetime.to_rfc3339()
+        if self.all_day {
+            Self::format()
+                .in_timezone(self.datetime)
+                .format(ISO_DATE_FORMAT)
+                .to_string()
+        } else {
+            self.datetime.to_rfc3339()
+        }
     }
 }
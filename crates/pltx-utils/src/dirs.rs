@@ -1,6 +1,34 @@
-use std::{fs, path::PathBuf};
+use std::{env, fs, path::PathBuf, sync::OnceLock};
 
-/// Get the pltx config directory.
+/// The config directory that is set with `--config-dir`.
+static CONFIG_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// The data directory that is set with `--data-dir`.
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Use another config directory instead of the platform directory. It must be
+/// set before the config is read, and can only be set once.
+pub fn set_config_dir(path: PathBuf) {
+    let _ = CONFIG_DIR.set(path);
+}
+
+/// Use another data directory instead of the platform directory. It must be
+/// set before the database is opened, and can only be set once.
+pub fn set_data_dir(path: PathBuf) {
+    let _ = DATA_DIR.set(path);
+}
+
+/// The directory that is set with a flag, or else with an environment
+/// variable.
+fn override_dir(dir: &OnceLock<PathBuf>, var: &str) -> Option<PathBuf> {
+    dir.get().cloned().or_else(|| {
+        env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Get the pltx config directory. It's overridden by `--config-dir` or
+/// `PLTX_CONFIG_DIR`.
 /// ### Linux
 /// Value: `$XDG_CONFIG_HOME/.config/pltx`
 /// Default: `/home/user/.config/pltx`
@@ -11,16 +39,19 @@ use std::{fs, path::PathBuf};
 /// Value: `{FOLDERID_RoamingAppData}/pltx`
 /// Default: `C:\Users\User\AppData\Roaming\pltx`
 pub fn config_dir() -> PathBuf {
-    let config_dir = dirs::config_dir()
-        .expect("failed to get the config directory")
-        .join("pltx");
+    let config_dir = override_dir(&CONFIG_DIR, "PLTX_CONFIG_DIR").unwrap_or_else(|| {
+        dirs::config_dir()
+            .expect("failed to get the config directory")
+            .join("pltx")
+    });
     if !config_dir.exists() {
         fs::create_dir_all(&config_dir).expect("failed to create the config directory")
     }
     config_dir
 }
 
-/// Get the pltx data directory.
+/// Get the pltx data directory. It's overridden by `--data-dir` or
+/// `PLTX_DATA_DIR`.
 /// ### Linux
 /// Value: `$XDG_CONFIG_HOME/.local/share/pltx`
 /// Default: `/home/user/.local/share/pltx`
@@ -31,9 +62,11 @@ pub fn config_dir() -> PathBuf {
 /// Value: `{FOLDERID_RoamingAppData}/pltx`
 /// Default: `C:\Users\User\AppData\Roaming\pltx`
 pub fn data_dir() -> PathBuf {
-    let data_dir = dirs::data_dir()
-        .expect("failed to get the data directory")
-        .join("pltx");
+    let data_dir = override_dir(&DATA_DIR, "PLTX_DATA_DIR").unwrap_or_else(|| {
+        dirs::data_dir()
+            .expect("failed to get the data directory")
+            .join("pltx")
+    });
     if !data_dir.exists() {
         fs::create_dir_all(&data_dir).expect("failed to create the data directory")
     }
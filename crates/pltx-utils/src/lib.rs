@@ -8,7 +8,9 @@ pub mod dirs;
 pub mod symbols;
 mod widget;
 
-pub use datetime::DateTime;
+pub use chrono::Weekday;
+pub use chrono_tz::Tz;
+pub use datetime::{DateTime, DateTimeFormat, DurationSince, CLOCKS, LOCAL_TIMEZONE, WEEKDAYS};
 pub use widget::*;
 
 /// Center a rect by all sides within an area
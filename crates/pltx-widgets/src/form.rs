@@ -169,32 +169,30 @@ impl FormState {
 impl KeyEventHandler<FormState> for Form {
     /// Returns whether the user has submit the form.
     fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) -> FormState {
+        let keys = app.config.keys.clone();
+
         if app.mode.is_normal() {
             if self.show_close_prompt {
-                match key_event.code {
-                    KeyCode::Char('y') => {
-                        self.reset();
-                        app.view.default();
-                        return FormState::Closed;
-                    }
-                    KeyCode::Char('n') => {
-                        self.show_close_prompt = false;
-                        self.title.clone_from(&self.default_title);
-                        if self.view == EditorView::Input {
-                            let border_height = 2;
-                            let margin = 2;
-                            let height = self.current_input_state().height + border_height + margin;
-                            self.size = PopupSize::default()
-                                .width(self.default_size.width)
-                                .height(height);
-                        } else {
-                            self.size = self.default_size;
-                        }
-                        return FormState::None;
+                if keys.confirm.yes.matches(&key_event) {
+                    self.reset();
+                    app.view.default();
+                    return FormState::Closed;
+                } else if keys.confirm.no.matches(&key_event) {
+                    self.show_close_prompt = false;
+                    self.title.clone_from(&self.default_title);
+                    if self.view == EditorView::Input {
+                        let border_height = 2;
+                        let margin = 2;
+                        let height = self.current_input_state().height + border_height + margin;
+                        self.size = PopupSize::default()
+                            .width(self.default_size.width)
+                            .height(height);
+                    } else {
+                        self.size = self.default_size;
                     }
-                    _ => {}
+                    return FormState::None;
                 }
-            } else if key_event.code == KeyCode::Char('q') {
+            } else if keys.navigation.close.matches(&key_event) {
                 self.show_close_prompt = true;
                 self.title = None;
                 self.size = PopupSize::default().width(35).height(5);
@@ -205,41 +203,31 @@ impl KeyEventHandler<FormState> for Form {
         if app.mode.is_normal() && self.view == EditorView::Selection {
             self.selection.key_event_handler(app, key_event);
 
-            match key_event.code {
-                KeyCode::Enter | KeyCode::Char('l') => {
-                    let border_height = 2;
-                    let margin = 2;
-                    let height = self.current_input_state().height + border_height + margin;
-                    self.size = PopupSize::default()
-                        .width(self.default_size.width)
-                        .height(height);
-                    self.view = EditorView::Input;
-                    if self.current_input_state().uses_insert_mode {
-                        app.mode.insert();
-                    }
+            if keys.navigation.open.matches(&key_event) || keys.navigation.right.matches(&key_event)
+            {
+                let border_height = 2;
+                let margin = 2;
+                let height = self.current_input_state().height + border_height + margin;
+                self.size = PopupSize::default()
+                    .width(self.default_size.width)
+                    .height(height);
+                self.view = EditorView::Input;
+                if self.current_input_state().uses_insert_mode {
+                    app.mode.insert();
                 }
-                KeyCode::Char('s') => {
-                    if self.view == EditorView::Selection {
-                        return FormState::Submit;
-                    }
-                }
-                _ => {}
+            } else if keys.editing.submit.matches(&key_event) && self.view == EditorView::Selection
+            {
+                return FormState::Submit;
             }
         } else if self.view == EditorView::Input {
             if self.current_input_state().enter_back {
-                match key_event.code {
-                    KeyCode::Char('[') => {
-                        if app.mode.is_normal() {
-                            self.view = EditorView::Selection;
-                            self.size = self.default_size;
-                        }
-                    }
-                    KeyCode::Enter => {
-                        self.view = EditorView::Selection;
-                        app.mode.normal();
-                        self.size = self.default_size;
-                    }
-                    _ => {}
+                if key_event.code == KeyCode::Enter {
+                    self.view = EditorView::Selection;
+                    app.mode.normal();
+                    self.size = self.default_size;
+                } else if app.mode.is_normal() && keys.navigation.back.matches(&key_event) {
+                    self.view = EditorView::Selection;
+                    self.size = self.default_size;
                 }
             }
 
@@ -17,6 +17,9 @@ use ratatui::{
 use crate::{FormInputState, FormWidget};
 
 const WORD_SEPARATORS: [char; 1] = [' '];
+/// The most characters of a datetime input, which is enough for the long date
+/// formats, e.g., "Wednesday, September 30, 2024 12:00 PM".
+const DATETIME_INPUT_MAX: usize = 40;
 
 // pub enum TextInputEvent {
 //     OnChange,
@@ -99,6 +102,7 @@ pub struct TextInput {
     max: Option<usize>,
     view: View,
     inline: bool,
+    masked: bool,
     form_input: bool,
     use_size: bool,
     size: TextInputSize,
@@ -202,33 +206,38 @@ impl KeyEventHandler for TextInput {
         }
 
         if app.view == self.view && app.mode.is_normal() {
-            match key_event.code {
-                KeyCode::Char('h') | KeyCode::Left => self.move_cursor_left(),
-                KeyCode::Char('l') | KeyCode::Right => self.move_cursor_right(),
-                KeyCode::Char('w') => self.cursor_next_word(),
-                KeyCode::Char('b') => self.cursor_prev_word(),
-                KeyCode::Char('0') => self.cursor_start_line(),
-                KeyCode::Char('$') => self.cursor_end_line(),
-                KeyCode::Char('i') => {
-                    app.mode.insert();
-                    self.keys.clear();
-                }
-                KeyCode::Char('I') => {
-                    app.mode.insert();
-                    self.cursor_start_line();
-                }
-                KeyCode::Char('a') => {
-                    app.mode.insert();
-                    self.move_cursor_right();
-                }
-                KeyCode::Char('A') => {
-                    app.mode.insert();
-                    self.cursor_end_line();
-                }
-                KeyCode::Char('x') => self.delete_char_forward(),
-                KeyCode::Char('d') => self.delete_line(),
-                KeyCode::Esc => self.keys.clear(),
-                _ => {}
+            let keys = &app.config.keys.text_input;
+
+            if keys.cursor_left.matches(&key_event) {
+                self.move_cursor_left();
+            } else if keys.cursor_right.matches(&key_event) {
+                self.move_cursor_right();
+            } else if keys.next_word.matches(&key_event) {
+                self.cursor_next_word();
+            } else if keys.prev_word.matches(&key_event) {
+                self.cursor_prev_word();
+            } else if keys.line_start.matches(&key_event) {
+                self.cursor_start_line();
+            } else if keys.line_end.matches(&key_event) {
+                self.cursor_end_line();
+            } else if keys.insert.matches(&key_event) {
+                app.mode.insert();
+                self.keys.clear();
+            } else if keys.insert_start.matches(&key_event) {
+                app.mode.insert();
+                self.cursor_start_line();
+            } else if keys.append.matches(&key_event) {
+                app.mode.insert();
+                self.move_cursor_right();
+            } else if keys.append_end.matches(&key_event) {
+                app.mode.insert();
+                self.cursor_end_line();
+            } else if keys.delete_char.matches(&key_event) {
+                self.delete_char_forward();
+            } else if keys.delete_line.matches(&key_event) {
+                self.delete_line(key_event.code);
+            } else if key_event.code == KeyCode::Esc {
+                self.keys.clear();
             }
         }
     }
@@ -275,6 +284,7 @@ impl TextInput {
             max: None,
             view: View::Default,
             inline: false,
+            masked: false,
             form_input: false,
             use_size: false,
             size: TextInputSize::default(),
@@ -318,8 +328,9 @@ impl TextInput {
     pub fn datetime_input(mut self) -> Self {
         self.input_type = TextInputType::Date;
         self.placeholder = Some(DateTime::display_now());
-        self.min = Some(16);
-        self.max = Some(16);
+        // The length depends on the date format, so the input is only checked
+        // when it's parsed.
+        self.max = Some(DATETIME_INPUT_MAX);
         self
     }
 
@@ -381,6 +392,12 @@ impl TextInput {
         self
     }
 
+    /// Hide the characters of the input, e.g., for passphrases.
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
     pub fn prompt(mut self) -> Self {
         self.style = InputStyle::Prompt;
         self
@@ -448,13 +465,14 @@ impl TextInput {
             before_char_to_delete.chain(after_char_to_delete).collect();
     }
 
-    fn delete_line(&mut self) {
-        if self.keys.key_is(KeyCode::Char('d')) {
+    /// Clear the line when the key is pressed twice.
+    fn delete_line(&mut self, key_code: KeyCode) {
+        if self.keys.key_is(key_code) {
             self.input[self.cursor_position.y].clear();
             self.cursor_position.x = 0;
             self.keys.clear();
         } else {
-            self.keys.add_key(KeyCode::Char('d'));
+            self.keys.add_key(key_code);
         }
     }
 
@@ -569,6 +587,7 @@ impl TextInput {
                             style = style.fg(colors.input_cursor_fg).bg(colors.input_cursor_bg)
                         }
                     }
+                    let character = if self.masked { '•' } else { *character };
                     let mut span = vec![Span::from(character.to_string()).style(style)];
 
                     let is_last_char = real_line_x_value
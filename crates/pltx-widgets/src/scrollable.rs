@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, KeyEventHandler};
 use ratatui::{layout::Rect, widgets::Widget, Frame};
 
@@ -41,45 +41,40 @@ impl Scrollable {
 }
 
 impl KeyEventHandler for Scrollable {
-    fn key_event_handler(&mut self, _: &mut App, key_event: KeyEvent) {
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
         let header_height = if self.col_lengths.is_some() { 1 } else { 0 };
         let area_height = *self.area_height.borrow() as usize / self.row_height as usize;
+        let keys = &app.config.keys.navigation;
 
-        match key_event.code {
-            KeyCode::Char('j') => {
-                if self.focused != self.row_count.borrow().saturating_sub(1) {
-                    let is_focus_row_end = self.focused
-                        == self.from_top + area_height.saturating_sub(1 + header_height);
-                    if is_focus_row_end {
-                        self.from_top += 1;
-                    }
-                    self.focused_prev = self.focused;
-                    self.focused += 1;
+        if keys.down.matches(&key_event) {
+            if self.focused != self.row_count.borrow().saturating_sub(1) {
+                let is_focus_row_end =
+                    self.focused == self.from_top + area_height.saturating_sub(1 + header_height);
+                if is_focus_row_end {
+                    self.from_top += 1;
                 }
+                self.focused_prev = self.focused;
+                self.focused += 1;
             }
-            KeyCode::Char('k') => {
-                if self.focused != 0 {
-                    if self.focused == self.from_top {
-                        self.from_top -= 1;
-                    }
-                    self.focused_prev = self.focused;
-                    self.focused -= 1;
+        } else if keys.up.matches(&key_event) {
+            if self.focused != 0 {
+                if self.focused == self.from_top {
+                    self.from_top -= 1;
                 }
+                self.focused_prev = self.focused;
+                self.focused -= 1;
             }
-            KeyCode::Char('g') => {
-                self.from_top = 0;
-                self.focused_prev = 0;
-                self.focused = 0;
-            }
-            KeyCode::Char('G') => {
-                self.from_top = self
-                    .row_count
-                    .borrow()
-                    .saturating_sub(area_height - header_height);
-                self.focused_prev = 0;
-                self.focused = self.row_count.borrow().saturating_sub(1);
-            }
-            _ => {}
+        } else if keys.top.matches(&key_event) {
+            self.from_top = 0;
+            self.focused_prev = 0;
+            self.focused = 0;
+        } else if keys.bottom.matches(&key_event) {
+            self.from_top = self
+                .row_count
+                .borrow()
+                .saturating_sub(area_height - header_height);
+            self.focused_prev = 0;
+            self.focused = self.row_count.borrow().saturating_sub(1);
         }
     }
 }
@@ -165,6 +160,20 @@ impl Scrollable {
             .collect::<Vec<Rect>>()
     }
 
+    /// Focus a row and scroll to it if it's not visible.
+    pub fn focus(&mut self, index: usize) {
+        let header_height = if self.col_lengths.is_some() { 1 } else { 0 };
+        let visible_rows = (*self.area_height.borrow() / self.row_height) as usize - header_height;
+
+        self.focused_prev = self.focused;
+        self.focused = index;
+        if index < self.from_top {
+            self.from_top = index;
+        } else if visible_rows > 0 && index >= self.from_top + visible_rows {
+            self.from_top = index + 1 - visible_rows;
+        }
+    }
+
     pub fn reset(&mut self) {
         self.focused = 0;
         self.focused_prev = 0;
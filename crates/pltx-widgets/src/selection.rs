@@ -1,6 +1,6 @@
 use std::{cell::RefCell, collections::HashSet, rc::Rc};
 
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, CompositeWidget, DefaultWidget, KeyEventHandler};
 use pltx_utils::symbols;
 use ratatui::{
@@ -25,6 +25,7 @@ pub struct Selection<T> {
     title: String,
     height: u16,
     checklist: bool,
+    single: bool,
 }
 
 impl<T> Selection<T> {
@@ -36,6 +37,7 @@ impl<T> Selection<T> {
             title: title.into(),
             height: DEFAULT_HEIGHT,
             checklist: false,
+            single: false,
         }
     }
 
@@ -53,12 +55,21 @@ impl<T> Selection<T> {
         self
     }
 
+    /// Only one option can be selected, which replaces the selected option.
+    pub fn single(mut self) -> Self {
+        self.single = true;
+        self
+    }
+
     pub fn options(&mut self, options: SelectionOptions<T>) {
         self.options = options;
     }
 
     pub fn select(&mut self) {
-        if self.selected.contains(&self.focused_option) {
+        if self.single {
+            self.selected.clear();
+            self.selected.insert(self.focused_option);
+        } else if self.selected.contains(&self.focused_option) {
             self.selected.remove(&self.focused_option);
         } else {
             self.selected.insert(self.focused_option);
@@ -66,6 +77,9 @@ impl<T> Selection<T> {
     }
 
     pub fn toggle_all(&mut self) {
+        if self.single {
+            return;
+        }
         if self.selected.len() == self.options.len() {
             self.selected.clear();
         } else {
@@ -76,6 +90,9 @@ impl<T> Selection<T> {
     }
 
     pub fn invert_selection(&mut self) {
+        if self.single {
+            return;
+        }
         for (i, _) in self.options.iter().enumerate() {
             if self.selected.contains(&i) {
                 self.selected.remove(&i);
@@ -92,16 +109,24 @@ impl<T> Selection<T> {
 }
 
 impl<T> KeyEventHandler for Selection<T> {
-    fn key_event_handler(&mut self, _: &mut App, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('j') => self.focus_next(),
-            KeyCode::Char('k') => self.focus_prev(),
-            KeyCode::Char('g') => self.focus_first(),
-            KeyCode::Char('G') => self.focus_last(),
-            KeyCode::Char(' ') => self.select(),
-            KeyCode::Char('a') => self.toggle_all(),
-            KeyCode::Char('i') => self.invert_selection(),
-            _ => {}
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
+        let navigation = &app.config.keys.navigation;
+        let selection = &app.config.keys.selection;
+
+        if navigation.down.matches(&key_event) {
+            self.focus_next();
+        } else if navigation.up.matches(&key_event) {
+            self.focus_prev();
+        } else if navigation.top.matches(&key_event) {
+            self.focus_first();
+        } else if navigation.bottom.matches(&key_event) {
+            self.focus_last();
+        } else if selection.select.matches(&key_event) {
+            self.select();
+        } else if selection.select_all.matches(&key_event) {
+            self.toggle_all();
+        } else if selection.invert_selection.matches(&key_event) {
+            self.invert_selection();
         }
     }
 }
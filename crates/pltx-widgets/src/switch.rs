@@ -1,6 +1,6 @@
 use std::{cell::RefCell, rc::Rc};
 
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{state::View, App, DefaultWidget, FormWidgetOld, KeyEventHandler};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
@@ -92,10 +92,11 @@ impl FormWidget for Switch {
 }
 
 impl KeyEventHandler for Switch {
-    fn key_event_handler(&mut self, _: &mut App, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char(' ') | KeyCode::Enter => self.toggle_state(),
-            _ => {}
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
+        if app.config.keys.selection.select.matches(&key_event)
+            || app.config.keys.navigation.open.matches(&key_event)
+        {
+            self.toggle_state();
         }
     }
 }
@@ -106,7 +107,7 @@ impl From<&str> for Switch {
             title: title.to_string(),
             original_state: false,
             state: false,
-            max_title_len: 0,
+            max_title_len: title.chars().count() as u16,
         }
     }
 }
@@ -1,4 +1,4 @@
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, DefaultWidget, KeyEventHandler};
 use pltx_utils::symbols;
 use ratatui::{
@@ -30,25 +30,20 @@ impl<T: Clone + PartialEq, const N: usize> From<[(T, &str); N]> for Tabs<T> {
 }
 
 impl<T: Clone + PartialEq> KeyEventHandler for Tabs<T> {
-    fn key_event_handler(&mut self, _: &mut App, key_event: KeyEvent) {
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
         let tab_position = self
             .tabs
             .iter()
             .position(|t| t.0 == self.active)
             .expect("invalid tab position");
 
-        match key_event.code {
-            KeyCode::Char('}') => {
-                if tab_position != self.tabs.len() - 1 {
-                    self.active = self.tabs[tab_position + 1].0.clone();
-                }
+        let keys = &app.config.keys.navigation;
+        if keys.next_tab.matches(&key_event) {
+            if tab_position != self.tabs.len() - 1 {
+                self.active = self.tabs[tab_position + 1].0.clone();
             }
-            KeyCode::Char('{') => {
-                if tab_position != 0 {
-                    self.active = self.tabs[tab_position - 1].0.clone();
-                }
-            }
-            _ => {}
+        } else if keys.prev_tab.matches(&key_event) && tab_position != 0 {
+            self.active = self.tabs[tab_position - 1].0.clone();
         }
     }
 }
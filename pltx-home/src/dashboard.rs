@@ -1,8 +1,10 @@
 use std::vec;
 
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, DefaultWidget, KeyEventHandler, Screen};
+use pltx_database::{SessionStatus, SessionSummary};
+use pltx_sync::SyncStatus;
 use pltx_utils::{symbols, DateTime, WidgetMargin};
 use pltx_widgets::{CardCell, CardLayout, CardRow, Scrollable};
 use ratatui::{
@@ -17,13 +19,6 @@ const CARDS_MAX_WIDTH: u16 = 200;
 const SMALL_HEIGHT: u16 = 35;
 const MEDIUM_HEIGHT: u16 = 45;
 
-struct Session {
-    id: i32,
-    started: Option<DateTime>,
-    ended: Option<DateTime>,
-    is_current: bool,
-}
-
 #[derive(PartialEq, Clone)]
 enum Pane {
     Sessions,
@@ -33,13 +28,13 @@ enum Pane {
 
 pub struct Dashboard {
     pane: Pane,
-    sessions: Vec<Session>,
+    sessions: Vec<SessionSummary>,
     scrollable_sessions: Scrollable,
 }
 
 impl Screen for Dashboard {
     fn init(app: &App) -> Result<Self> {
-        let sessions = Dashboard::db_get_sessions(app)?;
+        let sessions = app.db.sessions(20)?;
 
         Ok(Self {
             pane: Pane::Sessions,
@@ -54,22 +49,20 @@ impl Screen for Dashboard {
         }
 
         if app.view.is_default() {
-            match key_event.code {
-                KeyCode::Tab => {
-                    self.pane = match self.pane {
-                        Pane::Sessions => Pane::Tasks,
-                        Pane::Tasks => Pane::Calendar,
-                        Pane::Calendar => Pane::Sessions,
-                    }
+            let keys = &app.config.keys.navigation;
+
+            if keys.next_pane.matches(&key_event) {
+                self.pane = match self.pane {
+                    Pane::Sessions => Pane::Tasks,
+                    Pane::Tasks => Pane::Calendar,
+                    Pane::Calendar => Pane::Sessions,
                 }
-                KeyCode::BackTab => {
-                    self.pane = match self.pane {
-                        Pane::Sessions => Pane::Calendar,
-                        Pane::Tasks => Pane::Sessions,
-                        Pane::Calendar => Pane::Tasks,
-                    }
+            } else if keys.prev_pane.matches(&key_event) {
+                self.pane = match self.pane {
+                    Pane::Sessions => Pane::Calendar,
+                    Pane::Tasks => Pane::Sessions,
+                    Pane::Calendar => Pane::Tasks,
                 }
-                _ => {}
             }
         }
     }
@@ -93,32 +86,6 @@ impl Screen for Dashboard {
 }
 
 impl Dashboard {
-    fn db_get_sessions(app: &App) -> Result<Vec<Session>> {
-        let query = "SELECT id, started, ended FROM session ORDER BY started DESC LIMIT 20";
-        let conn = app.db.conn();
-        let mut stmt = conn.prepare(query)?;
-        let sessions_iter = stmt.query_map([], |row| {
-            Ok(Session {
-                id: row.get(0)?,
-                started: DateTime::from_db_option(row.get(1)?),
-                ended: DateTime::from_db_option(row.get(2)?),
-                is_current: false,
-            })
-        })?;
-
-        let mut sessions = Vec::new();
-        for s in sessions_iter {
-            let mut session = s?;
-            if app.db.session_id.is_some_and(|id| id == session.id) {
-                session.is_current = true;
-            }
-
-            sessions.push(session)
-        }
-
-        Ok(sessions)
-    }
-
     fn render_welcome(&self, app: &App, frame: &mut Frame, area: Rect, parent_area: Rect) {
         let vertical_spacing = if parent_area.height < SMALL_HEIGHT {
             1
@@ -243,6 +210,7 @@ impl Dashboard {
             .iter()
             .enumerate()
             .map(|(i, s)| {
+                let is_current = app.db.session_id().is_some_and(|id| id == s.id);
                 vec![
                     Paragraph::new(format!(" {}", s.id)).fg(colors.fg).bg(
                         if self.pane == Pane::Sessions && self.scrollable_sessions.focused == i {
@@ -251,16 +219,10 @@ impl Dashboard {
                             colors.bg
                         },
                     ),
-                    Paragraph::new(if let Some(started) = &s.started {
-                        if s.is_current {
-                            DateTime::new().duration_since(started).to_string()
-                        } else if let Some(ended) = &s.ended {
-                            ended.duration_since(started).to_string()
-                        } else {
-                            "<unknown>".to_string()
-                        }
+                    Paragraph::new(if is_current {
+                        app.db.session_duration().unwrap_or(s.active).to_string()
                     } else {
-                        "<pending>".to_string()
+                        s.active.to_string()
                     })
                     .fg(colors.success)
                     .bg(
@@ -270,20 +232,11 @@ impl Dashboard {
                             colors.bg
                         },
                     ),
-                    Paragraph::new(if let Some(started) = &s.started {
-                        Line::from(vec![
-                            Span::from(started.display_date()).fg(colors.date_fg),
-                            Span::from(" "),
-                            Span::from(started.display_time_with_seconds()).fg(colors.time_fg),
-                        ])
-                    } else {
-                        Line::from("<pending>".to_string())
-                    })
-                    .fg(if s.started.is_some() {
-                        colors.date_fg
-                    } else {
-                        colors.secondary_fg
-                    })
+                    Paragraph::new(Line::from(vec![
+                        Span::from(s.started.display_date()).fg(colors.date_fg),
+                        Span::from(" "),
+                        Span::from(s.started.display_time_with_seconds()).fg(colors.time_fg),
+                    ]))
                     .bg(
                         if self.pane == Pane::Sessions && self.scrollable_sessions.focused == i {
                             colors.input_focus_bg
@@ -291,26 +244,26 @@ impl Dashboard {
                             colors.bg
                         },
                     ),
-                    Paragraph::new(if s.is_current {
+                    Paragraph::new(if is_current {
                         Line::from(vec![
                             Span::from(DateTime::new().display_date()).fg(colors.date_fg),
                             Span::from(" "),
                             Span::from(DateTime::new().display_time_with_seconds())
                                 .fg(colors.time_fg),
                         ])
-                    } else if let Some(ended) = &s.ended {
+                    } else if s.status == SessionStatus::Crashed {
+                        // The last heartbeat before the crash.
                         Line::from(vec![
-                            Span::from(ended.display_date()).fg(colors.date_fg),
+                            Span::from(s.ended.display_date()).fg(colors.danger),
                             Span::from(" "),
-                            Span::from(ended.display_time_with_seconds()).fg(colors.time_fg),
+                            Span::from(s.ended.display_time_with_seconds()).fg(colors.danger),
                         ])
                     } else {
-                        Line::from("<empty>".to_string())
-                    })
-                    .fg(if s.ended.is_some() {
-                        colors.time_fg
-                    } else {
-                        colors.secondary_fg
+                        Line::from(vec![
+                            Span::from(s.ended.display_date()).fg(colors.date_fg),
+                            Span::from(" "),
+                            Span::from(s.ended.display_time_with_seconds()).fg(colors.time_fg),
+                        ])
                     })
                     .bg(
                         if self.pane == Pane::Sessions && self.scrollable_sessions.focused == i {
@@ -336,9 +289,30 @@ impl Dashboard {
 
     fn render_calendar(&self, app: &App) -> impl Widget {
         let colors = &app.config.colors;
-
-        let tasks = vec![Line::from("You don't have any planned events.").fg(colors.secondary_fg)];
-        Paragraph::new(tasks)
+        let today = DateTime::today();
+
+        let mut lines = vec![Line::from(
+            DateTime::weekday_names()
+                .iter()
+                .map(|name| Span::from(format!("{:>3}", &name[..2])).fg(colors.secondary_fg))
+                .collect::<Vec<Span>>(),
+        )];
+        for week in DateTime::month_weeks() {
+            lines.push(Line::from(
+                week.iter()
+                    .map(|day| match day {
+                        Some(day) if *day == today => Span::from(format!("{:>3}", day))
+                            .fg(colors.highlight_fg)
+                            .bold(),
+                        Some(day) => Span::from(format!("{:>3}", day)).fg(colors.date_fg),
+                        None => Span::from("   "),
+                    })
+                    .collect::<Vec<Span>>(),
+            ));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from("You don't have any planned events.").fg(colors.secondary_fg));
+        Paragraph::new(lines)
     }
 
     fn render_screentime(&self, app: &App) -> impl Widget {
@@ -350,6 +324,13 @@ impl Dashboard {
 
     fn render_app_info(&self, app: &App) -> impl Widget {
         let colors = &app.config.colors;
+        let status = |ok: bool| {
+            if ok {
+                Span::from(symbols::CHECK).fg(colors.success)
+            } else {
+                Span::from(symbols::CROSS).fg(colors.danger)
+            }
+        };
 
         Paragraph::new(vec![
             Line::from(format!("Version: {}", env!("CARGO_PKG_VERSION"))),
@@ -363,15 +344,15 @@ impl Dashboard {
             ]),
             Line::from(vec![
                 Span::from("Server storage: "),
-                Span::from(symbols::CROSS).fg(colors.danger),
+                status(app.sync.status != SyncStatus::Disabled),
             ]),
             Line::from(vec![
                 Span::from("Connected to server: "),
-                Span::from(symbols::CROSS).fg(colors.danger),
+                status(app.sync.status == SyncStatus::Connected),
             ]),
             Line::from(vec![
                 Span::from("Encrypted: "),
-                Span::from(symbols::CROSS).fg(colors.danger),
+                status(app.db.is_encrypted()),
             ]),
         ])
     }
@@ -2,7 +2,7 @@ use std::cell::RefCell;
 
 use ansi_to_ratatui::IntoText;
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, DefaultWidget, Screen};
 use pltx_utils::{centered_rect, symbols};
 use pltx_widgets::{Card, Scrollable};
@@ -18,6 +18,8 @@ use syntect::{easy::HighlightLines, highlighting::ThemeSet, parsing::SyntaxSet};
 
 use crate::generated_docs::DOCUMENTS;
 
+const KEYBINDINGS_TITLE: &str = "Keybindings";
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Document {
     pub filename: &'static str,
@@ -69,103 +71,102 @@ impl Screen for Help {
         })
     }
 
-    fn key_event_handler(&mut self, _: &mut App, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('j') => {
-                if self.page == Page::Selection {
-                    if self.selected_page != DOCUMENTS.len().saturating_sub(1) {
-                        self.selected_page += 1;
-                    } else {
-                        self.selected_page = 0;
-                    }
-                } else if self.focused != self.line_count.saturating_sub(1) {
-                    if self.focused + 1 == self.from_top + *self.area_height.borrow() {
-                        self.from_top += 1;
-                    }
-                    self.focused_prev = self.focused;
-                    self.focused += 1;
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
+        let keys = &app.config.keys.navigation;
+
+        if keys.down.matches(&key_event) {
+            if self.page == Page::Selection {
+                // The keybindings page is after the documents.
+                if self.selected_page != DOCUMENTS.len() {
+                    self.selected_page += 1;
+                } else {
+                    self.selected_page = 0;
                 }
-            }
-            KeyCode::Char('k') => {
-                if self.page == Page::Selection {
-                    if self.selected_page != 0 {
-                        self.selected_page -= 1;
-                    } else {
-                        self.selected_page = DOCUMENTS.len().saturating_sub(1);
-                    }
-                } else if self.focused != 0 {
-                    if self.focused == self.from_top {
-                        self.from_top -= 1;
-                    }
-                    self.focused_prev = self.focused;
-                    self.focused -= 1;
+            } else if self.focused != self.line_count.saturating_sub(1) {
+                if self.focused + 1 == self.from_top + *self.area_height.borrow() {
+                    self.from_top += 1;
                 }
+                self.focused_prev = self.focused;
+                self.focused += 1;
             }
-            KeyCode::Char('g') => {
-                self.from_top = 0;
-                self.focused_prev = 0;
-                self.focused = 0;
-            }
-            KeyCode::Char('G') => {
-                self.from_top = self
-                    .line_count
-                    .saturating_sub(*self.area_height.borrow() - 1);
-                self.focused_prev = 0;
-                self.focused = self.line_count.saturating_sub(1);
+        } else if keys.up.matches(&key_event) {
+            if self.page == Page::Selection {
+                if self.selected_page != 0 {
+                    self.selected_page -= 1;
+                } else {
+                    self.selected_page = DOCUMENTS.len();
+                }
+            } else if self.focused != 0 {
+                if self.focused == self.from_top {
+                    self.from_top -= 1;
+                }
+                self.focused_prev = self.focused;
+                self.focused -= 1;
             }
-            KeyCode::Enter | KeyCode::Char('l') => {
-                let document = &DOCUMENTS[self.selected_page];
-
-                self.scrollable.reset();
-                self.document = Some(document);
-                self.page = Page::Document;
-
-                // TODO: This is very slow, possibly try to render the content first and load
-                // the highlighting in the background?
-                let ps = SyntaxSet::load_defaults_newlines();
-                let ts = ThemeSet::load_defaults();
-                let syntax = ps.find_syntax_by_extension("md").unwrap();
-                let mut highlight = HighlightLines::new(syntax, &ts.themes["base16-eighties.dark"]);
-
-                // Skip 1 line which is an empty line.
-
-                self.line_count = document.content.split('\n').count() - 1;
-
-                let highlighted_content = document
-                    .content
-                    .split('\n')
-                    .skip(1)
-                    .map(|line| format!("{}\n", line))
-                    .map(|line| {
-                        syntect::util::as_24_bit_terminal_escaped(
-                            &highlight.highlight_line(&line, &ps).unwrap(),
-                            false,
-                        )
-                    })
-                    .collect::<String>();
+        } else if keys.top.matches(&key_event) {
+            self.from_top = 0;
+            self.focused_prev = 0;
+            self.focused = 0;
+        } else if keys.bottom.matches(&key_event) {
+            self.from_top = self
+                .line_count
+                .saturating_sub(*self.area_height.borrow() - 1);
+            self.focused_prev = 0;
+            self.focused = self.line_count.saturating_sub(1);
+        } else if keys.open.matches(&key_event) || keys.right.matches(&key_event) {
+            self.scrollable.reset();
+            self.page = Page::Document;
 
-                self.highlighted_content = Some(highlighted_content);
-            }
-            KeyCode::Char('[') => {
-                if self.page == Page::Document {
-                    self.page = Page::Selection;
+            // The keybindings page isn't a file, it is generated from the config.
+            let content = match DOCUMENTS.get(self.selected_page) {
+                Some(document) => {
+                    self.document = Some(document);
+                    document.content.to_string()
+                }
+                None => {
                     self.document = None;
-                    self.highlighted_content = None;
-                    self.line_count = 0;
-                    self.focused = 0;
-                    self.focused_prev = 0;
-                    self.from_top = 0;
+                    keybindings_content(app)
                 }
-            }
-            _ => {}
+            };
+
+            // TODO: This is very slow, possibly try to render the content first and load
+            // the highlighting in the background?
+            let ps = SyntaxSet::load_defaults_newlines();
+            let ts = ThemeSet::load_defaults();
+            let syntax = ps.find_syntax_by_extension("md").unwrap();
+            let mut highlight = HighlightLines::new(syntax, &ts.themes["base16-eighties.dark"]);
+
+            // Skip 1 line which is an empty line.
+
+            self.line_count = content.split('\n').count() - 1;
+
+            let highlighted_content = content
+                .split('\n')
+                .skip(1)
+                .map(|line| format!("{}\n", line))
+                .map(|line| {
+                    syntect::util::as_24_bit_terminal_escaped(
+                        &highlight.highlight_line(&line, &ps).unwrap(),
+                        false,
+                    )
+                })
+                .collect::<String>();
+
+            self.highlighted_content = Some(highlighted_content);
+        } else if keys.back.matches(&key_event) && self.page == Page::Document {
+            self.page = Page::Selection;
+            self.document = None;
+            self.highlighted_content = None;
+            self.line_count = 0;
+            self.focused = 0;
+            self.focused_prev = 0;
+            self.from_top = 0;
         }
     }
 
     fn render(&self, app: &App, frame: &mut Frame, area: Rect) {
         if self.page == Page::Document {
-            if let Some(document) = self.document {
-                self.render_document(app, frame, area, document);
-            }
+            self.render_document(app, frame, area, self.document);
         } else {
             self.render_selection(app, frame, area);
         }
@@ -173,7 +174,13 @@ impl Screen for Help {
 }
 
 impl Help {
-    fn render_document(&self, app: &App, frame: &mut Frame, area: Rect, document: &Document) {
+    fn render_document(
+        &self,
+        app: &App,
+        frame: &mut Frame,
+        area: Rect,
+        document: Option<&Document>,
+    ) {
         let colors = &app.config.colors;
 
         let [frontmatter_layout, content_layout] = Layout::default()
@@ -220,29 +227,42 @@ impl Help {
 
         frame.render_widget(side_line, side_line_layout);
 
-        let frontmatter = Paragraph::new(vec![
-            Line::from(vec![
-                Span::from("Title: ").fg(colors.secondary_fg),
-                Span::from(document.frontmatter.title),
-            ]),
-            Line::from(vec![
-                Span::from("Last Updated: ").fg(colors.secondary_fg),
-                Span::from(document.frontmatter.last_updated),
-            ]),
-            Line::from(vec![
-                Span::from("Filename: ").fg(colors.secondary_fg),
-                Span::from(format!("{}.md", document.filename)),
-            ]),
-            Line::from(vec![
-                Span::from("GitHub: ").fg(colors.secondary_fg),
-                Span::from(format!(
-                    "https://github.com/pltx/pltx-tui/blob/main/docs/{}.md",
-                    document.filename
-                )),
-            ])
-            .fg(colors.primary),
-        ])
-        .block(
+        let frontmatter_lines = match document {
+            Some(document) => vec![
+                Line::from(vec![
+                    Span::from("Title: ").fg(colors.secondary_fg),
+                    Span::from(document.frontmatter.title),
+                ]),
+                Line::from(vec![
+                    Span::from("Last Updated: ").fg(colors.secondary_fg),
+                    Span::from(document.frontmatter.last_updated),
+                ]),
+                Line::from(vec![
+                    Span::from("Filename: ").fg(colors.secondary_fg),
+                    Span::from(format!("{}.md", document.filename)),
+                ]),
+                Line::from(vec![
+                    Span::from("GitHub: ").fg(colors.secondary_fg),
+                    Span::from(format!(
+                        "https://github.com/pltx/pltx-tui/blob/main/docs/{}.md",
+                        document.filename
+                    )),
+                ])
+                .fg(colors.primary),
+            ],
+            None => vec![
+                Line::from(vec![
+                    Span::from("Title: ").fg(colors.secondary_fg),
+                    Span::from(KEYBINDINGS_TITLE),
+                ]),
+                Line::from(vec![
+                    Span::from("Source: ").fg(colors.secondary_fg),
+                    Span::from("the [keys] section of your config"),
+                ]),
+            ],
+        };
+
+        let frontmatter = Paragraph::new(frontmatter_lines).block(
             Block::new()
                 .padding(Padding::horizontal(1))
                 .borders(Borders::ALL)
@@ -272,15 +292,15 @@ impl Help {
 
         let table = DOCUMENTS
             .iter()
+            .map(|d| d.frontmatter.title)
+            .chain([KEYBINDINGS_TITLE])
             .enumerate()
-            .map(|(i, d)| {
-                Paragraph::new(format!(" {} ", d.frontmatter.title)).bg(
-                    if self.selected_page == i {
-                        colors.input_focus_bg
-                    } else {
-                        colors.bg
-                    },
-                )
+            .map(|(i, title)| {
+                Paragraph::new(format!(" {} ", title)).bg(if self.selected_page == i {
+                    colors.input_focus_bg
+                } else {
+                    colors.bg
+                })
             })
             .collect::<Vec<Paragraph>>();
 
@@ -291,3 +311,37 @@ impl Help {
         self.scrollable.render(frame, card.child_layout(), table);
     }
 }
+
+/// The keybindings page in markdown. It lists the keys of the config, so it
+/// changes with the config.
+fn keybindings_content(app: &App) -> String {
+    let mut content = String::from(
+        "
+# Keybindings
+
+These are the keys that are currently set. They can be changed in the `[keys]` section of the \
+         config file, where an action can have several keys separated by spaces, e.g., `\"l \
+         right\"`. Keys can't be bound to more than one action that is available at the same time.
+",
+    );
+
+    for (scope, actions) in app.config.keys.scopes() {
+        let mut title = scope.replace('_', " ");
+        title[..1].make_ascii_uppercase();
+        content.push_str(&format!(
+            "\n## {}\n\n| Key | Action | Description |\n| --- | --- | --- |\n",
+            title
+        ));
+        for (action, description, keys) in actions {
+            let keys = keys
+                .0
+                .iter()
+                .map(|k| format!("**`{}`**", k))
+                .collect::<Vec<String>>()
+                .join(" ");
+            content.push_str(&format!("| {} | `{}` | {} |\n", keys, action, description));
+        }
+    }
+
+    content
+}
@@ -11,9 +11,11 @@ use ratatui::{
 mod dashboard;
 mod generated_docs;
 mod help;
+mod settings;
 
 use dashboard::Dashboard;
 use help::Help;
+use settings::Settings;
 
 #[derive(Clone, PartialEq)]
 enum Tab {
@@ -24,6 +26,7 @@ enum Tab {
 
 pub struct Screens {
     dashboard: Dashboard,
+    settings: Settings,
     help: Help,
 }
 
@@ -59,17 +62,21 @@ impl Module for Home {
             ]),
             screens: Screens {
                 dashboard: Dashboard::init(app)?,
+                settings: Settings::init(app)?,
                 help: Help::init(app)?,
             },
         })
     }
 
     fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
-        self.tabs.key_event_handler(app, key_event);
+        // The tab keys can be typed in the inputs of a popup.
+        if app.view.is_default() {
+            self.tabs.key_event_handler(app, key_event);
+        }
 
         match self.tabs.active {
             Tab::Dashboard => self.screens.dashboard.key_event_handler(app, key_event),
-            Tab::Settings => {}
+            Tab::Settings => self.screens.settings.key_event_handler(app, key_event),
             Tab::Help => self.screens.help.key_event_handler(app, key_event),
         }
     }
@@ -83,7 +90,7 @@ impl Module for Home {
 
         match self.tabs.active {
             Tab::Dashboard => self.screens.dashboard.render(app, frame, screen_layout),
-            Tab::Settings => {}
+            Tab::Settings => self.screens.settings.render(app, frame, screen_layout),
             Tab::Help => self.screens.help.render(app, frame, screen_layout),
         }
     }
@@ -0,0 +1,513 @@
+//! The settings screen. Each section opens a form with its options, which are
+//! written to the config file of the profile when the form is submitted. The
+//! colors are previewed while they are edited.
+
+use std::{cell::RefCell, rc::Rc, str::FromStr};
+
+use color_eyre::Result;
+use crossterm::event::KeyEvent;
+use pltx_app::{state::AppPopup, App, DefaultWidget, KeyEventHandler, Screen};
+use pltx_config::{
+    color_names, config_file_value, config_key_file, config_value, preview_colors,
+    set_config_values, theme_names, ColorsConfig, UserColors, COLOR_MODES, LOG_LEVELS,
+};
+use pltx_utils::{CLOCKS, WEEKDAYS};
+use pltx_widgets::{
+    Card, Form, FormInput, FormWidget, PopupSize, Scrollable, Selection, Switch, TextInput,
+};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Stylize},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// The most inputs that are shown in a form before it scrolls.
+const MAX_FORM_HEIGHT: u16 = 20;
+
+const DATETIME_KEYS: [&str; 5] = [
+    "date_format",
+    "clock",
+    "first_day_of_week",
+    "relative_dates",
+    "timezone",
+];
+const PROJECT_MANAGEMENT_NUMBERS: [&str; 3] = ["max_lists", "due_soon_days", "archive_purge_days"];
+const PROJECT_MANAGEMENT_CHARS: [&str; 6] = [
+    "completed_char",
+    "overdue_char",
+    "due_soon_char",
+    "in_progress_char",
+    "important_char",
+    "default_char",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    General,
+    Colors,
+    DateTime,
+    Home,
+    ProjectManagement,
+}
+
+const SECTIONS: [(Section, &str); 5] = [
+    (Section::General, "General"),
+    (Section::Colors, "Colors"),
+    (Section::DateTime, "Date & Time"),
+    (Section::Home, "Home"),
+    (Section::ProjectManagement, "Project Management"),
+];
+
+enum FieldInput {
+    Text(Rc<RefCell<TextInput>>),
+    Choice(Rc<RefCell<Selection<String>>>),
+}
+
+/// An input of a form and the config value that it changes.
+struct Field {
+    key: String,
+    /// The config file that the value is written to.
+    file: String,
+    input: FieldInput,
+    /// The value of the input when the form was opened.
+    shown: String,
+    /// The value in the config file when the form was opened.
+    saved: Option<String>,
+}
+
+impl Field {
+    fn value(&self) -> String {
+        match &self.input {
+            FieldInput::Text(input) => (*input).borrow().input_string(),
+            FieldInput::Choice(input) => {
+                let input = (*input).borrow();
+                input
+                    .selected
+                    .iter()
+                    .next()
+                    .map(|i| input.options[*i].0.clone())
+                    .unwrap_or(self.shown.clone())
+            }
+        }
+    }
+
+    /// The value to write to the config file, which is `None` to remove it.
+    /// Returns `None` if the input hasn't changed.
+    fn change(&self) -> Option<Option<String>> {
+        let value = self.value();
+        if value == self.shown {
+            None
+        } else {
+            Some((!value.is_empty()).then_some(value))
+        }
+    }
+
+    /// The value that the config file has after the change.
+    fn edited(&self) -> Option<String> {
+        self.change().unwrap_or(self.saved.clone())
+    }
+}
+
+/// The keys and values that are changed in a config file. A value of `None`
+/// removes the key.
+type FileChanges = (String, Vec<(String, Option<String>)>);
+
+/// The form of the section that is being edited.
+struct Editor {
+    section: Section,
+    form: Form,
+    fields: Vec<Field>,
+    /// Removes the colors of the config file, so the colors of the section
+    /// are only used for the preview.
+    reset_colors: Option<Rc<RefCell<Switch>>>,
+    /// The colors before they were previewed, which are restored if the form
+    /// is closed without saving.
+    colors: ColorsConfig,
+    /// The colors of the config files when the form was opened, which the
+    /// edited colors are previewed with.
+    user_colors: UserColors,
+}
+
+impl Editor {
+    fn resets_colors(&self) -> bool {
+        self.reset_colors
+            .as_ref()
+            .is_some_and(|reset| (*reset).borrow().state)
+    }
+
+    /// The changed values of each config file.
+    fn changes(&self) -> Vec<FileChanges> {
+        let mut files: Vec<FileChanges> = vec![];
+        for field in &self.fields {
+            let change = if self.resets_colors() {
+                field.saved.as_ref().map(|_| None)
+            } else {
+                field.change()
+            };
+            let Some(value) = change else {
+                continue;
+            };
+            match files.iter_mut().find(|(file, _)| *file == field.file) {
+                Some((_, values)) => values.push((field.key.clone(), value)),
+                None => files.push((field.file.clone(), vec![(field.key.clone(), value)])),
+            }
+        }
+        files
+    }
+
+    /// Apply the colors of the inputs, so they can be seen before they are
+    /// saved.
+    fn preview(&self, app: &mut App) {
+        let colors = self
+            .fields
+            .iter()
+            .filter(|_| !self.resets_colors())
+            .filter_map(|field| {
+                let name = field.key.strip_prefix("colors.")?;
+                Some((name.to_string(), field.edited()?))
+            })
+            .collect::<Vec<(String, String)>>();
+        app.config.colors = preview_colors(&self.user_colors, &colors);
+    }
+}
+
+pub struct Settings {
+    selection: Scrollable,
+    editor: Option<Editor>,
+}
+
+impl Screen for Settings {
+    fn init(_: &App) -> Result<Self> {
+        Ok(Self {
+            selection: Scrollable::default(),
+            editor: None,
+        })
+    }
+
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
+        if app.view.is_popup() {
+            if app.popup != AppPopup::None {
+                return;
+            }
+            let Some(editor) = &mut self.editor else {
+                return;
+            };
+            let result = editor.form.key_event_handler(app, key_event);
+            if result.is_submit() {
+                self.submit(app);
+            } else if result.is_closed() {
+                app.config.colors = editor.colors.clone();
+                self.editor = None;
+            } else if editor.section == Section::Colors {
+                editor.preview(app);
+            }
+            return;
+        }
+
+        self.selection.key_event_handler(app, key_event);
+
+        let keys = &app.config.keys.navigation;
+        if keys.open.matches(&key_event) || keys.right.matches(&key_event) {
+            let section = SECTIONS[self.selection.focused].0;
+            self.editor = Some(self.section_editor(app, section));
+            app.view.popup();
+        }
+    }
+
+    fn render(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let [sections_layout, values_layout] = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(30), Constraint::Fill(1)])
+            .areas(area);
+
+        let table = SECTIONS
+            .iter()
+            .enumerate()
+            .map(|(i, (_, title))| {
+                Paragraph::new(format!(" {} ", title)).bg(if self.selection.focused == i {
+                    colors.input_focus_bg
+                } else {
+                    colors.bg
+                })
+            })
+            .collect::<Vec<Paragraph>>();
+        let card = Card::new("Settings", sections_layout);
+        card.render(frame, app, sections_layout, true);
+        self.selection.render(frame, card.child_layout(), table);
+
+        let (section, title) = SECTIONS[self.selection.focused];
+        let card = Card::new(title, values_layout);
+        card.render(frame, app, values_layout, false);
+        let [values_layout, hint_layout] = Layout::default()
+            .horizontal_margin(1)
+            .constraints([Constraint::Fill(1), Constraint::Length(1)])
+            .areas(card.child_layout());
+
+        let keys = section_keys(section, app);
+        let key_width = keys.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+        let lines = keys
+            .iter()
+            .map(|(key, _)| {
+                let value = config_value(&app.config, key).unwrap_or_default();
+                let mut spans = vec![Span::from(format!("{:width$}  ", key, width = key_width))
+                    .fg(colors.secondary_fg)];
+                if let Some(color) = key
+                    .strip_prefix("colors.")
+                    .filter(|name| *name != "preset")
+                    .and_then(|_| Color::from_str(&value).ok())
+                {
+                    spans.push(Span::from("██ ").fg(color));
+                }
+                spans.push(Span::from(value).fg(colors.fg));
+                Line::from(spans)
+            })
+            .collect::<Vec<Line>>();
+        frame.render_widget(Paragraph::new(lines), values_layout);
+
+        frame.render_widget(
+            Paragraph::new(format!(
+                "Press {} to edit, the changes are saved to {}",
+                app.config.keys.navigation.open.first(),
+                app.profile.config_file
+            ))
+            .fg(colors.tertiary_fg),
+            hint_layout,
+        );
+
+        if app.popup == AppPopup::None {
+            if let Some(editor) = &self.editor {
+                editor.form.render(frame, app, area, true);
+            }
+        }
+    }
+}
+
+/// The keys of the options of a section, and the config files that they are
+/// written to.
+fn section_keys(section: Section, app: &App) -> Vec<(String, String)> {
+    let keys = match section {
+        Section::General => vec![
+            "log_level".to_string(),
+            "color_mode".to_string(),
+            "default_profile".to_string(),
+        ],
+        Section::Colors => ["preset".to_string()]
+            .into_iter()
+            .chain(color_names())
+            .map(|name| format!("colors.{}", name))
+            .collect(),
+        Section::DateTime => DATETIME_KEYS
+            .iter()
+            .map(|name| format!("datetime.{}", name))
+            .collect(),
+        Section::Home => ["dashboard_title", "dashboard_message"]
+            .iter()
+            .map(|name| format!("modules.home.{}", name))
+            .collect(),
+        Section::ProjectManagement => PROJECT_MANAGEMENT_NUMBERS
+            .iter()
+            .chain(PROJECT_MANAGEMENT_CHARS.iter())
+            .map(|name| format!("modules.project_management.{}", name))
+            .collect(),
+    };
+    keys.into_iter()
+        .map(|key| {
+            let file = config_key_file(&app.profile, &key);
+            (key, file)
+        })
+        .collect()
+}
+
+/// The title of an input, e.g., "Due Soon Days" for `due_soon_days`.
+fn input_title(key: &str) -> String {
+    key.rsplit('.')
+        .next()
+        .unwrap_or(key)
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// A selection of one of the options, with the current value selected.
+fn choice(key: &str, options: Vec<String>, current: &str) -> Rc<RefCell<Selection<String>>> {
+    let height = options.len() as u16;
+    let mut selection = Selection::new(
+        &input_title(key),
+        options
+            .into_iter()
+            .map(|option| (option.clone(), Span::from(option)))
+            .collect(),
+    )
+    .single()
+    .default_height(height);
+    if let Some(i) = selection.options.iter().position(|(o, _)| o == current) {
+        selection.focused_option = i;
+        selection.selected.insert(i);
+    }
+    selection.form()
+}
+
+impl Settings {
+    /// Create the form of a section with the current values.
+    fn section_editor(&self, app: &App, section: Section) -> Editor {
+        let mut inputs = vec![];
+        let mut fields = vec![];
+        let mut reset_colors = None;
+
+        for (key, file) in section_keys(section, app) {
+            let current = config_value(&app.config, &key).unwrap_or_default();
+            let saved = config_file_value(&file, &key);
+
+            let (input, shown) = match key.as_str() {
+                "log_level" => (
+                    FieldInput::Choice(choice(
+                        &key,
+                        LOG_LEVELS.iter().map(|l| l.to_string()).collect(),
+                        &current,
+                    )),
+                    current,
+                ),
+                "color_mode" => (
+                    FieldInput::Choice(choice(
+                        &key,
+                        COLOR_MODES.iter().map(|m| m.to_string()).collect(),
+                        &current,
+                    )),
+                    current,
+                ),
+                "default_profile" => (
+                    FieldInput::Choice(choice(
+                        &key,
+                        app.config.profiles.iter().map(|p| p.name.clone()).collect(),
+                        &current,
+                    )),
+                    current,
+                ),
+                "datetime.clock" => (
+                    FieldInput::Choice(choice(
+                        &key,
+                        CLOCKS.iter().map(|c| c.to_string()).collect(),
+                        &current,
+                    )),
+                    current,
+                ),
+                "datetime.first_day_of_week" => (
+                    FieldInput::Choice(choice(
+                        &key,
+                        WEEKDAYS.iter().map(|d| d.to_string()).collect(),
+                        &current,
+                    )),
+                    current,
+                ),
+                "datetime.relative_dates" => (
+                    FieldInput::Choice(choice(
+                        &key,
+                        vec!["false".to_string(), "true".to_string()],
+                        &current,
+                    )),
+                    current,
+                ),
+                "colors.preset" => (
+                    FieldInput::Choice(choice(&key, theme_names(), &current)),
+                    current,
+                ),
+                // A color is empty if it isn't set in the config file, and the
+                // current color is shown instead.
+                key if key.starts_with("colors.") => {
+                    let shown = saved.clone().unwrap_or_default();
+                    let input = TextInput::new(&input_title(key))
+                        .placeholder(&current)
+                        .max(20)
+                        .default_input(shown.clone());
+                    (FieldInput::Text(input.form()), shown)
+                }
+                key => {
+                    let input = TextInput::new(&input_title(key))
+                        .max(100)
+                        .default_input(current.clone());
+                    (FieldInput::Text(input.form()), current)
+                }
+            };
+
+            match &input {
+                FieldInput::Text(input) => inputs.push(FormInput(input.clone())),
+                FieldInput::Choice(input) => inputs.push(FormInput(input.clone())),
+            }
+            fields.push(Field {
+                key,
+                file,
+                input,
+                shown,
+                saved,
+            });
+
+            if section == Section::Colors && reset_colors.is_none() {
+                let reset = Switch::from("Reset Colors").form();
+                inputs.push(FormInput(reset.clone()));
+                reset_colors = Some(reset);
+            }
+        }
+
+        let title = SECTIONS
+            .iter()
+            .find(|(s, _)| *s == section)
+            .map(|(_, title)| format!("{} Settings", title))
+            .unwrap_or_default();
+        let height = (inputs.len() as u16 + 2).min(MAX_FORM_HEIGHT);
+
+        Editor {
+            section,
+            form: Form::from(inputs)
+                .default_title(&title)
+                .default_size(PopupSize::default().height(height)),
+            fields,
+            reset_colors,
+            colors: app.config.colors.clone(),
+            user_colors: UserColors::read(&app.profile),
+        }
+    }
+
+    /// Write the changes to the config files. The form stays open if a value is
+    /// invalid.
+    fn submit(&mut self, app: &mut App) {
+        let Some(editor) = &self.editor else {
+            return;
+        };
+
+        let changes = editor.changes();
+        for (file, values) in &changes {
+            let values = values
+                .iter()
+                .map(|(key, value)| (key.as_str(), value.as_deref()))
+                .collect::<Vec<(&str, Option<&str>)>>();
+            if let Err(err) = set_config_values(file, &values) {
+                app.error(format!("The settings weren't saved: {}", err));
+                return;
+            }
+        }
+
+        if changes.is_empty() {
+            app.status_message("No settings were changed");
+        } else {
+            let files = changes
+                .iter()
+                .map(|(file, _)| file.as_str())
+                .collect::<Vec<&str>>();
+            app.success(format!("Saved the settings to {}", files.join(", ")));
+        }
+        // The previewed colors are kept until the config is reloaded.
+        self.editor = None;
+        app.view.default();
+    }
+}
@@ -1,5 +1,3 @@
-BEGIN;
-
 CREATE TABLE IF NOT EXISTS project (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     title TEXT NOT NULL,
@@ -126,5 +124,3 @@ CREATE TABLE IF NOT EXISTS card_subtask (
 CREATE INDEX IF NOT EXISTS idx_card_subtask_position ON card_subtask (position);
 CREATE INDEX IF NOT EXISTS idx_card_subtask_project_id ON card_subtask (project_id);
 CREATE INDEX IF NOT EXISTS idx_card_subtask_card_id ON card_subtask (card_id);
-
-COMMIT;
@@ -0,0 +1 @@
+ALTER TABLE project_card ADD COLUMN completed_at DATETIME;
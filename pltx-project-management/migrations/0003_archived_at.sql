@@ -0,0 +1,11 @@
+ALTER TABLE project ADD COLUMN archived_at DATETIME;
+ALTER TABLE project_label ADD COLUMN archived_at DATETIME;
+ALTER TABLE project_list ADD COLUMN archived_at DATETIME;
+ALTER TABLE project_card ADD COLUMN archived_at DATETIME;
+ALTER TABLE card_subtask ADD COLUMN archived_at DATETIME;
+
+UPDATE project SET archived = 0 WHERE archived IS NULL;
+UPDATE project_label SET archived = 0 WHERE archived IS NULL;
+UPDATE project_list SET archived = 0 WHERE archived IS NULL;
+UPDATE project_card SET archived = 0 WHERE archived IS NULL;
+UPDATE card_subtask SET archived = 0 WHERE archived IS NULL;
@@ -0,0 +1,94 @@
+CREATE TABLE IF NOT EXISTS card_activity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id INTEGER NOT NULL,
+    card_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    field TEXT,
+    old_value TEXT,
+    new_value TEXT,
+    created_at DATETIME NOT NULL,
+    FOREIGN KEY (project_id)
+        REFERENCES project (id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE,
+    FOREIGN KEY (card_id)
+        REFERENCES project_card (id)
+            ON DELETE CASCADE
+            ON UPDATE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_card_activity_project_id ON card_activity (project_id);
+CREATE INDEX IF NOT EXISTS idx_card_activity_card_id ON card_activity (card_id);
+
+-- The activity is recorded by triggers, so every change to a card is included no matter where
+-- it's made.
+
+CREATE TRIGGER IF NOT EXISTS card_activity_title
+AFTER UPDATE OF title ON project_card WHEN OLD.title IS NOT NEW.title
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'edit', 'title', OLD.title, NEW.title, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_description
+AFTER UPDATE OF description ON project_card WHEN OLD.description IS NOT NEW.description
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'edit', 'description', OLD.description, NEW.description, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_start_date
+AFTER UPDATE OF start_date ON project_card WHEN OLD.start_date IS NOT NEW.start_date
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'edit', 'start_date', OLD.start_date, NEW.start_date, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_due_date
+AFTER UPDATE OF due_date ON project_card WHEN OLD.due_date IS NOT NEW.due_date
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'edit', 'due_date', OLD.due_date, NEW.due_date, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_reminder
+AFTER UPDATE OF reminder ON project_card WHEN OLD.reminder IS NOT NEW.reminder
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'edit', 'reminder', OLD.reminder, NEW.reminder, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_important
+AFTER UPDATE OF important ON project_card WHEN OLD.important IS NOT NEW.important
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, CASE WHEN NEW.important THEN 'important' ELSE 'not_important' END, NULL, NULL, NULL, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_completed
+AFTER UPDATE OF completed ON project_card WHEN OLD.completed IS NOT NEW.completed
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, CASE WHEN NEW.completed THEN 'complete' ELSE 'uncomplete' END, NULL, NULL, NULL, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_list
+AFTER UPDATE OF list_id ON project_card WHEN OLD.list_id IS NOT NEW.list_id
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'move', NULL, (SELECT title FROM project_list WHERE id = OLD.list_id), (SELECT title FROM project_list WHERE id = NEW.list_id), strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_archived
+AFTER UPDATE OF archived ON project_card WHEN OLD.archived IS NOT NEW.archived
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, CASE WHEN NEW.archived THEN 'archive' ELSE 'restore' END, NULL, NULL, NULL, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_activity_subtask
+AFTER UPDATE OF completed ON card_subtask WHEN OLD.completed IS NOT NEW.completed
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.card_id, CASE WHEN NEW.completed THEN 'check' ELSE 'uncheck' END, NULL, NULL, NEW.value, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
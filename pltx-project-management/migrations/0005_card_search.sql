@@ -0,0 +1,76 @@
+-- A full-text index of the cards, where the rowid is the ID of the card. Each row is rebuilt from
+-- the card, its project, list and subtasks whenever any of them change.
+CREATE VIRTUAL TABLE IF NOT EXISTS card_search USING fts5 (
+    project,
+    list,
+    title,
+    description,
+    subtasks,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO card_search (rowid, project, list, title, description, subtasks)
+    SELECT c.id, p.title, l.title, c.title, c.description,
+        (SELECT group_concat(s.value, ' ') FROM card_subtask s WHERE s.card_id = c.id)
+    FROM project_card c
+    JOIN project p ON p.id = c.project_id
+    JOIN project_list l ON l.id = c.list_id;
+
+CREATE TRIGGER IF NOT EXISTS card_search_card_insert AFTER INSERT ON project_card
+BEGIN
+    INSERT INTO card_search (rowid, project, list, title, description, subtasks)
+        SELECT NEW.id, p.title, l.title, NEW.title, NEW.description,
+            (SELECT group_concat(s.value, ' ') FROM card_subtask s WHERE s.card_id = NEW.id)
+        FROM project p, project_list l
+        WHERE p.id = NEW.project_id AND l.id = NEW.list_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_search_card_update
+AFTER UPDATE OF title, description, list_id, project_id ON project_card
+BEGIN
+    DELETE FROM card_search WHERE rowid = OLD.id;
+    INSERT INTO card_search (rowid, project, list, title, description, subtasks)
+        SELECT NEW.id, p.title, l.title, NEW.title, NEW.description,
+            (SELECT group_concat(s.value, ' ') FROM card_subtask s WHERE s.card_id = NEW.id)
+        FROM project p, project_list l
+        WHERE p.id = NEW.project_id AND l.id = NEW.list_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_search_card_delete AFTER DELETE ON project_card
+BEGIN
+    DELETE FROM card_search WHERE rowid = OLD.id;
+END;
+
+-- Rebuild the row of the card that a subtask belongs to.
+CREATE TRIGGER IF NOT EXISTS card_search_subtask_insert AFTER INSERT ON card_subtask
+BEGIN
+    UPDATE card_search
+        SET subtasks = (SELECT group_concat(s.value, ' ') FROM card_subtask s WHERE s.card_id = NEW.card_id)
+        WHERE rowid = NEW.card_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_search_subtask_update AFTER UPDATE OF value ON card_subtask
+BEGIN
+    UPDATE card_search
+        SET subtasks = (SELECT group_concat(s.value, ' ') FROM card_subtask s WHERE s.card_id = NEW.card_id)
+        WHERE rowid = NEW.card_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_search_subtask_delete AFTER DELETE ON card_subtask
+BEGIN
+    UPDATE card_search
+        SET subtasks = (SELECT group_concat(s.value, ' ') FROM card_subtask s WHERE s.card_id = OLD.card_id)
+        WHERE rowid = OLD.card_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_search_project_update AFTER UPDATE OF title ON project
+BEGIN
+    UPDATE card_search SET project = NEW.title
+        WHERE rowid IN (SELECT id FROM project_card WHERE project_id = NEW.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS card_search_list_update AFTER UPDATE OF title ON project_list
+BEGIN
+    UPDATE card_search SET list = NEW.title
+        WHERE rowid IN (SELECT id FROM project_card WHERE list_id = NEW.id);
+END;
@@ -0,0 +1,31 @@
+-- The start and due dates were stored as the time that was typed in UTC, but they were shown in
+-- local time. They are converted with the timezone of the config after the migrations, since the
+-- timezone isn't known here, and the row is deleted once they have been.
+CREATE TABLE IF NOT EXISTS input_timezone_fix (
+    id INTEGER PRIMARY KEY CHECK (id = 1)
+);
+
+INSERT OR IGNORE INTO input_timezone_fix (id) VALUES (1);
+
+-- The conversion isn't an edit of the cards, so the date activity isn't recorded while it's
+-- pending. The existing activity is converted with the dates.
+
+DROP TRIGGER IF EXISTS card_activity_start_date;
+
+CREATE TRIGGER card_activity_start_date
+AFTER UPDATE OF start_date ON project_card
+WHEN OLD.start_date IS NOT NEW.start_date AND NOT EXISTS (SELECT 1 FROM input_timezone_fix)
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'edit', 'start_date', OLD.start_date, NEW.start_date, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
+
+DROP TRIGGER IF EXISTS card_activity_due_date;
+
+CREATE TRIGGER card_activity_due_date
+AFTER UPDATE OF due_date ON project_card
+WHEN OLD.due_date IS NOT NEW.due_date AND NOT EXISTS (SELECT 1 FROM input_timezone_fix)
+BEGIN
+    INSERT INTO card_activity (project_id, card_id, action, field, old_value, new_value, created_at)
+    VALUES (NEW.project_id, NEW.id, 'edit', 'due_date', OLD.due_date, NEW.due_date, strftime('%Y-%m-%dT%H:%M:%f+00:00', 'now'));
+END;
@@ -0,0 +1,129 @@
+use std::time::Instant;
+
+use color_eyre::Result;
+use pltx_database::Database;
+use pltx_utils::DateTime;
+use tracing::info;
+
+/// The number of activities shown in the recent activity of a project.
+const RECENT_ACTIVITY_LIMIT: i32 = 200;
+
+/// A change to a card. Activities are recorded by the triggers in the
+/// `card_activity` migration.
+#[derive(Clone)]
+pub struct CardActivity {
+    pub card_id: i32,
+    pub card_title: String,
+    action: String,
+    field: Option<String>,
+    old_value: Option<String>,
+    new_value: Option<String>,
+    pub created_at: DateTime,
+}
+
+impl CardActivity {
+    /// Describe the activity, e.g., `Moved from "To Do" to "Done"`.
+    pub fn describe(&self) -> String {
+        let old_value = self.old_value.clone().unwrap_or_default();
+        let new_value = self.new_value.clone().unwrap_or_default();
+
+        match self.action.as_str() {
+            "edit" => {
+                let field = self.field.clone().unwrap_or_default();
+                match (&self.old_value, &self.new_value) {
+                    (None, Some(_)) => format!(
+                        "Set the {} to {}",
+                        field_name(&field),
+                        self.value(&new_value)
+                    ),
+                    (Some(_), None) => format!("Removed the {}", field_name(&field)),
+                    _ if field == "description" => String::from("Changed the description"),
+                    _ => format!(
+                        "Changed the {} from {} to {}",
+                        field_name(&field),
+                        self.value(&old_value),
+                        self.value(&new_value)
+                    ),
+                }
+            }
+            "move" => format!("Moved from \"{}\" to \"{}\"", old_value, new_value),
+            "complete" => String::from("Marked as completed"),
+            "uncomplete" => String::from("Marked as not completed"),
+            "important" => String::from("Marked as important"),
+            "not_important" => String::from("Marked as not important"),
+            "archive" => String::from("Archived"),
+            "restore" => String::from("Restored from the archive"),
+            "check" => format!("Checked the subtask \"{}\"", new_value),
+            "uncheck" => format!("Unchecked the subtask \"{}\"", new_value),
+            action => action.to_string(),
+        }
+    }
+
+    /// Display a value of the field that was edited, where dates are shown in
+    /// local time.
+    fn value(&self, value: &str) -> String {
+        match self.field.as_deref() {
+            Some("start_date" | "due_date" | "reminder") if DateTime::is_valid_db(value) => {
+                DateTime::from(value).display()
+            }
+            _ => format!("\"{}\"", value),
+        }
+    }
+}
+
+fn field_name(field: &str) -> String {
+    field.replace('_', " ")
+}
+
+const ACTIVITY_COLUMNS: &str = "SELECT a.card_id, c.title, a.action, a.field, a.old_value, \
+                                a.new_value, a.created_at FROM card_activity a JOIN project_card \
+                                c ON c.id = a.card_id";
+
+fn db_get_activity<P: rusqlite::Params>(
+    db: &Database,
+    query: &str,
+    params: P,
+) -> Result<Vec<CardActivity>> {
+    let conn = db.conn();
+    let mut stmt = conn.prepare(query)?;
+    let activity_iter = stmt.query_map(params, |r| {
+        Ok(CardActivity {
+            card_id: r.get(0)?,
+            card_title: r.get(1)?,
+            action: r.get(2)?,
+            field: r.get(3)?,
+            old_value: r.get(4)?,
+            new_value: r.get(5)?,
+            created_at: DateTime::from_db(r.get(6)?),
+        })
+    })?;
+    Ok(activity_iter.collect::<rusqlite::Result<Vec<CardActivity>>>()?)
+}
+
+/// Get the activity of a card, from newest to oldest.
+pub fn db_get_card_activity(db: &Database, card_id: i32) -> Result<Vec<CardActivity>> {
+    let start = Instant::now();
+    let query = format!(
+        "{} WHERE a.card_id = ?1 ORDER BY a.id DESC",
+        ACTIVITY_COLUMNS
+    );
+    let activity = db_get_activity(db, &query, [card_id])?;
+    info!("get card activity query executed in {:?}", start.elapsed());
+    Ok(activity)
+}
+
+/// Get the most recent activity of the cards in a project, from newest to
+/// oldest.
+pub fn db_get_project_activity(db: &Database, project_id: i32) -> Result<Vec<CardActivity>> {
+    let start = Instant::now();
+    let query = format!(
+        "{} WHERE a.project_id = ?1 ORDER BY a.id DESC LIMIT ?2",
+        ACTIVITY_COLUMNS
+    );
+    let activity = db_get_activity(db, &query, (project_id, RECENT_ACTIVITY_LIMIT))?;
+    info!(
+        "get project activity query executed in {:?}",
+        start.elapsed()
+    );
+    Ok(activity)
+}
@@ -1,20 +1,28 @@
 //! The Project Management Modules - Similar to Trello or GitHub Projects.
 
+use std::time::Instant;
+
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
 use pltx_app::{App, DefaultWidget, KeyEventHandler, Module, Screen};
+use pltx_database::{Database, OrderedTable};
+use pltx_utils::DateTime;
 use pltx_widgets::Tabs;
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     Frame,
 };
+use tracing::info;
 
+mod activity;
 mod list_projects;
 mod open_project;
 pub mod popups;
 mod projects;
+mod search;
 
 use projects::Projects;
+pub use search::{db_search_cards, CardSearchResult};
 
 include!("generated_sql.rs");
 
@@ -37,7 +45,8 @@ pub struct ProjectManagement {
 
 impl Module<Result<()>> for ProjectManagement {
     fn init(app: &App) -> Result<Self> {
-        app.db.conn().execute_batch(SQL)?;
+        migrate(&app.db)?;
+        purge_archived(app)?;
 
         Ok(Self {
             tabs: Tabs::from([
@@ -77,3 +86,168 @@ impl Module<Result<()>> for ProjectManagement {
         }
     }
 }
+
+impl ProjectManagement {
+    /// Fetch the data again after it was changed outside of the module, e.g.,
+    /// by undoing a change.
+    pub fn refresh(&mut self, app: &App) -> Result<()> {
+        self.screens.projects.refresh(app)
+    }
+
+    /// Go to a card in the project it belongs to, e.g., from a search result.
+    pub fn open_card(&mut self, app: &mut App, project_id: i32, card_id: i32) -> Result<()> {
+        self.tabs.active = Tab::Projects;
+        self.screens.projects.open_card(app, project_id, card_id)
+    }
+}
+
+/// Apply the migrations of the module and track the changes to its tables,
+/// e.g., before syncing without opening the module.
+pub fn migrate(db: &Database) -> Result<()> {
+    db.migrate("project_management", MIGRATIONS)?;
+    fix_input_timezones(db)?;
+    db.track_changes(&[
+        "project",
+        "project_label",
+        "project_list",
+        "project_card",
+        "card_label",
+        "card_subtask",
+    ])
+}
+
+/// Permanently delete the projects, lists, and cards that were archived more
+/// than `archive_purge_days` ago.
+fn purge_archived(app: &App) -> Result<()> {
+    let days = app.config.modules.project_management.archive_purge_days;
+    if days <= 0 {
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let before = DateTime::days_ago(days);
+    let purged = app.db.transaction(|tx| {
+        let mut purged = 0;
+        for table in [
+            OrderedTable::Project,
+            OrderedTable::ProjectList,
+            OrderedTable::ProjectCard,
+        ] {
+            purged += table.purge_archived(tx.conn(), &before)?;
+        }
+        Ok(purged)
+    })?;
+
+    info!(
+        "purged {} archived items older than {} days in {:?}",
+        purged,
+        days,
+        start.elapsed()
+    );
+
+    Ok(())
+}
+
+/// Convert the start and due dates that were stored before the timezone was
+/// applied to date inputs, once the migration that marks them has been
+/// applied. Every date with a time that exists while the marker does was
+/// stored that way. The activity and the undo history that record them are
+/// converted too, so undoing an older edit doesn't bring back an unconverted
+/// date. The migration keeps the conversion from being recorded as activity.
+fn fix_input_timezones(db: &Database) -> Result<()> {
+    let start = Instant::now();
+    let fixed = db.transaction(|tx| {
+        let conn = tx.conn();
+        let pending: bool = conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM input_timezone_fix)",
+            (),
+            |r| r.get(0),
+        )?;
+        if !pending {
+            return Ok(None);
+        }
+
+        let fix =
+            |value: Option<String>| value.map(|v| DateTime::fix_input_timezone(&v).unwrap_or(v));
+
+        let mut stmt = conn.prepare(
+            "SELECT id, start_date, due_date FROM project_card WHERE start_date IS NOT NULL OR \
+             due_date IS NOT NULL",
+        )?;
+        let cards = stmt
+            .query_map((), |r| {
+                Ok((
+                    r.get::<_, i32>(0)?,
+                    r.get::<_, Option<String>>(1)?,
+                    r.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<(i32, Option<String>, Option<String>)>>>()?;
+        let fixed = cards.len();
+        for (id, start_date, due_date) in cards {
+            tx.execute(
+                "UPDATE project_card SET start_date = ?1, due_date = ?2 WHERE id = ?3",
+                (fix(start_date), fix(due_date), id),
+            )?;
+        }
+
+        let mut stmt = conn.prepare(
+            "SELECT id, old_value, new_value FROM card_activity WHERE field IN ('start_date', \
+             'due_date')",
+        )?;
+        let activity = stmt
+            .query_map((), |r| {
+                Ok((
+                    r.get::<_, i32>(0)?,
+                    r.get::<_, Option<String>>(1)?,
+                    r.get::<_, Option<String>>(2)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<(i32, Option<String>, Option<String>)>>>()?;
+        for (id, old_value, new_value) in activity {
+            tx.execute(
+                "UPDATE card_activity SET old_value = ?1, new_value = ?2 WHERE id = ?3",
+                (fix(old_value), fix(new_value), id),
+            )?;
+        }
+
+        for image in ["before", "after"] {
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, json_extract({image}, '$.start_date'), json_extract({image}, \
+                 '$.due_date') FROM journal_change WHERE table_name = 'project_card' AND {image} \
+                 IS NOT NULL"
+            ))?;
+            let changes = stmt
+                .query_map((), |r| {
+                    Ok((
+                        r.get::<_, i32>(0)?,
+                        r.get::<_, Option<String>>(1)?,
+                        r.get::<_, Option<String>>(2)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<(i32, Option<String>, Option<String>)>>>()?;
+            for (id, start_date, due_date) in changes {
+                tx.execute(
+                    &format!(
+                        "UPDATE journal_change SET {image} = json_set({image}, '$.start_date', \
+                         ?1, '$.due_date', ?2) WHERE id = ?3"
+                    ),
+                    (fix(start_date), fix(due_date), id),
+                )?;
+            }
+        }
+
+        tx.execute("DELETE FROM input_timezone_fix", ())?;
+        Ok(Some(fixed))
+    })?;
+
+    if let Some(fixed) = fixed {
+        info!(
+            "converted the dates of {} cards to the input timezone in {:?}",
+            fixed,
+            start.elapsed()
+        );
+    }
+
+    Ok(())
+}
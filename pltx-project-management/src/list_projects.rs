@@ -1,9 +1,9 @@
 use std::time::Instant;
 
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, KeyEventHandler, Screen};
-use pltx_database::Database;
+use pltx_database::{Database, OrderedTable};
 use pltx_utils::{centered_rect, DateTime};
 use pltx_widgets::Scrollable;
 use ratatui::{
@@ -23,6 +23,7 @@ pub struct Project {
     position: i32,
     created_at: DateTime,
     updated_at: DateTime,
+    archived_at: Option<DateTime>,
     labels: i32,
     lists: i32,
     total_cards: i32,
@@ -35,6 +36,8 @@ pub struct Project {
 pub struct ListProjects {
     pub selection: Scrollable,
     pub projects: Vec<Project>,
+    /// Whether the archived projects are shown instead of the active ones.
+    pub archived: bool,
 }
 
 impl Screen<Result<bool>> for ListProjects {
@@ -42,6 +45,7 @@ impl Screen<Result<bool>> for ListProjects {
         let mut list_projects = ListProjects {
             projects: vec![],
             selection: Scrollable::default().cols([5, 50, 7, 13, 10, 9, 9, 8]),
+            archived: false,
         };
 
         list_projects.db_get_projects(app)?;
@@ -52,24 +56,33 @@ impl Screen<Result<bool>> for ListProjects {
     fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) -> Result<bool> {
         self.selection.key_event_handler(app, key_event);
 
-        if app.mode.is_normal() {
-            match key_event.code {
-                KeyCode::Char('d') => app.mode.delete(),
-                KeyCode::Char('J') => self.increment_project_position(app)?,
-                KeyCode::Char('K') => self.decrement_project_position(app)?,
-                _ => {}
-            }
-        }
+        let keys = app.config.keys.clone();
 
-        if app.mode.is_delete() {
-            match key_event.code {
-                KeyCode::Char('y') => {
-                    self.db_delete_project(&app.db)?;
-                    self.db_get_projects(app)?;
-                    app.mode.normal();
+        if app.mode.is_normal() {
+            if keys.editing.archived.matches(&key_event) {
+                self.archived = !self.archived;
+                self.selection.focused = 0;
+                self.db_get_projects(app)?;
+            } else if self.archived {
+                if keys.editing.restore.matches(&key_event) {
+                    self.db_unarchive_project(app)?;
+                } else if keys.editing.delete.matches(&key_event) && !self.projects.is_empty() {
+                    app.mode.delete();
                 }
-                KeyCode::Char('n') => app.mode.normal(),
-                _ => {}
+            } else if keys.editing.delete.matches(&key_event) {
+                self.db_archive_project(app)?;
+            } else if keys.project_management.move_down.matches(&key_event) {
+                self.increment_project_position(app)?;
+            } else if keys.project_management.move_up.matches(&key_event) {
+                self.decrement_project_position(app)?;
+            }
+        } else if app.mode.is_delete() {
+            if keys.confirm.yes.matches(&key_event) {
+                self.db_delete_project(&app.db)?;
+                self.db_get_projects(app)?;
+                app.mode.normal();
+            } else if keys.confirm.no.matches(&key_event) {
+                app.mode.normal();
             }
         }
         Ok(false)
@@ -103,18 +116,36 @@ impl Screen<Result<bool>> for ListProjects {
             .areas(centered_list_layout);
 
         if self.projects.is_empty() {
-            let content = Paragraph::new(Text::from(vec![Line::from(vec![
-                Span::from("You have no projects. Press "),
-                Span::styled("n", Style::new().bold().fg(colors.keybind_key)),
-                Span::from(" to create a new project."),
-            ])]));
+            let content = Paragraph::new(Text::from(vec![Line::from(if self.archived {
+                vec![
+                    Span::from("You have no archived projects. Press "),
+                    Span::styled(
+                        app.config.keys.editing.archived.first(),
+                        Style::new().bold().fg(colors.keybind_key),
+                    ),
+                    Span::from(" to go back to your projects."),
+                ]
+            } else {
+                vec![
+                    Span::from("You have no projects. Press "),
+                    Span::styled(
+                        app.config.keys.editing.new.first(),
+                        Style::new().bold().fg(colors.keybind_key),
+                    ),
+                    Span::from(" to create a new project."),
+                ]
+            })]));
 
             frame.render_widget(content, list_layout);
             frame.render_widget(Block::new(), info_layout);
         } else {
             let header = [
                 Paragraph::new(" "),
-                Paragraph::new("Title"),
+                Paragraph::new(if self.archived {
+                    "Archived Projects"
+                } else {
+                    "Title"
+                }),
                 Paragraph::new("Cards"),
                 Paragraph::new("In Progress"),
                 Paragraph::new("Due Soon"),
@@ -131,7 +162,12 @@ impl Screen<Result<bool>> for ListProjects {
                 .enumerate()
                 .map(|(i, p)| {
                     vec![
-                        Paragraph::new(format!(" {}", p.position)).fg(colors.secondary_fg),
+                        Paragraph::new(if self.archived {
+                            String::from(" -")
+                        } else {
+                            format!(" {}", p.position)
+                        })
+                        .fg(colors.secondary_fg),
                         Paragraph::new(p.title.to_string()),
                         Paragraph::new(if p.total_cards > 0 {
                             p.total_cards.to_string()
@@ -289,7 +325,17 @@ impl Screen<Result<bool>> for ListProjects {
                     Span::from(project.updated_at.display()),
                 ]),
             ];
-            let info_text = Text::from([info_1, description, info_2].concat());
+            let info_3 = project
+                .archived_at
+                .iter()
+                .map(|archived_at| {
+                    Line::from(vec![
+                        Span::styled("Archived At: ", Style::new().fg(colors.secondary_fg)),
+                        Span::from(archived_at.display()),
+                    ])
+                })
+                .collect::<Vec<Line>>();
+            let info_text = Text::from([info_1, description, info_2, info_3].concat());
             let info_content = Paragraph::new(info_text).block(
                 Block::new()
                     .title(" Project Information ")
@@ -313,14 +359,27 @@ impl ListProjects {
         Some(self.projects[self.selection.focused].id)
     }
 
+    /// The title of the focused project, used to describe changes to it.
+    fn get_title(&self) -> &str {
+        self.projects
+            .get(self.selection.focused)
+            .map(|p| p.title.as_str())
+            .unwrap_or_default()
+    }
+
     pub fn db_get_projects(&mut self, app: &App) -> Result<()> {
         let _guard = info_span!("project management", screen = "list projects").entered();
 
         let start = Instant::now();
 
         let conn = app.db.conn();
-        let project_query = "SELECT id, title, description, position, created_at, updated_at FROM \
-                             project ORDER BY position";
+        let project_query = if self.archived {
+            "SELECT id, title, description, position, created_at, updated_at, archived_at FROM \
+             project WHERE archived = 1 ORDER BY archived_at DESC"
+        } else {
+            "SELECT id, title, description, position, created_at, updated_at, archived_at FROM \
+             project WHERE archived = 0 ORDER BY position"
+        };
         let mut project_stmt = conn.prepare(project_query)?;
         let project_iter = project_stmt.query_map([], |row| {
             Ok(Project {
@@ -330,6 +389,7 @@ impl ListProjects {
                 position: row.get(3)?,
                 created_at: DateTime::from_db(row.get(4)?),
                 updated_at: DateTime::from_db(row.get(5)?),
+                archived_at: DateTime::from_db_option(row.get(6)?),
                 labels: 0,
                 lists: 0,
                 total_cards: 0,
@@ -350,6 +410,10 @@ impl ListProjects {
         projects = self.db_get_cards(app, &mut projects)?;
 
         self.projects = projects;
+        self.selection.focused = self
+            .selection
+            .focused
+            .min(self.projects.len().saturating_sub(1));
 
         info!(
             "get projects query durations totaled at {:?}",
@@ -367,11 +431,9 @@ impl ListProjects {
         let label_iter = stmt.query_map([], |row| row.get::<usize, i32>(0))?;
         for label_id in label_iter {
             let id = label_id?;
-            let index = projects
-                .iter()
-                .position(|p| p.id == id)
-                .expect("failed to get project index");
-            projects[index].labels += 1;
+            if let Some(index) = projects.iter().position(|p| p.id == id) {
+                projects[index].labels += 1;
+            }
         }
         info!("get project labels query executed in {:?}", start.elapsed());
         Ok(projects.to_vec())
@@ -380,16 +442,14 @@ impl ListProjects {
     fn db_get_lists(&self, db: &Database, projects: &mut [Project]) -> Result<Vec<Project>> {
         let start = Instant::now();
         let conn = db.conn();
-        let query = "SELECT project_id FROM project_list";
+        let query = "SELECT project_id FROM project_list WHERE archived = 0";
         let mut stmt = conn.prepare(query)?;
         let id_iter = stmt.query_map([], |r| r.get::<usize, i32>(0))?;
         for list_id in id_iter {
             let id = list_id?;
-            let index = projects
-                .iter()
-                .position(|p| p.id == id)
-                .expect("failed to get project index");
-            projects[index].lists += 1;
+            if let Some(index) = projects.iter().position(|p| p.id == id) {
+                projects[index].lists += 1;
+            }
         }
         info!("get project lists query executed in {:?}", start.elapsed());
         Ok(projects.to_vec())
@@ -405,8 +465,9 @@ impl ListProjects {
         }
 
         let conn = app.db.conn();
-        let query = "SELECT project_id, start_date, due_date, important FROM project_card ORDER \
-                     BY position";
+        let query = "SELECT project_id, start_date, due_date, important FROM project_card WHERE \
+                     archived = 0 AND list_id IN (SELECT id FROM project_list WHERE archived = 0) \
+                     ORDER BY position";
         let mut stmt = conn.prepare(query)?;
         let card_iter = stmt.query_map([], |row| {
             Ok(ListProjectCard {
@@ -419,13 +480,12 @@ impl ListProjects {
 
         for c in card_iter {
             let card = c?;
-            let index = projects
-                .iter()
-                .position(|p| p.id == card.project_id)
-                .expect("failed to get project index");
+            let Some(index) = projects.iter().position(|p| p.id == card.project_id) else {
+                continue;
+            };
             projects[index].total_cards += 1;
 
-            if card.start_date.as_ref().is_some_and(|d| d.is_past())
+            if card.start_date.as_ref().is_some_and(|d| d.has_started())
                 && !card.due_date.as_ref().is_some_and(|d| d.is_past())
             {
                 projects[index].cards_in_progress += 1;
@@ -453,45 +513,68 @@ impl ListProjects {
         Ok(projects.to_vec())
     }
 
-    fn db_delete_project(&mut self, db: &Database) -> Result<()> {
+    fn db_archive_project(&mut self, app: &App) -> Result<()> {
         if let Some(id) = self.get_id() {
             let start = Instant::now();
-            struct Select {
-                position: i32,
-            }
-            let conn = db.conn();
-            let select_query = "SELECT position FROM project WHERE id = ?1";
-            let mut select_stmt = conn.prepare(select_query)?;
-            let select = select_stmt.query_row([id], |r| {
-                Ok(Select {
-                    position: r.get(0)?,
-                })
+            let description = format!("archive project \"{}\"", self.get_title());
+            app.db.undoable(&description, |tx| {
+                OrderedTable::Project.archive(tx.conn(), id)
             })?;
+            self.unfocus_removed_project();
+            info!("archive project query executed in {:?}", start.elapsed());
+            self.db_get_projects(app)?;
+        }
 
-            db.execute("DELETE FROM project WHERE id = ?1", [id])?;
+        Ok(())
+    }
 
-            let update_position_query =
-                "UPDATE project SET position = position - 1, updated_at = ?1 WHERE position > ?2";
-            db.execute(update_position_query, (DateTime::now(), select.position))?;
+    fn db_unarchive_project(&mut self, app: &App) -> Result<()> {
+        if let Some(id) = self.get_id() {
+            let start = Instant::now();
+            let description = format!("restore project \"{}\"", self.get_title());
+            app.db.undoable(&description, |tx| {
+                OrderedTable::Project.unarchive(tx.conn(), id)
+            })?;
+            self.unfocus_removed_project();
+            info!("unarchive project query executed in {:?}", start.elapsed());
+            self.db_get_projects(app)?;
+        }
 
-            if self.selection.focused == self.selection.row_count.borrow().saturating_sub(1)
-                && self.selection.focused != 0
-            {
-                self.selection.focused -= 1;
-            }
+        Ok(())
+    }
 
+    fn db_delete_project(&mut self, db: &Database) -> Result<()> {
+        if let Some(id) = self.get_id() {
+            let start = Instant::now();
+            let description = format!("delete project \"{}\"", self.get_title());
+            db.undoable(&description, |tx| {
+                OrderedTable::Project.delete(tx.conn(), id)
+            })?;
+            self.unfocus_removed_project();
             info!("delete project query executed in {:?}", start.elapsed());
         }
 
         Ok(())
     }
 
+    /// Move the focus up if the focused project was the last one and is
+    /// removed from the view.
+    fn unfocus_removed_project(&mut self) {
+        if self.selection.focused == self.projects.len().saturating_sub(1)
+            && self.selection.focused != 0
+        {
+            self.selection.focused -= 1;
+        }
+    }
+
     fn increment_project_position(&mut self, app: &App) -> Result<()> {
         let start = Instant::now();
         if !self.projects.is_empty() && self.selection.focused + 1 != self.projects.len() {
             let id = self.projects[self.selection.focused].id;
-            let next_id = self.projects[self.selection.focused + 1].id;
-            app.db.increment_position("project", id, next_id)?;
+            let description = format!("move project \"{}\"", self.get_title());
+            app.db.undoable(&description, |tx| {
+                OrderedTable::Project.move_by(tx.conn(), id, 1)
+            })?;
             self.selection.focused += 1;
             info!(
                 "increment project position query executed in {:?}",
@@ -506,8 +589,10 @@ impl ListProjects {
         let start = Instant::now();
         if !self.projects.is_empty() && self.selection.focused != 0 {
             let id = self.projects[self.selection.focused].id;
-            let prev_id = self.projects[self.selection.focused - 1].id;
-            app.db.decrement_position("project", id, prev_id)?;
+            let description = format!("move project \"{}\"", self.get_title());
+            app.db.undoable(&description, |tx| {
+                OrderedTable::Project.move_by(tx.conn(), id, -1)
+            })?;
             self.selection.focused -= 1;
             info!(
                 "decrement project position query executed in {:?}",
@@ -1,9 +1,9 @@
-use std::{collections::HashSet, str::FromStr, time::Instant};
+use std::{collections::HashSet, fmt, str::FromStr, time::Instant};
 
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{state::AppPopup, App, DefaultWidget, KeyEventHandler, Popup, Screen};
-use pltx_database::Database;
+use pltx_database::{Database, OrderedTable};
 use pltx_utils::{DateTime, WidgetMargin};
 use pltx_widgets::{Card, CardBorderType, Scrollable};
 use ratatui::{
@@ -15,7 +15,10 @@ use ratatui::{
 };
 use tracing::{info, info_span};
 
-use crate::popups::{card_editor::CardEditor, card_viewer::CardViewer, list_editor::ListEditor};
+use crate::{
+    activity::{self, CardActivity},
+    popups::{card_editor::CardEditor, card_viewer::CardViewer, list_editor::ListEditor},
+};
 
 #[derive(Clone)]
 pub struct ProjectLabel {
@@ -46,14 +49,13 @@ struct OpenProjectCard {
     start_date: Option<DateTime>,
     due_date: Option<DateTime>,
     completed: bool,
-    position: i32,
     labels: HashSet<i32>,
     subtasks: Vec<ProjectCardSubtask>,
 }
 
 impl OpenProjectCard {
     fn in_progress(&self) -> bool {
-        self.start_date.as_ref().is_some_and(|d| d.is_past()) && !self.overdue()
+        self.start_date.as_ref().is_some_and(|d| d.has_started()) && !self.overdue()
     }
 
     fn due_soon(&self, days: i32) -> bool {
@@ -97,11 +99,33 @@ struct Popups {
     edit_card: CardEditor,
 }
 
-#[derive(PartialEq)]
-enum DeleteSelection {
+#[derive(Clone, Copy, PartialEq)]
+enum ArchivedKind {
     List,
     Card,
-    None,
+}
+
+impl fmt::Display for ArchivedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match *self {
+                ArchivedKind::List => "list",
+                ArchivedKind::Card => "card",
+            }
+        )
+    }
+}
+
+/// An archived list or card that can be restored or permanently deleted.
+#[derive(Clone)]
+struct ArchivedItem {
+    id: i32,
+    kind: ArchivedKind,
+    title: String,
+    list_title: Option<String>,
+    archived_at: DateTime,
 }
 
 #[derive(PartialEq)]
@@ -116,9 +140,16 @@ pub struct OpenProject {
     data: ProjectData,
     popup: OpenProjectPopup,
     popups: Popups,
-    delete_selection: DeleteSelection,
     list_selections: Vec<Scrollable>,
     focus: Focus,
+    /// Whether the archived lists and cards are shown instead of the lists.
+    archived: bool,
+    archived_items: Vec<ArchivedItem>,
+    archived_selection: Scrollable,
+    /// Whether the recent activity of the cards is shown instead of the lists.
+    activity: bool,
+    activity_items: Vec<CardActivity>,
+    activity_selection: Scrollable,
 }
 
 impl Screen<Result<bool>> for OpenProject {
@@ -135,9 +166,14 @@ impl Screen<Result<bool>> for OpenProject {
                 new_card: CardEditor::init(),
                 edit_card: CardEditor::init(),
             },
-            delete_selection: DeleteSelection::None,
             list_selections: vec![],
             focus: Focus::Card,
+            archived: false,
+            archived_items: vec![],
+            archived_selection: Scrollable::default().cols([8, 40, 30, 20]),
+            activity: false,
+            activity_items: vec![],
+            activity_selection: Scrollable::default().cols([18, 30, 80]),
         })
     }
 
@@ -178,61 +214,86 @@ impl Screen<Result<bool>> for OpenProject {
             };
         }
 
-        if app.view.is_default() && app.mode.is_normal() {
-            match key_event.code {
-                KeyCode::Char('[') => return Ok(true),
-                KeyCode::Char('h') => {
-                    if self.selected_list_index != 0 {
-                        self.selected_list_index -= 1;
-                    }
+        let keys = app.config.keys.clone();
+        let pm_keys = &keys.project_management;
+
+        if app.view.is_default() && app.mode.is_normal() && self.activity {
+            self.activity_selection.key_event_handler(app, key_event);
+
+            if pm_keys.activity.matches(&key_event) || keys.navigation.back.matches(&key_event) {
+                self.activity = false;
+            } else if keys.navigation.open.matches(&key_event) {
+                self.focus_activity_card(app);
+            }
+        } else if app.view.is_default() && app.mode.is_normal() && self.archived {
+            self.archived_selection.key_event_handler(app, key_event);
+
+            if keys.editing.archived.matches(&key_event) || keys.navigation.back.matches(&key_event)
+            {
+                self.archived = false;
+                self.db_get_project(app)?;
+            } else if keys.editing.restore.matches(&key_event) {
+                self.db_unarchive(app)?;
+            } else if keys.editing.delete.matches(&key_event) && !self.archived_items.is_empty() {
+                app.mode.delete();
+            }
+        } else if app.view.is_default() && app.mode.is_normal() {
+            if keys.navigation.back.matches(&key_event) {
+                return Ok(true);
+            } else if keys.editing.archived.matches(&key_event) {
+                self.archived = true;
+                self.archived_selection.reset();
+                self.db_get_archived(app)?;
+            } else if pm_keys.activity.matches(&key_event) {
+                if let Some(project_id) = self.project_id {
+                    self.activity = true;
+                    self.activity_selection.reset();
+                    self.activity_items = activity::db_get_project_activity(&app.db, project_id)?;
                 }
-                KeyCode::Char('l') => {
-                    if self.selected_list_index != self.data.lists.len().saturating_sub(1) {
-                        self.selected_list_index += 1;
-                    }
+            } else if keys.navigation.left.matches(&key_event) {
+                if self.selected_list_index != 0 {
+                    self.selected_list_index -= 1;
                 }
-                _ => {}
+            } else if keys.navigation.right.matches(&key_event)
+                && self.selected_list_index != self.data.lists.len().saturating_sub(1)
+            {
+                self.selected_list_index += 1;
             }
 
             if self.focus == Focus::List {
-                match key_event.code {
-                    KeyCode::Char('H') => self.decrement_list_position(app)?,
-                    KeyCode::Char('L') => self.increment_list_position(app)?,
-                    KeyCode::Char('j') => {
-                        self.focus = Focus::Card;
-                    }
-                    KeyCode::Char('d') => {
-                        if self.project_id.is_some() && !self.data.lists.is_empty() {
-                            self.delete_selection = DeleteSelection::List;
-                            app.mode.delete();
-                        }
+                if pm_keys.move_left.matches(&key_event) {
+                    self.decrement_list_position(app)?;
+                } else if pm_keys.move_right.matches(&key_event) {
+                    self.increment_list_position(app)?;
+                } else if keys.navigation.down.matches(&key_event) {
+                    self.focus = Focus::Card;
+                } else if keys.editing.delete.matches(&key_event) {
+                    if self.project_id.is_some() && !self.data.lists.is_empty() {
+                        self.db_archive_list(app)?;
                     }
-                    KeyCode::Char('e') => {
-                        if !self.data.lists.is_empty() {
-                            let list_id = self.data.lists[self.selected_list_index].id;
-                            self.popup = OpenProjectPopup::EditList;
-                            self.popups.edit_list.set(&app.db, list_id)?;
-                            app.view.popup();
-                            app.mode.insert();
-                        }
-                    }
-                    KeyCode::Char('n') => {
-                        self.popup = OpenProjectPopup::NewList;
+                } else if keys.editing.edit.matches(&key_event) {
+                    if !self.data.lists.is_empty() {
+                        let list_id = self.data.lists[self.selected_list_index].id;
+                        self.popup = OpenProjectPopup::EditList;
+                        self.popups.edit_list.set(&app.db, list_id)?;
                         app.view.popup();
                         app.mode.insert();
                     }
-                    _ => {}
+                } else if keys.editing.new.matches(&key_event) {
+                    self.popup = OpenProjectPopup::NewList;
+                    app.view.popup();
+                    app.mode.insert();
                 }
             } else if self.focus == Focus::Card
                 && self.data.lists.is_empty()
-                && key_event.code == KeyCode::Char('n')
+                && keys.editing.new.matches(&key_event)
             {
                 self.popup = OpenProjectPopup::NewList;
                 app.view.popup();
                 app.mode.insert();
             } else if self.focus == Focus::Card && !self.data.lists.is_empty() {
                 if self.list_selections[self.selected_list_index].focused == 0
-                    && key_event.code == KeyCode::Char('k')
+                    && keys.navigation.up.matches(&key_event)
                 {
                     self.focus = Focus::List;
                 } else {
@@ -240,85 +301,62 @@ impl Screen<Result<bool>> for OpenProject {
                         .key_event_handler(app, key_event);
                 }
 
-                match key_event.code {
-                    KeyCode::Char('J') => self.increment_card_position(app)?,
-                    KeyCode::Char('K') => self.decrement_card_position(app)?,
-                    KeyCode::Char('H') => self.move_card_left(app)?,
-                    KeyCode::Char('L') => self.move_card_right(app)?,
-                    KeyCode::Enter => {
-                        if !self.data.lists.is_empty()
-                            && !self.data.lists[self.selected_list_index].cards.is_empty()
-                        {
-                            self.popup = OpenProjectPopup::ViewCard;
+                if pm_keys.move_down.matches(&key_event) {
+                    self.increment_card_position(app)?;
+                } else if pm_keys.move_up.matches(&key_event) {
+                    self.decrement_card_position(app)?;
+                } else if pm_keys.move_left.matches(&key_event) {
+                    self.move_card_left(app)?;
+                } else if pm_keys.move_right.matches(&key_event) {
+                    self.move_card_right(app)?;
+                } else if keys.navigation.open.matches(&key_event) {
+                    if !self.data.lists[self.selected_list_index].cards.is_empty() {
+                        self.popup = OpenProjectPopup::ViewCard;
+                        let card_index = self.list_selections[self.selected_list_index].focused;
+                        let card_id =
+                            self.data.lists[self.selected_list_index].cards[card_index].id;
+                        self.popups.view_card.id(card_id);
+                        self.popups.view_card.set_data(&app.db, card_id)?;
+                        app.view.popup();
+                    }
+                } else if keys.editing.new.matches(&key_event) {
+                    if let Some(project_id) = self.project_id {
+                        let list_id = self.data.lists[self.selected_list_index].id;
+                        self.popups.new_card.ids(project_id, list_id);
+                        self.popup = OpenProjectPopup::NewCard;
+                        app.view.popup();
+                    }
+                } else if keys.editing.edit.matches(&key_event) {
+                    if let Some(project_id) = self.project_id {
+                        if !self.data.lists[self.selected_list_index].cards.is_empty() {
+                            let list_id = self.data.lists[self.selected_list_index].id;
+                            self.popups.edit_card.ids(project_id, list_id);
+                            self.popup = OpenProjectPopup::EditCard;
                             let card_index = self.list_selections[self.selected_list_index].focused;
                             let card_id =
                                 self.data.lists[self.selected_list_index].cards[card_index].id;
-                            self.popups.view_card.id(card_id);
-                            self.popups.view_card.set_data(&app.db, card_id)?;
+                            self.popups.edit_card.set_data(&app.db, card_id)?;
                             app.view.popup();
                         }
                     }
-                    KeyCode::Char('n') => {
-                        if let Some(project_id) = self.project_id {
-                            if !self.data.lists.is_empty() {
-                                let list_id = self.data.lists[self.selected_list_index].id;
-                                self.popups.new_card.ids(project_id, list_id);
-                                self.popup = OpenProjectPopup::NewCard;
-                                app.view.popup();
-                            }
-                        }
-                    }
-                    KeyCode::Char('e') => {
-                        if let Some(project_id) = self.project_id {
-                            let selected_list_has_cards =
-                                !self.data.lists[self.selected_list_index].cards.is_empty();
-                            if !self.data.lists.is_empty() && selected_list_has_cards {
-                                let list_id = self.data.lists[self.selected_list_index].id;
-                                self.popups.edit_card.ids(project_id, list_id);
-                                self.popup = OpenProjectPopup::EditCard;
-                                let card_index =
-                                    self.list_selections[self.selected_list_index].focused;
-                                let card_id =
-                                    self.data.lists[self.selected_list_index].cards[card_index].id;
-                                self.popups.edit_card.set_data(&app.db, card_id)?;
-                                app.view.popup();
-                            }
-                        }
-                    }
-                    KeyCode::Char('c') => self.db_toggle_card_completed(app)?,
-                    KeyCode::Char('i') => self.db_toggle_card_important(app)?,
-                    KeyCode::Char('d') => {
-                        if !self.data.lists.is_empty()
-                            && !self.data.lists[self.selected_list_index].cards.is_empty()
-                        {
-                            self.delete_selection = DeleteSelection::Card;
-                            app.mode.delete();
-                        }
-                    }
-                    _ => {}
+                } else if pm_keys.complete_card.matches(&key_event) {
+                    self.db_toggle_card_completed(app)?;
+                } else if pm_keys.important_card.matches(&key_event) {
+                    self.db_toggle_card_important(app)?;
+                } else if keys.editing.delete.matches(&key_event)
+                    && !self.data.lists[self.selected_list_index].cards.is_empty()
+                {
+                    self.db_archive_card(app)?;
                 }
             }
         }
 
         if app.mode.is_delete() {
-            match key_event.code {
-                KeyCode::Char('y') => {
-                    if self.delete_selection == DeleteSelection::List {
-                        if !self.data.lists.is_empty() {
-                            self.db_delete_list(&app.db)?;
-                            self.db_get_project(app)?;
-                            app.mode.normal();
-                        }
-                    } else if self.delete_selection == DeleteSelection::Card {
-                        self.db_delete_card(&app.db)?;
-                        self.db_get_project(app)?;
-                        app.mode.normal();
-                    }
-                    self.delete_selection = DeleteSelection::None;
-                }
-                KeyCode::Char('n') => app.mode.normal(),
-
-                _ => {}
+            if keys.confirm.yes.matches(&key_event) {
+                self.db_delete_archived(app)?;
+                app.mode.normal();
+            } else if keys.confirm.no.matches(&key_event) {
+                app.mode.normal();
             }
         }
         Ok(false)
@@ -346,10 +384,17 @@ impl Screen<Result<bool>> for OpenProject {
 
         frame.render_widget(title, title_area);
 
-        if self.data.lists.is_empty() {
+        if self.activity {
+            self.render_activity(app, frame, list_areas);
+        } else if self.archived {
+            self.render_archived(app, frame, list_areas);
+        } else if self.data.lists.is_empty() {
             let content = Paragraph::new(Text::from(vec![Line::from(vec![
                 Span::from("You have no lists in your project. Press "),
-                Span::styled("n", Style::new().bold().fg(colors.keybind_key)),
+                Span::styled(
+                    app.config.keys.editing.new.first(),
+                    Style::new().bold().fg(colors.keybind_key),
+                ),
                 Span::from(" to create a new list."),
             ])]))
             .block(Block::new().padding(Padding::horizontal(1)));
@@ -391,7 +436,9 @@ impl Screen<Result<bool>> for OpenProject {
                     frame.render_widget(
                         Line::from(vec![
                             Span::from("There are no tasks in this list. Press "),
-                            Span::from("n").bold().fg(colors.keybind_key),
+                            Span::from(app.config.keys.editing.new.first())
+                                .bold()
+                                .fg(colors.keybind_key),
                             Span::from(" to create a new task."),
                         ]),
                         list_card.child_layout(),
@@ -423,6 +470,158 @@ impl Screen<Result<bool>> for OpenProject {
 }
 
 impl OpenProject {
+    fn render_activity(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let activity_card =
+            Card::new(" Recent Activity ", area).border_type(CardBorderType::Rounded);
+        activity_card.render(frame, app, area, true);
+
+        if self.activity_items.is_empty() {
+            frame.render_widget(
+                Line::from(vec![
+                    Span::from("There is no activity in this project yet. Press "),
+                    Span::from(app.config.keys.project_management.activity.first())
+                        .bold()
+                        .fg(colors.keybind_key),
+                    Span::from(" to go back to the lists."),
+                ]),
+                activity_card.child_layout(),
+            );
+            return;
+        }
+
+        let header = ["Time", "Card", "Activity"]
+            .into_iter()
+            .map(|h| Paragraph::new(h).fg(colors.secondary_fg))
+            .collect::<Vec<Paragraph>>();
+
+        let table = self
+            .activity_items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let style = if self.activity_selection.focused == i {
+                    Style::new()
+                        .bold()
+                        .fg(colors.active_fg)
+                        .bg(colors.active_bg)
+                } else {
+                    Style::new().fg(colors.fg)
+                };
+                vec![
+                    Paragraph::new(item.created_at.display()).fg(colors.secondary_fg),
+                    Paragraph::new(item.card_title.to_string()),
+                    Paragraph::new(item.describe()).fg(colors.secondary_fg),
+                ]
+                .into_iter()
+                .map(|widget| widget.style(style))
+                .collect::<Vec<Paragraph>>()
+            })
+            .collect::<Vec<Vec<Paragraph>>>();
+
+        self.activity_selection.render_with_cols(
+            frame,
+            activity_card.child_layout(),
+            header,
+            table,
+        );
+    }
+
+    /// Go back to the lists with the card of the focused activity focused.
+    fn focus_activity_card(&mut self, app: &mut App) {
+        let Some(card_id) = self
+            .activity_items
+            .get(self.activity_selection.focused)
+            .map(|a| a.card_id)
+        else {
+            return;
+        };
+
+        if self.focus_card(card_id) {
+            self.activity = false;
+        } else {
+            app.status_message("The card is archived");
+        }
+    }
+
+    /// Focus a card in the lists of the project. Returns false if the card
+    /// isn't in any of the lists, e.g., because it's archived.
+    pub fn focus_card(&mut self, card_id: i32) -> bool {
+        for (list_index, list) in self.data.lists.iter().enumerate() {
+            if let Some(card_index) = list.cards.iter().position(|c| c.id == card_id) {
+                self.focus = Focus::Card;
+                self.selected_list_index = list_index;
+                self.list_selections[list_index].focus(card_index);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn render_archived(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let archived_card = Card::new(" Archived ", area).border_type(CardBorderType::Rounded);
+        archived_card.render(frame, app, area, true);
+
+        if self.archived_items.is_empty() {
+            frame.render_widget(
+                Line::from(vec![
+                    Span::from("There are no archived lists or cards. Press "),
+                    Span::from(app.config.keys.editing.archived.first())
+                        .bold()
+                        .fg(colors.keybind_key),
+                    Span::from(" to go back to the lists."),
+                ]),
+                archived_card.child_layout(),
+            );
+            return;
+        }
+
+        let header = ["Type", "Title", "List", "Archived At"]
+            .into_iter()
+            .map(|h| Paragraph::new(h).fg(colors.secondary_fg))
+            .collect::<Vec<Paragraph>>();
+
+        let table = self
+            .archived_items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let style = if self.archived_selection.focused == i {
+                    Style::new()
+                        .bold()
+                        .fg(colors.active_fg)
+                        .bg(colors.active_bg)
+                } else {
+                    Style::new().fg(colors.fg)
+                };
+                vec![
+                    Paragraph::new(match item.kind {
+                        ArchivedKind::List => "List",
+                        ArchivedKind::Card => "Card",
+                    })
+                    .fg(colors.secondary_fg),
+                    Paragraph::new(item.title.to_string()),
+                    Paragraph::new(item.list_title.clone().unwrap_or_default())
+                        .fg(colors.secondary_fg),
+                    Paragraph::new(item.archived_at.display()).fg(colors.secondary_fg),
+                ]
+                .into_iter()
+                .map(|widget| widget.style(style))
+                .collect::<Vec<Paragraph>>()
+            })
+            .collect::<Vec<Vec<Paragraph>>>();
+
+        self.archived_selection.render_with_cols(
+            frame,
+            archived_card.child_layout(),
+            header,
+            table,
+        );
+    }
+
     fn render_card(
         &self,
         app: &App,
@@ -535,7 +734,36 @@ impl OpenProject {
         self.popups.view_card.reset();
         self.popups.new_card.reset();
         self.popups.edit_card.reset();
-        self.delete_selection = DeleteSelection::None;
+        self.archived = false;
+        self.archived_items = vec![];
+        self.archived_selection.reset();
+        self.activity = false;
+        self.activity_items = vec![];
+        self.activity_selection.reset();
+    }
+
+    /// Whether the open project still exists and hasn't been archived.
+    pub fn exists(&self, app: &App) -> Result<bool> {
+        let exists = app.db.conn().query_row(
+            "SELECT EXISTS (SELECT 1 FROM project WHERE id = ?1 AND archived = 0)",
+            [self.project_id],
+            |r| r.get(0),
+        )?;
+        Ok(exists)
+    }
+
+    /// Fetch the data of the open project again.
+    pub fn refresh(&mut self, app: &App) -> Result<()> {
+        if self.activity {
+            if let Some(project_id) = self.project_id {
+                self.activity_items = activity::db_get_project_activity(&app.db, project_id)?;
+            }
+            self.db_get_project(app)
+        } else if self.archived {
+            self.db_get_archived(app)
+        } else {
+            self.db_get_project(app)
+        }
     }
 }
 
@@ -566,6 +794,8 @@ impl OpenProject {
             project = self.db_get_card_labels(&app.db, &mut project, project_id)?;
             project = self.db_get_card_subtasks(&app.db, &mut project, project_id)?;
 
+            self.keep_selections(&project.lists);
+
             if !project.lists.is_empty() {
                 let list_id = project.lists[self.selected_list_index].id;
 
@@ -588,6 +818,45 @@ impl OpenProject {
         Ok(())
     }
 
+    /// Keep the focus on the same list and cards when the lists are fetched
+    /// again, e.g., after another instance has moved or added cards. If the
+    /// focused list or card was removed, the focus stays at the same position.
+    fn keep_selections(&mut self, lists: &[ProjectList]) {
+        let selected_list_id = self.data.lists.get(self.selected_list_index).map(|l| l.id);
+        let mut previous = self
+            .data
+            .lists
+            .iter()
+            .zip(std::mem::take(&mut self.list_selections))
+            .map(|(list, selection)| {
+                let card_id = list.cards.get(selection.focused).map(|c| c.id);
+                (list.id, card_id, selection)
+            })
+            .collect::<Vec<(i32, Option<i32>, Scrollable)>>();
+
+        for list in lists {
+            let mut selection = match previous.iter().position(|(id, _, _)| *id == list.id) {
+                Some(index) => {
+                    let (_, card_id, mut selection) = previous.swap_remove(index);
+                    if let Some(card_index) =
+                        card_id.and_then(|id| list.cards.iter().position(|c| c.id == id))
+                    {
+                        selection.focused = card_index;
+                    }
+                    selection
+                }
+                None => Scrollable::default().row_height(2),
+            };
+            selection.focused = selection.focused.min(list.cards.len().saturating_sub(1));
+            self.list_selections.push(selection);
+        }
+
+        self.selected_list_index = selected_list_id
+            .and_then(|id| lists.iter().position(|l| l.id == id))
+            .unwrap_or(self.selected_list_index)
+            .min(lists.len().saturating_sub(1));
+    }
+
     fn db_get_labels(&mut self, app: &App) -> Result<Vec<ProjectLabel>> {
         let start = Instant::now();
         let mut labels = vec![];
@@ -627,8 +896,8 @@ impl OpenProject {
         let mut lists = vec![];
 
         let conn = db.conn();
-        let query =
-            "SELECT id, title, position FROM project_list WHERE project_id = ?1 ORDER BY position";
+        let query = "SELECT id, title, position FROM project_list WHERE project_id = ?1 AND \
+                     archived = 0 ORDER BY position";
         let mut stmt = conn.prepare(query)?;
         let project_list_iter = stmt.query_map([project_id], |r| {
             Ok(ProjectList {
@@ -639,8 +908,6 @@ impl OpenProject {
         })?;
         for list in project_list_iter {
             lists.push(list?);
-            self.list_selections
-                .push(Scrollable::default().row_height(2));
         }
 
         info!("get project lists query executed in {:?}", start.elapsed());
@@ -657,8 +924,8 @@ impl OpenProject {
         let start = Instant::now();
         let conn = db.conn();
         let project_card_query = "SELECT id, list_id, title, description, important, start_date, \
-                                  due_date, completed, position FROM project_card WHERE \
-                                  project_id = ?1 ORDER BY position";
+                                  due_date, completed FROM project_card WHERE project_id = ?1 AND \
+                                  archived = 0 ORDER BY position";
         let mut project_card_stmt = conn.prepare(project_card_query)?;
         let project_card_iter = project_card_stmt.query_map([project_id], |r| {
             Ok(OpenProjectCard {
@@ -670,19 +937,16 @@ impl OpenProject {
                 start_date: DateTime::from_db_option(r.get(5)?),
                 due_date: DateTime::from_db_option(r.get(6)?),
                 completed: r.get(7)?,
-                position: r.get(8)?,
                 labels: HashSet::new(),
                 subtasks: vec![],
             })
         })?;
         for card in project_card_iter {
             let c = card?;
-            let index = project
-                .lists
-                .iter()
-                .position(|l| l.id == c.list_id)
-                .expect("failed to get project list index");
-            project.lists[index].cards.push(c);
+            // The cards of an archived list are hidden with the list.
+            if let Some(index) = project.lists.iter().position(|l| l.id == c.list_id) {
+                project.lists[index].cards.push(c);
+            }
         }
 
         info!("get project cards query executed in {:?}", start.elapsed());
@@ -711,11 +975,13 @@ impl OpenProject {
         for card_label in card_label_iter {
             let label = card_label?;
 
-            let list_index = project
+            let Some(list_index) = project
                 .lists
                 .iter()
                 .position(|l| l.cards.iter().any(|c| c.id == label.card_id))
-                .expect("failed to get project list index");
+            else {
+                continue;
+            };
             let card_index = project.lists[list_index]
                 .cards
                 .iter()
@@ -752,11 +1018,13 @@ impl OpenProject {
 
         for card_subtask in card_subtask_iter {
             let subtask = card_subtask?;
-            let list_index = project
+            let Some(list_index) = project
                 .lists
                 .iter()
                 .position(|l| l.cards.iter().any(|c| c.id == subtask.card_id))
-                .expect("failed to get project list index");
+            else {
+                continue;
+            };
             let card_index = project.lists[list_index]
                 .cards
                 .iter()
@@ -772,41 +1040,38 @@ impl OpenProject {
         Ok(project.clone())
     }
 
-    fn db_delete_list(&mut self, db: &Database) -> Result<()> {
+    fn db_archive_list(&mut self, app: &App) -> Result<()> {
         let start = Instant::now();
 
-        let list_id = self.data.lists[self.selected_list_index].id;
-        let original_position = db.get_position("project_list", list_id)?;
-
-        let query = "DELETE FROM project_list WHERE id = ?1";
-        db.execute(query, [list_id])?;
-
-        db.decrement_positions_after("project_list", original_position)?;
+        let list = &self.data.lists[self.selected_list_index];
+        let list_id = list.id;
+        let description = format!("archive list \"{}\"", list.title);
+        app.db.undoable(&description, |tx| {
+            OrderedTable::ProjectList.archive(tx.conn(), list_id)
+        })?;
 
         if self.selected_list_index != 0 {
             self.selected_list_index -= 1;
         }
 
         info!(
-            "delete project list query executed in {:?}",
+            "archive project list query executed in {:?}",
             start.elapsed()
         );
 
-        Ok(())
+        self.db_get_project(app)
     }
 
-    fn db_delete_card(&mut self, db: &Database) -> Result<()> {
+    fn db_archive_card(&mut self, app: &App) -> Result<()> {
         let start = Instant::now();
 
         let card_index = self.list_selections[self.selected_list_index].focused;
         let card = self.data.lists[self.selected_list_index].cards[card_index].clone();
 
-        let original_position = db.get_position("project_card", card.id)?;
-
-        let query = "DELETE FROM project_card WHERE id = ?1";
-        db.execute(query, [card.id])?;
-
-        db.decrement_positions_after("project_card", original_position)?;
+        let description = format!("archive card \"{}\"", card.title);
+        app.db.undoable(&description, |tx| {
+            OrderedTable::ProjectCard.archive(tx.conn(), card.id)
+        })?;
 
         let list = &self.data.lists[self.selected_list_index];
 
@@ -818,20 +1083,138 @@ impl OpenProject {
         }
 
         info!(
-            "delete project card query executed in {:?}",
+            "archive project card query executed in {:?}",
             start.elapsed()
         );
 
+        self.db_get_project(app)
+    }
+
+    fn db_get_archived(&mut self, app: &App) -> Result<()> {
+        let start = Instant::now();
+        let _span = info_span!("project management", screen = "open project").entered();
+
+        let conn = app.db.conn();
+        let query = "SELECT 'list', id, title, NULL, archived_at FROM project_list WHERE \
+                     project_id = ?1 AND archived = 1 UNION ALL SELECT 'card', c.id, c.title, \
+                     l.title, c.archived_at FROM project_card c JOIN project_list l ON l.id = \
+                     c.list_id WHERE c.project_id = ?1 AND c.archived = 1 ORDER BY 5 DESC";
+        let mut stmt = conn.prepare(query)?;
+        let archived_iter = stmt.query_map([self.project_id], |r| {
+            Ok(ArchivedItem {
+                kind: if r.get::<_, String>(0)? == "list" {
+                    ArchivedKind::List
+                } else {
+                    ArchivedKind::Card
+                },
+                id: r.get(1)?,
+                title: r.get(2)?,
+                list_title: r.get(3)?,
+                archived_at: DateTime::from_db(r.get(4)?),
+            })
+        })?;
+
+        self.archived_items = archived_iter.collect::<rusqlite::Result<Vec<ArchivedItem>>>()?;
+        let last_index = self.archived_items.len().saturating_sub(1);
+        if self.archived_selection.focused > last_index {
+            self.archived_selection.focused = last_index;
+        }
+
+        info!("get archived items query executed in {:?}", start.elapsed());
+
         Ok(())
     }
 
+    /// Restore the focused archived item. The list of an archived card is
+    /// restored with it, unless the project already has the maximum number of
+    /// lists.
+    fn db_unarchive(&mut self, app: &mut App) -> Result<()> {
+        let start = Instant::now();
+
+        let Some(item) = self
+            .archived_items
+            .get(self.archived_selection.focused)
+            .cloned()
+        else {
+            return Ok(());
+        };
+
+        let max_lists = app.config.modules.project_management.max_lists;
+        let project_id = self.project_id;
+        let description = format!("restore {} \"{}\"", item.kind, item.title);
+        let restored = app.db.undoable(&description, |tx| {
+            let list_id = match item.kind {
+                ArchivedKind::List => item.id,
+                ArchivedKind::Card => tx.conn().query_row(
+                    "SELECT list_id FROM project_card WHERE id = ?1",
+                    [item.id],
+                    |r| r.get(0),
+                )?,
+            };
+
+            if OrderedTable::ProjectList.is_archived(tx.conn(), list_id)? {
+                if OrderedTable::ProjectList.count(tx.conn(), project_id)? >= max_lists {
+                    return Ok(false);
+                }
+                OrderedTable::ProjectList.unarchive(tx.conn(), list_id)?;
+            }
+
+            if item.kind == ArchivedKind::Card {
+                OrderedTable::ProjectCard.unarchive(tx.conn(), item.id)?;
+            }
+
+            Ok(true)
+        })?;
+
+        info!("unarchive query executed in {:?}", start.elapsed());
+
+        if !restored {
+            app.error(format!("Cannot restore more than {} lists", max_lists));
+            return Ok(());
+        }
+
+        self.db_get_archived(app)
+    }
+
+    fn db_delete_archived(&mut self, app: &App) -> Result<()> {
+        let start = Instant::now();
+
+        if let Some(item) = self.archived_items.get(self.archived_selection.focused) {
+            let table = match item.kind {
+                ArchivedKind::List => OrderedTable::ProjectList,
+                ArchivedKind::Card => OrderedTable::ProjectCard,
+            };
+            let description = format!("delete {} \"{}\"", item.kind, item.title);
+            app.db
+                .undoable(&description, |tx| table.delete(tx.conn(), item.id))?;
+            info!(
+                "delete archived item query executed in {:?}",
+                start.elapsed()
+            );
+        }
+
+        self.db_get_archived(app)
+    }
+
     fn db_toggle_card_completed(&mut self, app: &App) -> Result<()> {
         let start = Instant::now();
 
         if let Some(card) = self.get_card() {
-            let query = "UPDATE project_card SET completed = ?1, updated_at = ?2 WHERE id = ?3";
-            let params = (!card.completed, DateTime::now(), card.id);
-            app.db.execute(query, params)?;
+            let query = "UPDATE project_card SET completed = ?1, completed_at = ?2, updated_at = \
+                         ?3 WHERE id = ?4";
+            let completed_at = if card.completed {
+                None
+            } else {
+                Some(DateTime::now())
+            };
+            let params = (!card.completed, completed_at, DateTime::now(), card.id);
+            let description = if card.completed {
+                format!("mark card \"{}\" as not completed", card.title)
+            } else {
+                format!("complete card \"{}\"", card.title)
+            };
+            app.db
+                .undoable(&description, |tx| tx.execute(query, params))?;
 
             self.db_get_project(app)?;
 
@@ -850,7 +1233,13 @@ impl OpenProject {
         if let Some(card) = self.get_card() {
             let query = "UPDATE project_card SET important = ?1, updated_at = ?2 WHERE id = ?3";
             let params = (!card.important, DateTime::now(), card.id);
-            app.db.execute(query, params)?;
+            let description = if card.important {
+                format!("mark card \"{}\" as not important", card.title)
+            } else {
+                format!("mark card \"{}\" as important", card.title)
+            };
+            app.db
+                .undoable(&description, |tx| tx.execute(query, params))?;
 
             self.db_get_project(app)?;
 
@@ -877,10 +1266,12 @@ impl OpenProject {
         let _span = info_span!("project management", screen = "open project").entered();
         let start = Instant::now();
         if self.selected_list_index + 1 != self.data.lists.len() {
-            let id = self.data.lists[self.selected_list_index].id;
-            let next_id = self.data.lists[self.selected_list_index + 1].id;
-            app.db.increment_position("project_list", id, next_id)?;
-            self.selected_list_index += 1;
+            let list = &self.data.lists[self.selected_list_index];
+            let id = list.id;
+            let description = format!("move list \"{}\"", list.title);
+            app.db.undoable(&description, |tx| {
+                OrderedTable::ProjectList.move_by(tx.conn(), id, 1)
+            })?;
             info!(
                 "increment list position query executed in {:?}",
                 start.elapsed()
@@ -894,10 +1285,12 @@ impl OpenProject {
         let _span = info_span!("project management", screen = "open project").entered();
         let start = Instant::now();
         if self.selected_list_index != 0 {
-            let id = self.data.lists[self.selected_list_index].id;
-            let prev_id = self.data.lists[self.selected_list_index - 1].id;
-            app.db.decrement_position("project_list", id, prev_id)?;
-            self.selected_list_index -= 1;
+            let list = &self.data.lists[self.selected_list_index];
+            let id = list.id;
+            let description = format!("move list \"{}\"", list.title);
+            app.db.undoable(&description, |tx| {
+                OrderedTable::ProjectList.move_by(tx.conn(), id, -1)
+            })?;
             info!(
                 "decrement list position query executed in {:?}",
                 start.elapsed()
@@ -916,10 +1309,12 @@ impl OpenProject {
             .map(|l| l.focused)
         {
             if card_index + 1 != self.data.lists[self.selected_list_index].cards.len() {
-                let id = self.data.lists[self.selected_list_index].cards[card_index].id;
-                let next_id = self.data.lists[self.selected_list_index].cards[card_index + 1].id;
-                app.db.increment_position("project_card", id, next_id)?;
-                self.list_selections[self.selected_list_index].focused += 1;
+                let card = &self.data.lists[self.selected_list_index].cards[card_index];
+                let id = card.id;
+                let description = format!("move card \"{}\"", card.title);
+                app.db.undoable(&description, |tx| {
+                    OrderedTable::ProjectCard.move_by(tx.conn(), id, 1)
+                })?;
                 info!(
                     "increment card position query executed in {:?}",
                     start.elapsed()
@@ -939,10 +1334,12 @@ impl OpenProject {
             .map(|l| l.focused)
         {
             if card_index != 0 {
-                let id = self.data.lists[self.selected_list_index].cards[card_index].id;
-                let prev_id = self.data.lists[self.selected_list_index].cards[card_index - 1].id;
-                app.db.decrement_position("project_card", id, prev_id)?;
-                self.list_selections[self.selected_list_index].focused -= 1;
+                let card = &self.data.lists[self.selected_list_index].cards[card_index];
+                let id = card.id;
+                let description = format!("move card \"{}\"", card.title);
+                app.db.undoable(&description, |tx| {
+                    OrderedTable::ProjectCard.move_by(tx.conn(), id, -1)
+                })?;
                 info!(
                     "decrement card position query executed in {:?}",
                     start.elapsed()
@@ -962,38 +1359,23 @@ impl OpenProject {
             .map(|l| l.focused)
         {
             if self.selected_list_index != 0 {
-                let original_list = &self.data.lists[self.selected_list_index];
+                let card = &self.data.lists[self.selected_list_index].cards[card_index];
+                let id = card.id;
                 let left_list = &self.data.lists[self.selected_list_index - 1];
-                let left_list_last_position =
-                    left_list.cards.last().map(|l| l.position).unwrap_or(-1);
-
-                app.db.execute(
-                    "UPDATE project_card SET list_id = ?1, position = ?2 where list_id = ?3 and \
-                     id = ?4",
-                    [
-                        left_list.id,
-                        left_list_last_position + 1,
-                        original_list.id,
-                        original_list.cards[card_index].id,
-                    ],
-                )?;
-
-                app.db.decrement_positions_after_where(
-                    "project_card",
-                    card_index as i32,
-                    "list_id",
-                    original_list.id,
-                )?;
+                let description =
+                    format!("move card \"{}\" to \"{}\"", card.title, left_list.title);
+
+                app.db.undoable(&description, |tx| {
+                    OrderedTable::ProjectCard.move_to_parent(tx.conn(), id, left_list.id, None)
+                })?;
 
                 self.list_selections[self.selected_list_index].focused = self.list_selections
                     [self.selected_list_index]
                     .focused
                     .saturating_sub(1);
-                self.list_selections[self.selected_list_index - 1].focused =
-                    left_list_last_position as usize + 1;
-                self.selected_list_index -= 1;
                 info!("move card left query executed in {:?}", start.elapsed());
                 self.db_get_project(app)?;
+                self.focus_card(id);
             }
         }
         Ok(())
@@ -1008,37 +1390,23 @@ impl OpenProject {
             .map(|l| l.focused)
         {
             if self.selected_list_index + 1 != self.data.lists.len() {
-                let list = &self.data.lists[self.selected_list_index];
+                let card = &self.data.lists[self.selected_list_index].cards[card_index];
+                let id = card.id;
                 let right_list = &self.data.lists[self.selected_list_index + 1];
-                let right_list_last_position =
-                    right_list.cards.last().map(|c| c.position).unwrap_or(-1);
-
-                let query = "UPDATE project_card SET list_id = ?1, position = ?2 where list_id = \
-                             ?3 and id = ?4";
-                let params = [
-                    right_list.id,
-                    right_list_last_position + 1,
-                    list.id,
-                    list.cards[card_index].id,
-                ];
-                app.db.execute(query, params)?;
-
-                app.db.decrement_positions_after_where(
-                    "project_card",
-                    card_index as i32,
-                    "list_id",
-                    list.id,
-                )?;
+                let description =
+                    format!("move card \"{}\" to \"{}\"", card.title, right_list.title);
+
+                app.db.undoable(&description, |tx| {
+                    OrderedTable::ProjectCard.move_to_parent(tx.conn(), id, right_list.id, None)
+                })?;
 
                 self.list_selections[self.selected_list_index].focused = self.list_selections
                     [self.selected_list_index]
                     .focused
                     .saturating_sub(1);
-                self.list_selections[self.selected_list_index + 1].focused =
-                    (right_list_last_position + 1) as usize;
-                self.selected_list_index += 1;
                 info!("move card right query executed in {:?}", start.elapsed());
                 self.db_get_project(app)?;
+                self.focus_card(id);
             }
         }
         Ok(())
@@ -4,7 +4,7 @@ use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use pltx_app::{state::View, App, DefaultWidget, KeyEventHandler, Popup};
 use pltx_config::ColorsConfig;
-use pltx_database::Database;
+use pltx_database::{Database, OrderedTable, Transaction};
 use pltx_utils::DateTime;
 use pltx_widgets::{Form, FormInput, FormInputState, FormWidget, Scrollable, Selection, TextInput};
 use ratatui::{
@@ -83,33 +83,30 @@ impl KeyEventHandler for SubtaskEditor {
             self.input.key_event_handler(app, key_event);
         }
 
-        match key_event.code {
-            KeyCode::Char('n') => {
-                if self.view == SubtaskView::Selection {
-                    if app.mode.is_delete() {
-                        app.mode.normal();
-                    } else {
-                        self.view = SubtaskView::Input;
-                        app.mode.insert();
-                    }
+        let keys = app.config.keys.clone();
+
+        if self.view == SubtaskView::Selection {
+            if app.mode.is_delete() {
+                if keys.confirm.yes.matches(&key_event) {
+                    self.subtasks.remove(self.selection.focused);
+                    self.selection.focused = self.selection.focused.saturating_sub(1);
+                    app.mode.normal();
+                } else if keys.confirm.no.matches(&key_event) {
+                    app.mode.normal();
                 }
-            }
-            KeyCode::Char('e') => {
-                if self.view == SubtaskView::Selection && !self.subtasks.is_empty() {
+            } else if keys.editing.new.matches(&key_event) {
+                self.view = SubtaskView::Input;
+                app.mode.insert();
+            } else if !self.subtasks.is_empty() {
+                if keys.editing.edit.matches(&key_event) {
                     self.has_id = true;
                     let subtask = &self.subtasks[self.selection.focused];
                     self.input.input(subtask.value.to_owned());
                     self.view = SubtaskView::Input;
                     app.mode.insert();
-                }
-            }
-            KeyCode::Char('d') => {
-                if self.view == SubtaskView::Selection && !self.subtasks.is_empty() {
+                } else if keys.editing.delete.matches(&key_event) {
                     app.mode.delete();
-                }
-            }
-            KeyCode::Char('.') => {
-                if self.view == SubtaskView::Selection && !self.subtasks.is_empty() {
+                } else if keys.editing.duplicate.matches(&key_event) {
                     let subtask = &self.subtasks[self.selection.focused];
                     self.subtasks.push(Subtask {
                         id: None,
@@ -117,38 +114,26 @@ impl KeyEventHandler for SubtaskEditor {
                     });
                 }
             }
-            KeyCode::Char('y') => {
-                if self.view == SubtaskView::Selection && app.mode.is_delete() {
-                    self.subtasks.remove(self.selection.focused);
-                    self.selection.focused = self.selection.focused.saturating_sub(1);
-                    app.mode.normal();
-                }
-            }
-            KeyCode::Char('[') => {
-                if app.mode.is_normal() && self.view == SubtaskView::Input {
-                    self.view = SubtaskView::Selection;
-                    self.input.reset();
-                }
-            }
-            KeyCode::Enter => {
-                if self.view == SubtaskView::Input {
-                    if self.has_id {
-                        self.subtasks[self.selection.focused] = Subtask {
-                            id: self.subtasks[self.selection.focused].id,
-                            value: self.input.input_string(),
-                        };
-                    } else {
-                        self.subtasks.push(Subtask {
-                            id: None,
-                            value: self.input.input_string(),
-                        });
+        } else if self.view == SubtaskView::Input {
+            if key_event.code == KeyCode::Enter {
+                if self.has_id {
+                    self.subtasks[self.selection.focused] = Subtask {
+                        id: self.subtasks[self.selection.focused].id,
+                        value: self.input.input_string(),
                     };
-                    self.view = SubtaskView::Selection;
-                    self.input.reset();
-                    app.mode.normal();
-                }
+                } else {
+                    self.subtasks.push(Subtask {
+                        id: None,
+                        value: self.input.input_string(),
+                    });
+                };
+                self.view = SubtaskView::Selection;
+                self.input.reset();
+                app.mode.normal();
+            } else if app.mode.is_normal() && keys.navigation.back.matches(&key_event) {
+                self.view = SubtaskView::Selection;
+                self.input.reset();
             }
-            _ => {}
         }
     }
 }
@@ -206,6 +191,12 @@ struct CardData {
     subtasks: Vec<Subtask<i32>>,
 }
 
+/// The dates of the date inputs, as they are stored in the database.
+struct CardDates {
+    start_date: Option<String>,
+    due_date: Option<String>,
+}
+
 pub struct CardEditor {
     project_id: Option<i32>,
     list_id: Option<i32>,
@@ -275,10 +266,16 @@ impl Popup<Result<bool>> for CardEditor {
 }
 
 impl CardEditor {
-    fn db_new_card(&self, db: &Database, project_id: i32, list_id: i32) -> Result<i32> {
+    fn db_new_card(
+        &self,
+        tx: &Transaction,
+        project_id: i32,
+        list_id: i32,
+        dates: &CardDates,
+    ) -> Result<i32> {
         let start = Instant::now();
 
-        let highest_position = db.get_highest_position_where("project_card", "list_id", list_id)?;
+        let position = OrderedTable::ProjectCard.next_position(tx.conn(), Some(list_id))?;
 
         let query_start = Instant::now();
         let query = "INSERT INTO project_card (project_id, list_id, title, description, \
@@ -290,27 +287,27 @@ impl CardEditor {
             (*self.inputs.title).borrow().input_string(),
             (*self.inputs.description).borrow().get_value_option(),
             false,
-            DateTime::from_input((*self.inputs.start_date).borrow().input_string()),
-            DateTime::from_input((*self.inputs.due_date).borrow().input_string()),
+            &dates.start_date,
+            &dates.due_date,
             Option::<String>::None,
-            highest_position + 1,
+            position,
             DateTime::now(),
             DateTime::now(),
         );
-        db.execute(query, params)?;
+        tx.execute(query, params)?;
 
         info!("new card query executed in {:?}", query_start.elapsed());
 
-        let new_card_id = db.last_row_id("project_card")?;
-        self.db_new_card_labels(db, project_id, new_card_id)?;
-        self.db_new_subtasks(db, project_id, new_card_id)?;
+        let new_card_id = tx.last_row_id("project_card")?;
+        self.db_new_card_labels(tx, project_id, new_card_id)?;
+        self.db_new_subtasks(tx, project_id, new_card_id)?;
 
         info!("new card query durations totaled at {:?}", start.elapsed());
 
         Ok(new_card_id)
     }
 
-    fn db_new_card_labels(&self, db: &Database, project_id: i32, card_id: i32) -> Result<()> {
+    fn db_new_card_labels(&self, tx: &Transaction, project_id: i32, card_id: i32) -> Result<()> {
         let start = Instant::now();
 
         for index in (*self.inputs.labels).borrow().selected.iter() {
@@ -324,7 +321,7 @@ impl CardEditor {
                 DateTime::now(),
                 DateTime::now(),
             );
-            db.execute(query, params)?;
+            tx.execute(query, params)?;
         }
 
         info!("new card labels query executed in {:?}", start.elapsed());
@@ -332,7 +329,7 @@ impl CardEditor {
         Ok(())
     }
 
-    fn db_new_subtasks(&self, db: &Database, project_id: i32, card_id: i32) -> Result<()> {
+    fn db_new_subtasks(&self, tx: &Transaction, project_id: i32, card_id: i32) -> Result<()> {
         for (i, subtask) in self.inputs.subtasks.borrow().subtasks.iter().enumerate() {
             let query = "INSERT INTO card_subtask (project_id, card_id, value, completed, \
                          position, created_at, updated_at) values (?1, ?2, ?3, ?4, ?5, ?6, ?7)";
@@ -345,12 +342,18 @@ impl CardEditor {
                 DateTime::now(),
                 DateTime::now(),
             );
-            db.execute(query, params)?;
+            tx.execute(query, params)?;
         }
         Ok(())
     }
 
-    fn db_edit_card(&self, db: &Database, data: &CardData, project_id: i32) -> Result<i32> {
+    fn db_edit_card(
+        &self,
+        tx: &Transaction,
+        data: &CardData,
+        project_id: i32,
+        dates: &CardDates,
+    ) -> Result<i32> {
         let start = Instant::now();
 
         let query = "UPDATE project_card SET title = ?1, description = ?2, important = ?3, \
@@ -359,18 +362,18 @@ impl CardEditor {
             (*self.inputs.title).borrow().input_string(),
             (*self.inputs.description).borrow().get_value_option(),
             false,
-            DateTime::from_input((*self.inputs.start_date).borrow().input_string()),
-            DateTime::from_input((*self.inputs.due_date).borrow().input_string()),
+            &dates.start_date,
+            &dates.due_date,
             Option::<String>::None,
             DateTime::now(),
             data.id,
         );
-        db.execute(query, params)?;
+        tx.execute(query, params)?;
 
         info!("edit card query executed in {:?}", start.elapsed());
 
-        self.db_edit_card_labels(db, data, project_id, data.id)?;
-        self.db_edit_subtasks(db, data, project_id, data.id)?;
+        self.db_edit_card_labels(tx, data, project_id, data.id)?;
+        self.db_edit_subtasks(tx, data, project_id, data.id)?;
 
         info!("edit card query durations total at {:?}", start.elapsed());
 
@@ -379,7 +382,7 @@ impl CardEditor {
 
     fn db_edit_card_labels(
         &self,
-        db: &Database,
+        tx: &Transaction,
         data: &CardData,
         project_id: i32,
         card_id: i32,
@@ -397,11 +400,11 @@ impl CardEditor {
                         DateTime::now(),
                         DateTime::now(),
                     );
-                    db.execute(query, params)?;
+                    tx.execute(query, params)?;
                 }
             } else {
                 let query = "DELETE FROM card_label WHERE card_id = ?1 and label_id = ?2";
-                db.execute(query, (card_id, &label.0))?;
+                tx.execute(query, (card_id, &label.0))?;
             }
         }
 
@@ -412,7 +415,7 @@ impl CardEditor {
 
     fn db_edit_subtasks(
         &self,
-        db: &Database,
+        tx: &Transaction,
         data: &CardData,
         project_id: i32,
         card_id: i32,
@@ -428,16 +431,16 @@ impl CardEditor {
         for subtask in &data.subtasks {
             if !input_subtask_ids.contains(&Some(subtask.id)) {
                 let query = "DELETE FROM card_subtask WHERE project_id = ?1 and id = ?2";
-                db.execute(query, (project_id, subtask.id))?;
+                tx.execute(query, (project_id, subtask.id))?;
             }
         }
 
         for (i, subtask) in self.inputs.subtasks.borrow().subtasks.iter().enumerate() {
             if let Some(id) = subtask.id {
-                let query = "UPDATE card_subtask SET value = ?1, updated_at = ?2 WHERE project_id \
-                             = ?3 and id = ?4";
-                let params = (subtask.value.to_owned(), DateTime::now(), project_id, id);
-                db.execute(query, params)?;
+                let query = "UPDATE card_subtask SET value = ?1, position = ?2, updated_at = ?3 \
+                             WHERE project_id = ?4 and id = ?5";
+                let params = (subtask.value.to_owned(), i, DateTime::now(), project_id, id);
+                tx.execute(query, params)?;
             } else {
                 let query = "INSERT INTO card_subtask (project_id, card_id, value, completed, \
                              position, created_at, updated_at) values (?1, ?2, ?3, ?4, ?5, ?6, ?7)";
@@ -450,7 +453,7 @@ impl CardEditor {
                     DateTime::now(),
                     DateTime::now(),
                 );
-                db.execute(query, params)?;
+                tx.execute(query, params)?;
             }
         }
         Ok(())
@@ -458,12 +461,49 @@ impl CardEditor {
 }
 
 impl CardEditor {
+    /// Read a date input. Returns `Err` with a message if the input isn't
+    /// empty but isn't a date either.
+    fn date_input(input: &Rc<RefCell<TextInput>>, name: &str) -> Result<Option<String>, String> {
+        let value = (**input).borrow().input_string();
+        if value.trim().is_empty() {
+            return Ok(None);
+        }
+        DateTime::from_input(value.clone())
+            .map(Some)
+            .ok_or_else(|| format!("The {} \"{}\" is not a valid date", name, value.trim()))
+    }
+
+    /// Read the date inputs. Returns `Err` with a message if one isn't a
+    /// valid date.
+    fn dates(&self) -> Result<CardDates, String> {
+        Ok(CardDates {
+            start_date: Self::date_input(&self.inputs.start_date, "start date")?,
+            due_date: Self::date_input(&self.inputs.due_date, "due date")?,
+        })
+    }
+
     fn submit(&mut self, app: &mut App) -> Result<bool> {
         if let Some(project_id) = self.project_id {
+            // Keep the form open so the date can be corrected, rather than
+            // saving the card without it.
+            let dates = match self.dates() {
+                Ok(dates) => dates,
+                Err(err) => {
+                    app.error(err);
+                    return Ok(false);
+                }
+            };
+            let title = (*self.inputs.title).borrow().input_string();
             if let Some(data) = &self.original_data {
-                self.db_edit_card(&app.db, data, project_id)?;
+                let description = format!("edit card \"{}\"", title);
+                app.db.undoable(&description, |tx| {
+                    self.db_edit_card(tx, data, project_id, &dates)
+                })?;
             } else if let Some(list_id) = self.list_id {
-                self.db_new_card(&app.db, project_id, list_id)?;
+                let description = format!("create card \"{}\"", title);
+                app.db.undoable(&description, |tx| {
+                    self.db_new_card(tx, project_id, list_id, &dates)
+                })?;
             }
             self.reset();
             app.view.default();
@@ -538,13 +578,13 @@ impl CardEditor {
             if let Some(start_date) = &data.start_date {
                 (*self.inputs.start_date)
                     .borrow_mut()
-                    .input(start_date.display());
+                    .input(start_date.display_input());
             }
 
             if let Some(due_date) = &data.due_date {
                 (*self.inputs.due_date)
                     .borrow_mut()
-                    .input(due_date.display());
+                    .input(due_date.display_input());
             }
 
             if let Some(reminder) = &data.reminder {
@@ -583,7 +623,7 @@ impl CardEditor {
         if let Some(start_date) = &data.start_date {
             (*self.inputs.start_date)
                 .borrow_mut()
-                .input(start_date.display());
+                .input(start_date.display_input());
         }
 
         info!("get card labels query executed in {:?}", start.elapsed());
@@ -1,11 +1,11 @@
 use std::{collections::HashSet, str::FromStr, time::Instant};
 
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, DefaultWidget, KeyEventHandler, Popup};
-use pltx_database::Database;
+use pltx_database::{Database, Transaction};
 use pltx_utils::{DateTime, WidgetMargin};
-use pltx_widgets::{PopupSize, PopupWidget, Selection};
+use pltx_widgets::{PopupSize, PopupWidget, Scrollable, Selection};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
@@ -15,7 +15,10 @@ use ratatui::{
 };
 use tracing::{info, info_span};
 
-use crate::open_project::ProjectLabel;
+use crate::{
+    activity::{self, CardActivity},
+    open_project::ProjectLabel,
+};
 
 struct Subtask {
     id: i32,
@@ -37,11 +40,20 @@ struct CardData {
     subtasks: Vec<Subtask>,
 }
 
+#[derive(PartialEq)]
+enum Focus {
+    Subtasks,
+    History,
+}
+
 pub struct CardViewer {
     id: Option<i32>,
     data: Option<CardData>,
     subtasks_selection: Selection<i32>,
     labels: Vec<ProjectLabel>,
+    history: Vec<CardActivity>,
+    history_selection: Scrollable,
+    focus: Focus,
 }
 
 impl Popup<Result<bool>> for CardViewer {
@@ -51,32 +63,49 @@ impl Popup<Result<bool>> for CardViewer {
             data: None,
             subtasks_selection: Selection::new("Subtasks", vec![]).checklist(),
             labels: vec![],
+            history: vec![],
+            history_selection: Scrollable::default(),
+            focus: Focus::Subtasks,
         }
     }
 
     fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) -> Result<bool> {
         let _span = info_span!("project management", popup = "card viewer").entered();
-        self.subtasks_selection.key_event_handler(app, key_event);
 
-        match key_event.code {
-            KeyCode::Char('q') => {
-                app.view.default();
-                self.reset();
-                return Ok(false);
-            }
-            KeyCode::Char(' ') => {
-                self.db_update_subtasks(&app.db)?;
-                return Ok(true);
-            }
-            KeyCode::Char('i') => {
-                self.db_update_subtasks(&app.db)?;
-                return Ok(true);
-            }
-            KeyCode::Char('a') => {
-                self.db_update_subtasks(&app.db)?;
-                return Ok(true);
-            }
-            _ => {}
+        let keys = app.config.keys.clone();
+
+        if keys.navigation.close.matches(&key_event) {
+            app.view.default();
+            self.reset();
+            return Ok(false);
+        } else if keys.navigation.next_pane.matches(&key_event)
+            || keys.navigation.prev_pane.matches(&key_event)
+        {
+            self.focus = if self.focus == Focus::Subtasks {
+                Focus::History
+            } else {
+                Focus::Subtasks
+            };
+        } else if self.history_focused() {
+            self.history_selection.key_event_handler(app, key_event);
+        } else if keys.selection.select.matches(&key_event)
+            || keys.selection.select_all.matches(&key_event)
+            || keys.selection.invert_selection.matches(&key_event)
+        {
+            self.subtasks_selection.key_event_handler(app, key_event);
+            let description = format!(
+                "update the subtasks of \"{}\"",
+                self.data
+                    .as_ref()
+                    .map(|d| d.title.as_str())
+                    .unwrap_or_default()
+            );
+            app.db
+                .undoable(&description, |tx| self.db_update_subtasks(tx))?;
+            self.reload(&app.db)?;
+            return Ok(true);
+        } else {
+            self.subtasks_selection.key_event_handler(app, key_event);
         }
 
         Ok(false)
@@ -105,7 +134,7 @@ impl Popup<Result<bool>> for CardViewer {
             };
 
             let spacing = 1;
-            let [description_area, labels_area, subtasks_area, dates_area, metadata_area] =
+            let [description_area, labels_area, subtasks_area, dates_area, metadata_area, history_area] =
                 Layout::default()
                     .constraints([
                         Constraint::Length(description_lines + spacing),
@@ -118,7 +147,8 @@ impl Popup<Result<bool>> for CardViewer {
                             5 + spacing
                         }),
                         Constraint::Length(3 + spacing),
-                        Constraint::Length(4),
+                        Constraint::Length(4 + spacing),
+                        Constraint::Fill(1),
                     ])
                     .areas(area);
 
@@ -176,7 +206,7 @@ impl Popup<Result<bool>> for CardViewer {
             frame.render_widget(labels, labels_area);
 
             self.subtasks_selection
-                .render(frame, app, subtasks_area, true);
+                .render(frame, app, subtasks_area, !self.history_focused());
 
             let dates = Paragraph::new(vec![
                 Line::from(vec![
@@ -229,10 +259,62 @@ impl Popup<Result<bool>> for CardViewer {
             .fg(colors.secondary_fg);
 
             frame.render_widget(metadata, metadata_area);
+
+            self.render_history(app, frame, history_area, data);
         }
     }
 }
 
+impl CardViewer {
+    fn render_history(&self, app: &App, frame: &mut Frame, area: Rect, data: &CardData) {
+        let colors = &app.config.colors;
+
+        let [title_area, table_area] = Layout::default()
+            .constraints([Constraint::Length(1), Constraint::Fill(1)])
+            .areas(area);
+
+        frame.render_widget(
+            Line::from(vec![
+                Span::from("History").bold().fg(if self.history_focused() {
+                    colors.primary
+                } else {
+                    colors.fg
+                }),
+                Span::from(" (Tab to focus)").fg(colors.tertiary_fg),
+            ]),
+            title_area,
+        );
+
+        // The card was created before any activity was recorded.
+        let created = [(data.created_at.clone(), String::from("Created"))];
+        let table = self
+            .history
+            .iter()
+            .map(|a| (a.created_at.clone(), a.describe()))
+            .chain(created)
+            .enumerate()
+            .map(|(i, (created_at, description))| {
+                let focused = self.history_focused() && self.history_selection.focused == i;
+                Paragraph::new(Line::from(vec![
+                    Span::from(if focused { "❯ " } else { "  " })
+                        .bold()
+                        .fg(colors.primary),
+                    Span::from(format!("{}  ", created_at.display())).fg(colors.secondary_fg),
+                    Span::from(description).fg(colors.fg),
+                ]))
+            })
+            .collect::<Vec<Paragraph>>();
+
+        self.history_selection.render(frame, table_area, table);
+    }
+
+    /// Whether the keys are directed to the history. The history is always
+    /// focused if there are no subtasks.
+    fn history_focused(&self) -> bool {
+        self.focus == Focus::History || self.subtasks_selection.options.is_empty()
+    }
+}
+
 impl CardViewer {
     pub fn labels(&mut self, labels: Vec<ProjectLabel>) {
         self.labels = labels;
@@ -273,6 +355,7 @@ impl CardViewer {
 
         self.db_get_card_labels(db, &mut card)?;
         self.db_get_subtasks(db, &mut card)?;
+        self.history = activity::db_get_card_activity(db, card_id)?;
         self.data = Some(card);
 
         info!("set card data in {:?}", start.elapsed());
@@ -336,9 +419,25 @@ impl CardViewer {
         self.data = None;
         self.subtasks_selection.reset();
         self.subtasks_selection.options.clear();
+        self.history = vec![];
+        self.history_selection.reset();
+        self.focus = Focus::Subtasks;
+    }
+
+    /// Fetch the card data again after it was changed, keeping the focused
+    /// subtask.
+    fn reload(&mut self, db: &Database) -> Result<()> {
+        if let Some(card_id) = self.id {
+            let focused_option = self.subtasks_selection.focused_option;
+            self.subtasks_selection.reset();
+            self.subtasks_selection.options.clear();
+            self.set_data(db, card_id)?;
+            self.subtasks_selection.focused_option = focused_option;
+        }
+        Ok(())
     }
 
-    fn db_update_subtasks(&self, db: &Database) -> Result<()> {
+    fn db_update_subtasks(&self, tx: &Transaction) -> Result<()> {
         let start = Instant::now();
         if let Some(data) = &self.data {
             for (_, subtask) in data.subtasks.iter().enumerate().filter(|(i, st)| {
@@ -353,7 +452,7 @@ impl CardViewer {
                     self.id.unwrap(),
                     subtask.id,
                 );
-                db.execute(query, params)?;
+                tx.execute(query, params)?;
             }
         }
         info!(
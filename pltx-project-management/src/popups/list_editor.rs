@@ -1,9 +1,9 @@
 use std::time::Instant;
 
-use color_eyre::{eyre::eyre, Result};
+use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use pltx_app::{state::View, App, DefaultWidget, KeyEventHandler, Popup};
-use pltx_database::Database;
+use pltx_database::{Database, OrderedTable};
 use pltx_utils::DateTime;
 use pltx_widgets::{PopupSize, PopupWidget, TextInput};
 use ratatui::{
@@ -45,7 +45,7 @@ impl Popup<Result<bool>> for ListEditor {
     fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) -> Result<bool> {
         self.title_input.key_event_handler(app, key_event);
 
-        if app.mode.is_normal() && key_event.code == KeyCode::Char('q') {
+        if app.mode.is_normal() && app.config.keys.navigation.close.matches(&key_event) {
             self.reset(app);
             return Ok(false);
         }
@@ -54,7 +54,12 @@ impl Popup<Result<bool>> for ListEditor {
             if self.original_data.is_some() {
                 self.db_edit_list(&app.db)?;
             } else if let Some(project_id) = self.project_id {
-                self.db_new_list(app, project_id)?;
+                if self.db_new_list(app, project_id)?.is_none() {
+                    app.error(format!(
+                        "Cannot create more than {} lists",
+                        app.config.modules.project_management.max_lists
+                    ));
+                }
             } else {
                 panic!("error")
             }
@@ -85,32 +90,34 @@ impl Popup<Result<bool>> for ListEditor {
 }
 
 impl ListEditor {
-    fn db_new_list(&self, app: &mut App, project_id: i32) -> Result<i32> {
+    /// Create the list, or return `None` if the project already has the
+    /// maximum number of lists.
+    fn db_new_list(&self, app: &mut App, project_id: i32) -> Result<Option<i32>> {
         let _span = info_span!("project management", popup = "list editor").entered();
         let start = Instant::now();
 
-        let highest_position =
-            app.db
-                .get_highest_position_where("project_list", "project_id", project_id)?;
-
         let max_lists = app.config.modules.project_management.max_lists;
-        if highest_position == max_lists - 1 {
-            // TODO: Replace with error notification
-            return Err(eyre!("cannot create more than {} lists", max_lists));
-        }
+        let description = format!("create list \"{}\"", self.title_input.input_string());
+        let new_list_id = app.db.undoable(&description, |tx| {
+            let position = OrderedTable::ProjectList.next_position(tx.conn(), Some(project_id))?;
 
-        let query = "INSERT INTO project_list (project_id, title, position, created_at, \
-                     updated_at) VALUES (?1, ?2, ?3, ?4, ?5)";
-        let params = (
-            project_id,
-            self.title_input.input_string(),
-            highest_position + 1,
-            DateTime::now(),
-            DateTime::now(),
-        );
-        app.db.execute(query, params)?;
+            if position >= max_lists {
+                return Ok(None);
+            }
 
-        let new_list_id = app.db.last_row_id("project_list")?;
+            let query = "INSERT INTO project_list (project_id, title, position, created_at, \
+                         updated_at) VALUES (?1, ?2, ?3, ?4, ?5)";
+            let params = (
+                project_id,
+                self.title_input.input_string(),
+                position,
+                DateTime::now(),
+                DateTime::now(),
+            );
+            tx.execute(query, params)?;
+
+            tx.last_row_id("project_list").map(Some)
+        })?;
 
         info!("new list query executed in {:?}", start.elapsed());
 
@@ -123,11 +130,12 @@ impl ListEditor {
 
         let data = self.original_data.as_ref().expect("list data was not set");
 
+        let title = self.title_input.input_string();
         let query = "UPDATE project_list SET title = ?1, updated_at = ?2 WHERE id = ?3";
-        db.execute(
-            query,
-            (&self.title_input.input_string(), DateTime::now(), data.id),
-        )?;
+        let description = format!("rename list \"{}\" to \"{}\"", data.title, title);
+        db.undoable(&description, |tx| {
+            tx.execute(query, (&title, DateTime::now(), data.id))
+        })?;
 
         info!("edit list query executed in {:?}", start.elapsed());
 
@@ -3,7 +3,7 @@ use std::{cell::RefCell, rc::Rc, str::FromStr};
 use color_eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent};
 use pltx_app::{state::View, App, DefaultWidget, KeyEventHandler, Popup};
-use pltx_database::Database;
+use pltx_database::{Database, OrderedTable, Transaction};
 use pltx_utils::DateTime;
 use pltx_widgets::{Form, FormInput, FormInputState, FormWidget, Scrollable, TextInput};
 use ratatui::{
@@ -111,34 +111,31 @@ impl KeyEventHandler for LabelEditor {
             }
         }
 
-        match key_event.code {
-            KeyCode::Char('n') => {
-                if self.view == LabelView::Selection {
-                    if app.mode.is_delete() {
-                        app.mode.normal();
-                    } else {
-                        self.view = LabelView::Input;
-                        app.mode.insert();
-                    }
+        let keys = app.config.keys.clone();
+
+        if self.view == LabelView::Selection {
+            if app.mode.is_delete() {
+                if keys.confirm.yes.matches(&key_event) {
+                    self.labels.remove(self.selection.focused);
+                    self.selection.focused = self.selection.focused.saturating_sub(1);
+                    app.mode.normal();
+                } else if keys.confirm.no.matches(&key_event) {
+                    app.mode.normal();
                 }
-            }
-            KeyCode::Char('e') => {
-                if self.view == LabelView::Selection && !self.labels.is_empty() {
+            } else if keys.editing.new.matches(&key_event) {
+                self.view = LabelView::Input;
+                app.mode.insert();
+            } else if !self.labels.is_empty() {
+                if keys.editing.edit.matches(&key_event) {
                     self.has_id = true;
                     let label = &self.labels[self.selection.focused];
                     self.inputs.title.input(label.title.to_owned());
                     self.inputs.color.input(label.color.to_owned());
                     self.view = LabelView::Input;
                     app.mode.insert();
-                }
-            }
-            KeyCode::Char('d') => {
-                if self.view == LabelView::Selection && !self.labels.is_empty() {
+                } else if keys.editing.delete.matches(&key_event) {
                     app.mode.delete();
-                }
-            }
-            KeyCode::Char('.') => {
-                if self.view == LabelView::Selection && !self.labels.is_empty() {
+                } else if keys.editing.duplicate.matches(&key_event) {
                     let label = &self.labels[self.selection.focused];
                     self.labels.push(Label {
                         id: None,
@@ -147,53 +144,40 @@ impl KeyEventHandler for LabelEditor {
                     });
                 }
             }
-            KeyCode::Char('y') => {
-                if self.view == LabelView::Selection && app.mode.is_delete() {
-                    self.labels.remove(self.selection.focused);
-                    self.selection.focused = self.selection.focused.saturating_sub(1);
-                    app.mode.normal();
-                }
-            }
-            KeyCode::Char('[') => {
-                if app.mode.is_normal() && self.view == LabelView::Input {
+        } else if self.view == LabelView::Input {
+            if key_event.code == KeyCode::Enter {
+                if self.focused_input == FocusedLabelInput::Title {
+                    self.focused_input = FocusedLabelInput::Color;
+                } else {
+                    if self.has_id {
+                        self.labels[self.selection.focused] = Label {
+                            id: self.labels[self.selection.focused].id,
+                            title: self.inputs.title.input_string(),
+                            color: self.inputs.color.input_string(),
+                        };
+                    } else {
+                        self.labels.push(Label {
+                            id: None,
+                            title: self.inputs.title.input_string(),
+                            color: self.inputs.color.input_string(),
+                        });
+                    };
                     self.view = LabelView::Selection;
                     self.focused_input = FocusedLabelInput::Title;
                     self.inputs.title.reset();
                     self.inputs.color.reset();
+                    app.mode.normal();
                 }
-            }
-            KeyCode::Tab => {
-                if self.view == LabelView::Input && self.focused_input == FocusedLabelInput::Title {
+            } else if keys.navigation.next_pane.matches(&key_event) {
+                if self.focused_input == FocusedLabelInput::Title {
                     self.focused_input = FocusedLabelInput::Color;
                 }
+            } else if app.mode.is_normal() && keys.navigation.back.matches(&key_event) {
+                self.view = LabelView::Selection;
+                self.focused_input = FocusedLabelInput::Title;
+                self.inputs.title.reset();
+                self.inputs.color.reset();
             }
-            KeyCode::Enter => {
-                if self.view == LabelView::Input {
-                    if self.focused_input == FocusedLabelInput::Title {
-                        self.focused_input = FocusedLabelInput::Color;
-                    } else {
-                        if self.has_id {
-                            self.labels[self.selection.focused] = Label {
-                                id: self.labels[self.selection.focused].id,
-                                title: self.inputs.title.input_string(),
-                                color: self.inputs.color.input_string(),
-                            };
-                        } else {
-                            self.labels.push(Label {
-                                id: None,
-                                title: self.inputs.title.input_string(),
-                                color: self.inputs.color.input_string(),
-                            });
-                        };
-                        self.view = LabelView::Selection;
-                        self.focused_input = FocusedLabelInput::Title;
-                        self.inputs.title.reset();
-                        self.inputs.color.reset();
-                        app.mode.normal();
-                    }
-                }
-            }
-            _ => {}
         }
     }
 }
@@ -319,10 +303,15 @@ impl Popup<Result<bool>> for ProjectEditor {
         let result = self.form.key_event_handler(app, key_event);
 
         if result.is_submit() {
+            let title = (*self.inputs.title).borrow().input_string();
             if self.original_data.is_some() {
-                self.db_edit_project(&app.db)?;
+                let description = format!("edit project \"{}\"", title);
+                app.db
+                    .undoable(&description, |tx| self.db_edit_project(tx))?;
             } else {
-                self.db_new_project(&app.db)?;
+                let description = format!("create project \"{}\"", title);
+                app.db
+                    .undoable(&description, |tx| self.db_new_project(tx))?;
             }
 
             self.reset();
@@ -406,28 +395,28 @@ impl ProjectEditor {
         Ok(labels)
     }
 
-    fn db_new_project(&self, db: &Database) -> Result<()> {
-        let highest_position = db.get_highest_position("project")?;
-        db.execute(
+    fn db_new_project(&self, tx: &Transaction) -> Result<()> {
+        let position = OrderedTable::Project.next_position(tx.conn(), None)?;
+        tx.execute(
             "INSERT INTO project (title, description, position, created_at, updated_at) VALUES \
              (?1, ?2, ?3, ?4, ?5)",
             (
                 (*self.inputs.title).borrow().input_string(),
                 (*self.inputs.description).borrow().get_value_option(),
-                highest_position + 1,
+                position,
                 DateTime::now(),
                 DateTime::now(),
             ),
         )?;
 
-        let new_project_id = db.last_row_id("project")?;
+        let new_project_id = tx.last_row_id("project")?;
 
-        self.db_new_labels(db, new_project_id)?;
+        self.db_new_labels(tx, new_project_id)?;
 
         Ok(())
     }
 
-    fn db_new_labels(&self, db: &Database, project_id: i32) -> Result<()> {
+    fn db_new_labels(&self, tx: &Transaction, project_id: i32) -> Result<()> {
         for (i, label) in self.inputs.labels.borrow().labels.iter().enumerate() {
             let query = "INSERT INTO project_label (project_id, title, color, position, \
                          created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)";
@@ -439,13 +428,13 @@ impl ProjectEditor {
                 DateTime::now(),
                 DateTime::now(),
             );
-            db.execute(query, params)?;
+            tx.execute(query, params)?;
         }
 
         Ok(())
     }
 
-    fn db_edit_project(&self, db: &Database) -> Result<()> {
+    fn db_edit_project(&self, tx: &Transaction) -> Result<()> {
         if let Some(data) = &self.original_data {
             let query =
                 "UPDATE project SET title = ?1, description = ?2, updated_at = ?3 WHERE id = ?4";
@@ -455,8 +444,8 @@ impl ProjectEditor {
                 DateTime::now(),
                 data.id,
             );
-            db.execute(query, params)?;
-            self.db_edit_labels(db, data.id)?;
+            tx.execute(query, params)?;
+            self.db_edit_labels(tx, data.id)?;
         } else {
             panic!("project data was not set")
         }
@@ -464,7 +453,7 @@ impl ProjectEditor {
         Ok(())
     }
 
-    fn db_edit_labels(&self, db: &Database, project_id: i32) -> Result<()> {
+    fn db_edit_labels(&self, tx: &Transaction, project_id: i32) -> Result<()> {
         if let Some(data) = &self.original_data {
             let input_label_ids = self
                 .inputs
@@ -477,23 +466,24 @@ impl ProjectEditor {
             for label in &data.labels {
                 if !input_label_ids.contains(&Some(label.id)) {
                     let query = "DELETE FROM project_label WHERE project_id = ?1 and id = ?2";
-                    db.execute(query, (project_id, label.id))?;
+                    tx.execute(query, (project_id, label.id))?;
                 }
             }
         }
 
         for (i, label) in self.inputs.labels.borrow().labels.iter().enumerate() {
             if let Some(label_id) = label.id {
-                let query = "UPDATE project_label SET title = ?1, color = ?2, updated_at = ?3 \
-                             WHERE project_id = ?4 and id = ?5";
+                let query = "UPDATE project_label SET title = ?1, color = ?2, position = ?3, \
+                             updated_at = ?4 WHERE project_id = ?5 and id = ?6";
                 let params = (
                     label.title.to_owned(),
                     label.color.to_owned(),
+                    i,
                     DateTime::now(),
                     project_id,
                     label_id,
                 );
-                db.execute(query, params)?;
+                tx.execute(query, params)?;
             } else {
                 let query = "INSERT INTO project_label (project_id, title, color, position, \
                              created_at, updated_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)";
@@ -505,7 +495,7 @@ impl ProjectEditor {
                     DateTime::now(),
                     DateTime::now(),
                 );
-                db.execute(query, params)?;
+                tx.execute(query, params)?;
             }
         }
 
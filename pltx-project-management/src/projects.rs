@@ -1,5 +1,5 @@
 use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::KeyEvent;
 use pltx_app::{App, Popup, Screen};
 use ratatui::{layout::Rect, Frame};
 
@@ -41,29 +41,31 @@ impl Screen<Result<()>> for Projects {
     }
 
     fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) -> Result<()> {
-        if app.mode.is_normal() && self.page == Page::ListProjects {
-            match key_event.code {
-                KeyCode::Char('n') => {
-                    self.page = Page::NewProject;
+        if app.mode.is_normal()
+            && self.page == Page::ListProjects
+            && !self.pages.list_projects.archived
+        {
+            let keys = app.config.keys.clone();
+
+            if keys.editing.new.matches(&key_event) {
+                self.page = Page::NewProject;
+                app.view.popup();
+            } else if keys.editing.edit.matches(&key_event) {
+                if let Some(id) = self.pages.list_projects.get_id() {
+                    self.pages.edit_project.set_project(&app.db, id)?;
+                    self.page = Page::EditProject;
                     app.view.popup();
                 }
-                KeyCode::Char('e') => {
-                    if let Some(id) = self.pages.list_projects.get_id() {
-                        self.pages.edit_project.set_project(&app.db, id)?;
-                        self.page = Page::EditProject;
-                        app.view.popup();
-                    }
-                }
-                KeyCode::Enter | KeyCode::Char('l') => {
-                    if let Some(id) = self.pages.list_projects.get_id() {
-                        self.pages.open_project.reset(app);
-                        self.pages.open_project.set_project_id(id);
-                        self.pages.open_project.db_get_project(app)?;
-                        self.page = Page::OpenProject;
-                        return Ok(());
-                    }
+            } else if keys.navigation.open.matches(&key_event)
+                || keys.navigation.right.matches(&key_event)
+            {
+                if let Some(id) = self.pages.list_projects.get_id() {
+                    self.pages.open_project.reset(app);
+                    self.pages.open_project.set_project_id(id);
+                    self.pages.open_project.db_get_project(app)?;
+                    self.page = Page::OpenProject;
+                    return Ok(());
                 }
-                _ => {}
             }
         }
 
@@ -91,3 +93,31 @@ impl Screen<Result<()>> for Projects {
         }
     }
 }
+
+impl Projects {
+    /// Open a project with one of its cards focused.
+    pub fn open_card(&mut self, app: &mut App, project_id: i32, card_id: i32) -> Result<()> {
+        self.pages.open_project.reset(app);
+        self.pages.open_project.set_project_id(project_id);
+        self.pages.open_project.db_get_project(app)?;
+        self.page = Page::OpenProject;
+        if !self.pages.open_project.focus_card(card_id) {
+            app.status_message("The card is archived");
+        }
+        Ok(())
+    }
+
+    /// Fetch the data of the current page again. Goes back to the list of
+    /// projects if the open project no longer exists.
+    pub fn refresh(&mut self, app: &App) -> Result<()> {
+        self.pages.list_projects.db_get_projects(app)?;
+        if self.page == Page::OpenProject {
+            if self.pages.open_project.exists(app)? {
+                self.pages.open_project.refresh(app)?;
+            } else {
+                self.page = Page::ListProjects;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::Instant;
+
+use color_eyre::Result;
+use pltx_database::Database;
+use tracing::info;
+
+/// The number of cards returned by a search.
+const SEARCH_LIMIT: i32 = 50;
+
+/// A card that matches a search, with the project and list it's in.
+#[derive(Clone)]
+pub struct CardSearchResult {
+    pub card_id: i32,
+    pub project_id: i32,
+    pub project_title: String,
+    pub list_title: String,
+    pub card_title: String,
+    /// The text around the best match, e.g., in the description.
+    pub snippet: String,
+}
+
+/// Convert the user input to an FTS5 query, where each word matches as a
+/// prefix, so results are shown while a word is being typed.
+fn fts_query(input: &str) -> String {
+    input
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Search the projects, lists, cards, descriptions and subtasks for cards that
+/// contain all of the words in the input. Archived cards, and cards in
+/// archived lists or projects, are not included. The results are ordered by
+/// relevance, where a match in the title of the card counts the most.
+pub fn db_search_cards(db: &Database, input: &str) -> Result<Vec<CardSearchResult>> {
+    let query = fts_query(input);
+    if query.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let start = Instant::now();
+    let conn = db.conn();
+    let mut stmt = conn.prepare(
+        "SELECT c.id, p.id, p.title, l.title, c.title, snippet(card_search, -1, '', '', '...', 8) \
+         FROM card_search JOIN project_card c ON c.id = card_search.rowid JOIN project_list l ON \
+         l.id = c.list_id JOIN project p ON p.id = c.project_id WHERE card_search MATCH ?1 AND \
+         c.archived = 0 AND l.archived = 0 AND p.archived = 0 ORDER BY bm25(card_search, 2.0, \
+         1.0, 10.0, 1.0, 1.0) LIMIT ?2",
+    )?;
+    let results = stmt
+        .query_map((query, SEARCH_LIMIT), |r| {
+            Ok(CardSearchResult {
+                card_id: r.get(0)?,
+                project_id: r.get(1)?,
+                project_title: r.get(2)?,
+                list_title: r.get(3)?,
+                card_title: r.get(4)?,
+                snippet: r.get::<_, Option<String>>(5)?.unwrap_or_default(),
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<CardSearchResult>>>()?;
+    info!("search cards query executed in {:?}", start.elapsed());
+    Ok(results)
+}
@@ -31,6 +31,7 @@ enum Command {
     ProjectManagement,
     Quit,
     Settings,
+    Theme,
     None,
 }
 
@@ -56,13 +57,14 @@ pub struct CommandHandler<'a> {
 }
 
 // NOTE: Add commands here.
-fn command_data<'a>() -> [(Command, &'a str); 6] {
+fn command_data<'a>() -> [(Command, &'a str); 7] {
     [
         (Command::Dashboard, "dashboard"),
         (Command::Help, "help"),
         (Command::Home, "home"),
         (Command::ProjectManagement, "project management"),
         (Command::Settings, "settings"),
+        (Command::Theme, "theme"),
         (Command::Quit, "quit"),
     ]
 }
@@ -100,33 +102,29 @@ impl<'a> CommandHandler<'a> {
         }
 
         if app.mode.is_normal() {
-            match key_event.code {
-                KeyCode::Enter => self.execute_command(app, interface),
-                KeyCode::Char('q') => {
-                    app.view.default();
-                    self.reset();
-                }
-                KeyCode::Char('j') => {
-                    if self.command_view == CommandView::Input {
-                        if self.focused_pane == FocusedPane::Input {
-                            self.focused_pane = FocusedPane::Options;
-                        } else if self.selected_option + 1 != self.command_options.len() {
-                            self.selected_option += 1;
-                        }
+            let keys = app.config.keys.navigation.clone();
+            if key_event.code == KeyCode::Enter {
+                self.execute_command(app, interface);
+            } else if keys.close.matches(&key_event) {
+                app.view.default();
+                self.reset();
+            } else if keys.down.matches(&key_event) {
+                if self.command_view == CommandView::Input {
+                    if self.focused_pane == FocusedPane::Input {
+                        self.focused_pane = FocusedPane::Options;
+                    } else if self.selected_option + 1 != self.command_options.len() {
+                        self.selected_option += 1;
                     }
                 }
-                KeyCode::Char('k') => {
-                    if self.command_view == CommandView::Input
-                        && self.focused_pane == FocusedPane::Options
-                    {
-                        if self.selected_option != 0 {
-                            self.selected_option -= 1;
-                        } else {
-                            self.focused_pane = FocusedPane::Input;
-                        }
-                    }
+            } else if keys.up.matches(&key_event)
+                && self.command_view == CommandView::Input
+                && self.focused_pane == FocusedPane::Options
+            {
+                if self.selected_option != 0 {
+                    self.selected_option -= 1;
+                } else {
+                    self.focused_pane = FocusedPane::Input;
                 }
-                _ => {}
             }
         } else if app.mode.is_insert() {
             match key_event.code {
@@ -251,6 +249,7 @@ impl<'a> CommandHandler<'a> {
                 app.mode.normal();
                 app.module = AppModule::ProjectManagement;
             }
+            Command::Theme => interface.popups.theme.open(app),
             Command::Quit => app.exit(),
             Command::None => {}
         }
@@ -5,10 +5,10 @@ use std::{
 };
 
 use color_eyre::{eyre::Context, Result};
-use crossterm::event::{self, Event as CrosstermEvent, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{self, Event as CrosstermEvent, KeyEvent, KeyEventKind};
 use pltx_app::{
-    state::{AppModule, View},
-    App, Module,
+    state::{AppModule, AppPopup, View},
+    App, Module, Popup,
 };
 
 use crate::{command_handler::CommandHandler, ui::Interface};
@@ -111,24 +111,72 @@ impl EventHandler {
         command_handler: &mut CommandHandler,
         key_event: KeyEvent,
     ) -> Result<()> {
+        let keys = app.config.keys.global.clone();
+
         if app.mode.is_normal() {
-            match key_event.code {
-                KeyCode::Char('`') => app.debug.toggle(),
-                KeyCode::Char('!') => app.debug.toggle_min_preview(),
-                KeyCode::Char('~') => app.debug.next_position(),
-                _ => {}
+            if keys.debug_pane.matches(&key_event) {
+                app.debug.toggle();
+            } else if keys.debug_min_preview.matches(&key_event) {
+                app.debug.toggle_min_preview();
+            } else if keys.debug_position.matches(&key_event) {
+                app.debug.next_position();
+            }
+        }
+
+        if app.view.is_default() && app.mode.is_normal() {
+            let undo = keys.undo.matches(&key_event);
+            let redo = keys.redo.matches(&key_event);
+            if undo || redo {
+                return self.history(app, interface, redo);
             }
         }
 
         match app.view {
             View::Default => {
-                if app.mode.is_normal() && key_event.code == KeyCode::Char(':') {
+                if app.mode.is_normal() && keys.command.matches(&key_event) {
                     app.mode.insert();
                     app.view.command();
                 }
+                if app.mode.is_normal() && keys.search.matches(&key_event) {
+                    interface.popups.search.open(app);
+                    return Ok(());
+                }
+                if app.mode.is_normal() && keys.notifications.matches(&key_event) {
+                    interface.popups.notifications.open(app);
+                    return Ok(());
+                }
+            }
+            View::Popup if app.popup == AppPopup::Search => {
+                if let Some(result) = interface.popups.search.key_event_handler(app, key_event)? {
+                    app.module = AppModule::ProjectManagement;
+                    interface.modules.project_management.open_card(
+                        app,
+                        result.project_id,
+                        result.card_id,
+                    )?;
+                }
+                return Ok(());
+            }
+            View::Popup if app.popup == AppPopup::Theme => {
+                interface.popups.theme.key_event_handler(app, key_event);
+                return Ok(());
+            }
+            View::Popup if app.popup == AppPopup::ConfigErrors => {
+                interface
+                    .popups
+                    .config_errors
+                    .key_event_handler(app, key_event);
+                return Ok(());
+            }
+            View::Popup if app.popup == AppPopup::Notifications => {
+                interface
+                    .popups
+                    .notifications
+                    .key_event_handler(app, key_event);
+                return Ok(());
             }
             View::Popup => {
-                if app.mode.is_normal() && key_event.code == KeyCode::Char(':') {
+                if app.mode.is_normal() && keys.command.matches(&key_event) {
                     app.mode.insert();
                     app.view.command();
                 }
@@ -150,4 +198,28 @@ impl EventHandler {
 
         Ok(())
     }
+
+    /// Undo the last change, or redo the last undone change, and describe it
+    /// in the status bar.
+    fn history(&mut self, app: &mut App, interface: &mut Interface, redo: bool) -> Result<()> {
+        let (description, action) = if redo {
+            (app.db.redo()?, "Redid")
+        } else {
+            (app.db.undo()?, "Undid")
+        };
+
+        match description {
+            Some(description) => {
+                interface.modules.project_management.refresh(app)?;
+                app.status_message(format!("{} {}", action, description));
+            }
+            None => app.status_message(if redo {
+                "Nothing to redo"
+            } else {
+                "Nothing to undo"
+            }),
+        }
+
+        Ok(())
+    }
 }
@@ -4,8 +4,12 @@
 use std::time::Instant;
 
 use color_eyre::Result;
+use crossterm::event::KeyEventKind;
 use keybinds::Event;
-use pltx_app::App;
+use pltx_app::{App, Popup};
+use pltx_config::ConfigError;
+use popups::passphrase::{PassphraseMode, PassphrasePrompt};
+use ratatui::{style::Stylize, widgets::Block};
 
 mod command_handler;
 pub mod errors;
@@ -19,12 +23,32 @@ use tracing::info;
 use tui::Tui;
 use ui::Interface;
 
-/// Initialize and run the terminal user interface
-pub fn run_tui(app: &mut App, application_start: Instant) -> Result<()> {
+/// Initialize and run the terminal user interface. The problems found in the
+/// config files are shown in a popup once the interface has been initialized.
+pub fn run_tui(
+    app: &mut App,
+    application_start: Instant,
+    config_errors: Vec<ConfigError>,
+) -> Result<()> {
     let mut tui = Tui::new()?;
+
+    if app.db.is_locked() || (app.profile.encrypted && !app.db.is_encrypted()) {
+        prompt_passphrase(app, &mut tui)?;
+        if app.exit {
+            Tui::restore()?;
+            return Ok(());
+        }
+    }
+
     app.db.start_session()?;
     let mut interface = Interface::init(app)?;
+    if !config_errors.is_empty() {
+        interface.popups.config_errors.open(app, config_errors);
+    }
     let mut command_handler = CommandHandler::init();
+    // Start watching for changes made by other instances of pltx.
+    app.db.poll_changes()?;
+    let mut changed = false;
 
     info!(
         "initialized application in {:?}",
@@ -34,21 +58,40 @@ pub fn run_tui(app: &mut App, application_start: Instant) -> Result<()> {
     while !app.exit {
         tui.terminal.draw(|frame| {
             interface.render(frame, app, &mut command_handler);
+            app.color_mode
+                .adapt_buffer(frame.buffer_mut(), &app.config.colors);
         })?;
 
         match tui.events.next()? {
-            Event::Tick => app.tick(),
+            Event::Tick => {
+                app.tick()?;
+                // The problems of a reloaded config wait for the open popup to
+                // be closed.
+                if !app.config_errors.is_empty() && !app.view.is_popup() {
+                    let config_errors = std::mem::take(&mut app.config_errors);
+                    interface.popups.config_errors.open(app, config_errors);
+                }
+                changed |= app.db.poll_changes()?;
+                // Popups keep what is being edited, so the screens are only
+                // reloaded once they are closed.
+                if changed && !app.view.is_popup() {
+                    interface.modules.project_management.refresh(app)?;
+                    changed = false;
+                }
+            }
             Event::Key(key_event) => {
+                app.db.record_activity()?;
                 tui.events
                     .key_events(app, &mut interface, &mut command_handler, key_event)?
             }
             // Event::Mouse(_) => {}
             // Event::Resize(_, _) => {}
-            Event::FocusGained => {}
-            Event::FocusLost => {} // Event::Paste(_) => {}
+            Event::FocusGained => app.db.record_activity()?,
+            Event::FocusLost => app.db.pause_session()?, // Event::Paste(_) => {}
         }
     }
 
+    app.db.end_session()?;
     Tui::restore()?;
 
     info!(
@@ -58,3 +101,36 @@ pub fn run_tui(app: &mut App, application_start: Instant) -> Result<()> {
 
     Ok(())
 }
+
+/// Ask for the passphrase of the profile database until it has been unlocked,
+/// or encrypted if the profile should be encrypted but isn't yet. The session
+/// cannot be started until then.
+fn prompt_passphrase(app: &mut App, tui: &mut Tui) -> Result<()> {
+    let mut prompt = PassphrasePrompt::init();
+    prompt.mode(if app.db.is_encrypted() {
+        PassphraseMode::Unlock
+    } else {
+        PassphraseMode::Encrypt
+    });
+    app.view.popup();
+    app.mode.insert();
+
+    while !app.exit {
+        tui.terminal.draw(|frame| {
+            frame.render_widget(Block::new().bg(app.config.colors.bg), frame.size());
+            prompt.render(app, frame, frame.size());
+            app.color_mode
+                .adapt_buffer(frame.buffer_mut(), &app.config.colors);
+        })?;
+
+        if let Event::Key(key_event) = tui.events.next()? {
+            if key_event.kind == KeyEventKind::Press && prompt.key_event_handler(app, key_event)? {
+                break;
+            }
+        }
+    }
+
+    app.view.default();
+    app.mode.normal();
+    Ok(())
+}
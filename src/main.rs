@@ -1,11 +1,22 @@
 //! pltx
-use std::time::Instant;
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::Instant,
+};
 
 use clap::{Parser, Subcommand};
-use color_eyre::Result;
+use color_eyre::{eyre::eyre, Result};
 use pltx::{errors, run_tui};
 use pltx_app::App;
-use pltx_config::{init_config, ProfileConfig};
+use pltx_config::{
+    config_key_file, config_schema, config_to_toml, config_value, copy_profile, create_profile,
+    default_config_text, delete_profile, init_config, rename_profile, set_config_values, Config,
+    ProfileConfig, ProfileFiles,
+};
+use pltx_database::{is_encrypted_file, Database};
+use pltx_sync::SyncKey;
 use pltx_utils::dirs;
 use tracing_error::ErrorLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
@@ -35,12 +46,152 @@ struct Cli {
     /// Use a profile
     #[arg(short, long)]
     profile: Option<String>,
+    /// Use another config directory, which can also be set with
+    /// PLTX_CONFIG_DIR
+    #[arg(long, global = true)]
+    config_dir: Option<PathBuf>,
+    /// Use another data directory, which can also be set with PLTX_DATA_DIR
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
     /// Delete all existing data
     Reset,
+    /// Encrypt the profile database with a passphrase
+    Encrypt,
+    /// Decrypt the profile database
+    Decrypt,
+    /// Change the passphrase of the profile database
+    ChangePassphrase,
+    /// Check the profile database for problems
+    Doctor {
+        /// Repair the problems that are found
+        #[arg(long)]
+        fix: bool,
+        /// Rebuild the database file to reclaim unused space
+        #[arg(long)]
+        vacuum: bool,
+    },
+    /// Back up the profile database to a file or the backup directory
+    #[command(args_conflicts_with_subcommands = true)]
+    Backup {
+        #[command(subcommand)]
+        command: Option<BackupCommands>,
+        /// The file to back up to
+        path: Option<PathBuf>,
+    },
+    /// Replace the profile database with a backup
+    Restore {
+        /// The file name of a backup in the backup directory, or the path of a
+        /// backup
+        snapshot: String,
+    },
+    /// Import the projects and sessions of another database into the profile
+    /// database
+    Merge {
+        /// The path of the database, or the file name of a database in the data
+        /// directory, e.g., dev.db
+        path: PathBuf,
+        /// Merge without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Sync the profile database with the sync server
+    #[command(args_conflicts_with_subcommands = true)]
+    Sync {
+        #[command(subcommand)]
+        command: Option<SyncCommands>,
+    },
+    /// Manage the profiles and their config, database, and log files
+    Profile {
+        #[command(subcommand)]
+        command: ProfileCommands,
+    },
+    /// Show and change the config of the profile
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum BackupCommands {
+    /// List the backups in the backup directory
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum ProfileCommands {
+    /// List the profiles
+    List,
+    /// Create a profile
+    Create {
+        /// The name of the profile, which is used in its file names
+        name: String,
+    },
+    /// Delete a profile and its files, except for the backups
+    Delete {
+        /// The name of the profile
+        name: String,
+        /// Delete without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Rename a profile and the files that are named after it
+    Rename {
+        /// The name of the profile
+        name: String,
+        /// The new name of the profile
+        new_name: String,
+    },
+    /// Copy a profile and its config and database to a new profile
+    Copy {
+        /// The name of the profile to copy
+        name: String,
+        /// The name of the new profile
+        new_name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommands {
+    /// Show the config that is used, with the config files and environment
+    /// variables merged
+    Show,
+    /// Show a value of the config that is used, e.g., `colors.fg`
+    Get {
+        /// The path of the value
+        key: String,
+    },
+    /// Set a value in the config file of the profile
+    Set {
+        /// The path of the value, e.g., `colors.fg`
+        key: String,
+        /// The new value. The value is removed without it, so it falls back to
+        /// its default
+        value: Option<String>,
+    },
+    /// Show the default config with its comments
+    Defaults,
+    /// Show the JSON schema of the config, for editors that complete TOML
+    /// files
+    Schema,
+}
+
+#[derive(Subcommand, Debug)]
+enum SyncCommands {
+    /// Show the sync key of the profile, creating a new sync space if there is
+    /// none
+    Key,
+    /// Sync the profile with the devices that use a sync key
+    Join {
+        /// The key shown by `pltx sync key` on another device
+        key: String,
+    },
+    /// Stop syncing the profile
+    Leave,
 }
 
 fn main() -> Result<()> {
@@ -48,24 +199,334 @@ fn main() -> Result<()> {
 
     errors::install_hooks()?;
     let cli = Cli::parse();
-    let (config, profile) = init_config(cli.profile.clone())?;
+    if let Some(config_dir) = &cli.config_dir {
+        dirs::set_config_dir(config_dir.to_owned());
+    }
+    if let Some(data_dir) = &cli.data_dir {
+        dirs::set_data_dir(data_dir.to_owned());
+    }
+    let (config, profile, config_errors) = init_config(cli.profile.clone())?;
     init_tracing(&config.log_level, &profile)?;
+    if cli.command.is_some() {
+        for error in &config_errors {
+            eprintln!("warning: {}", error);
+        }
+    }
+    // The profile and config commands change the files of the profiles, so the
+    // database isn't opened.
+    if let Some(Commands::Profile { command }) = &cli.command {
+        return profile_command(&config, command);
+    }
+    if let Some(Commands::Config { command }) = &cli.command {
+        return config_command(&config, &profile, command);
+    }
 
     let mut app = App::new(config, profile);
 
     match &cli.command {
         Some(Commands::Reset) => {
-            app.db.reset()?;
+            let backup = app.db.reset()?;
+            println!("Backed up to {}", backup.display());
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::Encrypt) => {
+            if app.db.is_encrypted() {
+                return Err(eyre!("the database is already encrypted"));
+            }
+            let passphrase = read_new_passphrase()?;
+            app.db.encrypt(&passphrase)?;
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::Decrypt) => {
+            unlock_db(&mut app.db)?;
+            app.db.decrypt()?;
+            if app.profile.encrypted {
+                println!(
+                    "The \"{}\" profile is set to be encrypted, so it will be encrypted again the \
+                     next time it is opened.",
+                    app.profile.name
+                );
+            }
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::ChangePassphrase) => {
+            unlock_db(&mut app.db)?;
+            let passphrase = read_new_passphrase()?;
+            app.db.change_passphrase(&passphrase)?;
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::Doctor { fix, vacuum }) => {
+            unlock_db(&mut app.db)?;
+            let report = app.db.check()?;
+            println!("{}", report);
+            if *fix && report.problems() > 0 {
+                app.db.repair()?;
+                println!("Repaired {} problems", report.problems());
+            } else if report.problems() > 0 {
+                println!("Run `pltx doctor --fix` to repair the problems");
+            }
+            if *vacuum {
+                app.db.vacuum()?;
+                println!("Vacuumed. {}", app.db.check()?.size_summary());
+            }
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::Backup {
+            command: Some(BackupCommands::List),
+            ..
+        }) => {
+            let backups = app.db.backups()?;
+            if backups.is_empty() {
+                println!("No backups in {}", app.db.backup_dir().display());
+            }
+            for backup in backups {
+                println!(
+                    "{:<32} {:>10} KiB {}",
+                    backup.name(),
+                    backup.size / 1024,
+                    if backup.automatic {
+                        "automatic"
+                    } else {
+                        "manual"
+                    }
+                );
+            }
+        }
+        Some(Commands::Backup {
+            command: None,
+            path,
+        }) => {
+            unlock_db(&mut app.db)?;
+            let backup = match path {
+                Some(path) => {
+                    app.db.backup_to(path)?;
+                    path.to_owned()
+                }
+                None => app.db.backup()?,
+            };
+            println!("Backed up to {}", backup.display());
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::Restore { snapshot }) => {
+            let snapshot = app.db.find_backup(snapshot)?;
+            unlock_db(&mut app.db)?;
+            // Keep the current data in case the wrong backup is restored.
+            let backup = app.db.backup()?;
+            println!("Backed up the current data to {}", backup.display());
+            app.db.restore(&snapshot)?;
+            println!("Restored {}", snapshot.display());
             println!("Ok {:?}", application_start.elapsed());
         }
+        Some(Commands::Merge { path, yes }) => {
+            let path = if path.is_file() {
+                path.to_owned()
+            } else {
+                dirs::data_dir().join(path)
+            };
+            if !path.is_file() {
+                return Err(eyre!("no database at {}", path.display()));
+            }
+            unlock_db(&mut app.db)?;
+            app.db.migrate_core()?;
+            pltx_project_management::migrate(&app.db)?;
+
+            let passphrase = if is_encrypted_file(&path) {
+                Some(rpassword::prompt_password(format!(
+                    "Passphrase of {}: ",
+                    path.display()
+                ))?)
+            } else {
+                None
+            };
+            let report = app.db.merge(&path, passphrase.as_deref(), true)?;
+            println!("{}", report);
+            if report.imported() == 0 {
+                println!("Nothing to merge");
+            } else if *yes || confirm(&format!("Merge {} rows?", report.imported()))? {
+                let backup = app.db.backup()?;
+                println!("Backed up the current data to {}", backup.display());
+                app.db.merge(&path, passphrase.as_deref(), false)?;
+                println!("Merged {}", path.display());
+            }
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::Sync { command }) => {
+            unlock_db(&mut app.db)?;
+            app.db.migrate_core()?;
+            pltx_project_management::migrate(&app.db)?;
+            match command {
+                Some(SyncCommands::Key) => {
+                    let key = match app.db.sync_state()?.key {
+                        Some(key) => key,
+                        None => {
+                            let key = SyncKey::generate().to_string();
+                            app.db.set_sync_key(Some(&key))?;
+                            key
+                        }
+                    };
+                    println!("{}", key);
+                    println!(
+                        "Run `pltx sync join <key>` on the other devices. Keep the key secret, as \
+                         it decrypts the synced data."
+                    );
+                }
+                Some(SyncCommands::Join { key }) => {
+                    SyncKey::parse(key)?;
+                    app.db.set_sync_key(Some(key))?;
+                    println!("Joined the sync space");
+                }
+                Some(SyncCommands::Leave) => {
+                    app.db.set_sync_key(None)?;
+                    println!("Stopped syncing");
+                }
+                None => {
+                    if app.profile.sync_server.is_empty() {
+                        return Err(eyre!(
+                            "no sync server is set for the \"{}\" profile",
+                            app.profile.name
+                        ));
+                    }
+                    let report = pltx_sync::sync(&app.db, &app.profile.sync_server)?;
+                    println!(
+                        "Sent {} changes, received {} change sets, and applied {} changes",
+                        report.sent, report.received, report.applied
+                    );
+                }
+            }
+            println!("Ok {:?}", application_start.elapsed());
+        }
+        Some(Commands::Profile { .. } | Commands::Config { .. }) => unreachable!(),
         None => {
-            run_tui(&mut app, application_start)?;
+            app.watch_config();
+            run_tui(&mut app, application_start, config_errors)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a profile command.
+fn profile_command(config: &Config, command: &ProfileCommands) -> Result<()> {
+    match command {
+        ProfileCommands::List => {
+            for profile in &config.profiles {
+                let db_size = ProfileFiles::of(profile)
+                    .db_file
+                    .and_then(|path| fs::metadata(path).ok())
+                    .map(|metadata| format!("{} KiB", metadata.len() / 1024))
+                    .unwrap_or(String::from("no database yet"));
+                println!(
+                    "{} {:<16} {:<20} {:<20} {}",
+                    if profile.name == config.default_profile {
+                        "*"
+                    } else {
+                        " "
+                    },
+                    profile.name,
+                    profile.config_file,
+                    profile.db_file,
+                    db_size
+                );
+            }
+            println!("The default profile is marked with *");
+        }
+        ProfileCommands::Create { name } => {
+            let profile = create_profile(config, name)?;
+            println!(
+                "Created the \"{}\" profile with {}. Open it with `pltx -p {}`",
+                profile.name, profile.config_file, profile.name
+            );
+        }
+        ProfileCommands::Delete { name, yes } => {
+            if *yes
+                || confirm(&format!(
+                    "Delete the \"{}\" profile and its database?",
+                    name
+                ))?
+            {
+                let profile = delete_profile(config, name)?;
+                println!("Deleted the \"{}\" profile", profile.name);
+            }
+        }
+        ProfileCommands::Rename { name, new_name } => {
+            let profile = rename_profile(config, name, new_name)?;
+            println!("Renamed the \"{}\" profile to \"{}\"", name, profile.name);
+        }
+        ProfileCommands::Copy { name, new_name } => {
+            let profile = copy_profile(config, name, new_name)?;
+            // The database may be in use by an instance of pltx, so it's
+            // copied with the backup API rather than as a file.
+            let source = config
+                .profiles
+                .iter()
+                .find(|p| p.name == *name)
+                .ok_or_else(|| eyre!("no profile \"{}\"", name))?;
+            if ProfileFiles::of(source).db_file.is_some() {
+                Database::init(source.db_file.clone())
+                    .backup_to(&dirs::data_dir().join(&profile.db_file))?;
+            }
+            println!("Copied the \"{}\" profile to \"{}\"", name, profile.name);
         }
     }
+    Ok(())
+}
 
+/// Run a config command.
+fn config_command(
+    config: &Config,
+    profile: &ProfileConfig,
+    command: &ConfigCommands,
+) -> Result<()> {
+    match command {
+        ConfigCommands::Show => print!("{}", config_to_toml(config)?),
+        ConfigCommands::Get { key } => {
+            let value = config_value(config, key).ok_or_else(|| eyre!("unknown key {}", key))?;
+            println!("{}", value.trim_end());
+        }
+        ConfigCommands::Set { key, value } => {
+            let file = config_key_file(profile, key);
+            set_config_values(&file, &[(key, value.as_deref())])?;
+            match value {
+                Some(value) => println!("Set {} to {} in {}", key, value, file),
+                None => println!("Removed {} from {}", key, file),
+            }
+        }
+        ConfigCommands::Defaults => print!("{}", default_config_text()),
+        ConfigCommands::Schema => println!("{}", config_schema()),
+    }
     Ok(())
 }
 
+/// Prompt for the passphrase if the database is locked.
+fn unlock_db(db: &mut Database) -> Result<()> {
+    if db.is_locked() {
+        let passphrase = rpassword::prompt_password("Passphrase: ")?;
+        db.unlock(&passphrase)?;
+    }
+    Ok(())
+}
+
+/// Ask a yes or no question, which defaults to no.
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Prompt for a new passphrase and confirm it.
+fn read_new_passphrase() -> Result<String> {
+    let passphrase = rpassword::prompt_password("New passphrase: ")?;
+    if passphrase.is_empty() {
+        return Err(eyre!("the passphrase cannot be empty"));
+    }
+    if passphrase != rpassword::prompt_password("Confirm passphrase: ")? {
+        return Err(eyre!("the passphrases do not match"));
+    }
+    Ok(passphrase)
+}
+
 /// Initialize the tracing crate.
 pub fn init_tracing(log_level: &str, profile: &ProfileConfig) -> Result<()> {
     std::env::set_var("RUST_LOG", log_level.to_uppercase());
@@ -1 +1,5 @@
-
+pub mod config_errors;
+pub mod notifications;
+pub mod passphrase;
+pub mod search;
+pub mod theme;
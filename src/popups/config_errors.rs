@@ -0,0 +1,104 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use pltx_app::{state::AppPopup, App, Popup};
+use pltx_config::ConfigError;
+use pltx_widgets::{PopupSize, PopupWidget};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+use tracing::warn;
+
+/// Lists the problems that were found in the config files. When pltx starts,
+/// every value with a problem falls back to its default. A reloaded config
+/// with problems isn't used at all.
+pub struct ConfigErrorsPopup {
+    errors: Vec<ConfigError>,
+    scroll: u16,
+    size: PopupSize,
+}
+
+impl Popup for ConfigErrorsPopup {
+    fn init() -> ConfigErrorsPopup {
+        ConfigErrorsPopup {
+            errors: vec![],
+            scroll: 0,
+            size: PopupSize::default().width(90).height(20),
+        }
+    }
+
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
+        let keys = &app.config.keys.navigation;
+
+        if keys.down.matches(&key_event) || key_event.code == KeyCode::Down {
+            self.scroll = self.scroll.saturating_add(1);
+        } else if keys.up.matches(&key_event) || key_event.code == KeyCode::Up {
+            self.scroll = self.scroll.saturating_sub(1);
+        } else if keys.close.matches(&key_event)
+            || keys.open.matches(&key_event)
+            || key_event.code == KeyCode::Esc
+        {
+            app.popup = AppPopup::None;
+            app.view.default();
+            app.mode.normal();
+        }
+    }
+
+    fn render(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let popup = PopupWidget::new(app, area)
+            .title_top("Config Errors")
+            .size(self.size)
+            .render(frame);
+
+        let [errors_layout, hint_layout] = Layout::default()
+            .vertical_margin(1)
+            .horizontal_margin(2)
+            .constraints([Constraint::Fill(1), Constraint::Length(2)])
+            .areas(popup.popup_area);
+
+        let mut lines = vec![];
+        for error in &self.errors {
+            let location = match error.line {
+                Some(line) => format!("{}:{}", error.file, line),
+                None => error.file.clone(),
+            };
+            let mut heading = vec![Span::from(location).fg(colors.secondary_fg)];
+            if !error.key.is_empty() {
+                heading.push(Span::from(format!(" {}", error.key)).fg(colors.highlight_fg));
+            }
+            lines.push(Line::from(heading));
+            lines.push(Line::from(error.message.as_str()).fg(colors.fg));
+            lines.push(Line::from(""));
+        }
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            errors_layout,
+        );
+
+        frame.render_widget(
+            Paragraph::new("Fix the problems and save the file to reload the config.")
+                .fg(colors.tertiary_fg),
+            hint_layout,
+        );
+    }
+}
+
+impl ConfigErrorsPopup {
+    /// Show the popup with the problems that were found. They are logged too.
+    pub fn open(&mut self, app: &mut App, errors: Vec<ConfigError>) {
+        for error in &errors {
+            warn!("config: {}", error);
+        }
+        self.errors = errors;
+        self.scroll = 0;
+        app.popup = AppPopup::ConfigErrors;
+        app.view.popup();
+        app.mode.normal();
+    }
+}
@@ -0,0 +1,106 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use pltx_app::{state::AppPopup, App, Popup};
+use pltx_widgets::{PopupSize, PopupWidget};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{Paragraph, Wrap},
+    Frame,
+};
+
+/// Lists the recent notifications, newest first, so the toasts that timed out
+/// can still be read.
+pub struct NotificationsPopup {
+    scroll: u16,
+    size: PopupSize,
+}
+
+impl Popup for NotificationsPopup {
+    fn init() -> NotificationsPopup {
+        NotificationsPopup {
+            scroll: 0,
+            size: PopupSize::default().width(90).height(20),
+        }
+    }
+
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
+        let keys = &app.config.keys.navigation;
+
+        if keys.down.matches(&key_event) || key_event.code == KeyCode::Down {
+            self.scroll = self.scroll.saturating_add(1);
+        } else if keys.up.matches(&key_event) || key_event.code == KeyCode::Up {
+            self.scroll = self.scroll.saturating_sub(1);
+        } else if app.config.keys.editing.delete.matches(&key_event) {
+            app.notifications.clear();
+            self.scroll = 0;
+        } else if keys.close.matches(&key_event)
+            || keys.open.matches(&key_event)
+            || key_event.code == KeyCode::Esc
+        {
+            app.popup = AppPopup::None;
+            app.view.default();
+            app.mode.normal();
+        }
+    }
+
+    fn render(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let popup = PopupWidget::new(app, area)
+            .title_top("Notifications")
+            .size(self.size)
+            .render(frame);
+
+        let [notifications_layout, hint_layout] = Layout::default()
+            .vertical_margin(1)
+            .horizontal_margin(2)
+            .constraints([Constraint::Fill(1), Constraint::Length(2)])
+            .areas(popup.popup_area);
+
+        let mut lines = vec![];
+        for notification in app.notifications.history() {
+            lines.push(Line::from(vec![
+                Span::from(notification.level.to_string())
+                    .fg(notification.level.color(colors))
+                    .bold(),
+                Span::from(format!(
+                    " {}",
+                    notification.created_at.display_with_seconds()
+                ))
+                .fg(colors.secondary_fg),
+            ]));
+            lines.push(Line::from(notification.text.as_str()).fg(colors.fg));
+            lines.push(Line::from(""));
+        }
+        if lines.is_empty() {
+            lines.push(Line::from("There are no notifications.").fg(colors.secondary_fg));
+        }
+        frame.render_widget(
+            Paragraph::new(lines)
+                .wrap(Wrap { trim: false })
+                .scroll((self.scroll, 0)),
+            notifications_layout,
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!(
+                "Press {} to clear the notifications.",
+                app.config.keys.editing.delete.first()
+            ))
+            .fg(colors.tertiary_fg),
+            hint_layout,
+        );
+    }
+}
+
+impl NotificationsPopup {
+    /// Show the notification history and mark it as read.
+    pub fn open(&mut self, app: &mut App) {
+        app.notifications.read();
+        self.scroll = 0;
+        app.popup = AppPopup::Notifications;
+        app.view.popup();
+        app.mode.normal();
+    }
+}
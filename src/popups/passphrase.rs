@@ -0,0 +1,176 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use pltx_app::{state::View, App, DefaultWidget, KeyEventHandler, Popup};
+use pltx_widgets::{PopupSize, PopupWidget, TextInput};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+
+/// Whether the passphrase is used to unlock an encrypted database, or to
+/// encrypt an unencrypted database.
+#[derive(PartialEq)]
+pub enum PassphraseMode {
+    Unlock,
+    Encrypt,
+}
+
+#[derive(PartialEq)]
+enum FocusedInput {
+    Passphrase,
+    Confirm,
+}
+
+/// Asks for the passphrase of the profile database before the session is
+/// started.
+pub struct PassphrasePrompt {
+    mode: PassphraseMode,
+    focused_input: FocusedInput,
+    passphrase_input: TextInput,
+    confirm_input: TextInput,
+    error: Option<String>,
+}
+
+impl Popup<Result<bool>> for PassphrasePrompt {
+    fn init() -> PassphrasePrompt {
+        PassphrasePrompt {
+            mode: PassphraseMode::Unlock,
+            focused_input: FocusedInput::Passphrase,
+            passphrase_input: TextInput::new("Passphrase")
+                .view(View::Popup)
+                .masked()
+                .prompt(),
+            confirm_input: TextInput::new("Confirm Passphrase")
+                .view(View::Popup)
+                .masked()
+                .prompt(),
+            error: None,
+        }
+    }
+
+    /// Returns whether the database has been unlocked.
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) -> Result<bool> {
+        if app.mode.is_normal() && app.config.keys.navigation.close.matches(&key_event) {
+            app.exit();
+            return Ok(false);
+        }
+
+        match key_event.code {
+            KeyCode::Enter => return self.submit(app),
+            KeyCode::Tab | KeyCode::BackTab if self.mode == PassphraseMode::Encrypt => {
+                self.focused_input = match self.focused_input {
+                    FocusedInput::Passphrase => FocusedInput::Confirm,
+                    FocusedInput::Confirm => FocusedInput::Passphrase,
+                };
+            }
+            _ => match self.focused_input {
+                FocusedInput::Passphrase => self.passphrase_input.key_event_handler(app, key_event),
+                FocusedInput::Confirm => self.confirm_input.key_event_handler(app, key_event),
+            },
+        }
+
+        Ok(false)
+    }
+
+    fn render(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let size = PopupSize::default()
+            .width(60)
+            .height(if self.mode == PassphraseMode::Encrypt {
+                11
+            } else {
+                8
+            });
+        let popup = PopupWidget::new(app, area)
+            .title_top(match self.mode {
+                PassphraseMode::Unlock => "Unlock Database",
+                PassphraseMode::Encrypt => "Encrypt Database",
+            })
+            .size(size)
+            .render(frame);
+
+        let [message_layout, passphrase_layout, confirm_layout] = Layout::default()
+            .margin(2)
+            .constraints([
+                Constraint::Length(2),
+                Constraint::Length(3),
+                Constraint::Length(if self.mode == PassphraseMode::Encrypt {
+                    3
+                } else {
+                    0
+                }),
+            ])
+            .areas(popup.popup_area);
+
+        let message = if let Some(error) = &self.error {
+            Line::from(error.as_str()).fg(colors.danger)
+        } else {
+            Line::from(match self.mode {
+                PassphraseMode::Unlock => "Enter the passphrase for this profile.",
+                PassphraseMode::Encrypt => "Choose a passphrase to encrypt this profile.",
+            })
+            .fg(colors.secondary_fg)
+        };
+        frame.render_widget(Paragraph::new(message), message_layout);
+
+        self.passphrase_input.render(
+            frame,
+            app,
+            passphrase_layout,
+            self.focused_input == FocusedInput::Passphrase,
+        );
+        if self.mode == PassphraseMode::Encrypt {
+            self.confirm_input.render(
+                frame,
+                app,
+                confirm_layout,
+                self.focused_input == FocusedInput::Confirm,
+            );
+        }
+    }
+}
+
+impl PassphrasePrompt {
+    pub fn mode(&mut self, mode: PassphraseMode) {
+        self.mode = mode;
+    }
+
+    fn submit(&mut self, app: &mut App) -> Result<bool> {
+        let passphrase = self.passphrase_input.input_string();
+
+        if passphrase.is_empty() {
+            self.error = Some(String::from("The passphrase cannot be empty."));
+            return Ok(false);
+        }
+
+        match self.mode {
+            PassphraseMode::Unlock => {
+                if app.db.unlock(&passphrase).is_err() {
+                    self.error = Some(String::from("Incorrect passphrase."));
+                    self.passphrase_input.reset();
+                    return Ok(false);
+                }
+            }
+            PassphraseMode::Encrypt => {
+                if self.focused_input == FocusedInput::Passphrase {
+                    self.focused_input = FocusedInput::Confirm;
+                    return Ok(false);
+                }
+                if passphrase != self.confirm_input.input_string() {
+                    self.error = Some(String::from("The passphrases do not match."));
+                    self.confirm_input.reset();
+                    return Ok(false);
+                }
+                app.db.encrypt(&passphrase)?;
+            }
+        }
+
+        self.passphrase_input.reset();
+        self.confirm_input.reset();
+        Ok(true)
+    }
+}
@@ -0,0 +1,187 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use nucleo::{
+    pattern::{Atom, AtomKind, CaseMatching, Normalization},
+    Matcher, Utf32Str,
+};
+use pltx_app::{
+    state::{AppPopup, View},
+    App, DefaultWidget, KeyEventHandler, Popup,
+};
+use pltx_project_management::{db_search_cards, CardSearchResult};
+use pltx_widgets::{PopupSize, PopupWidget, Scrollable, TextInput};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// Searches the cards of all projects. The results of the full-text search
+/// are ranked again by how well the input fuzzy matches the title of the card,
+/// like the commands in the command handler.
+pub struct SearchPopup {
+    input: TextInput,
+    results: Vec<CardSearchResult>,
+    selection: Scrollable,
+    matcher: Matcher,
+    size: PopupSize,
+}
+
+impl Popup<Result<Option<CardSearchResult>>> for SearchPopup {
+    fn init() -> SearchPopup {
+        let size = PopupSize::default().width(80).height(24);
+        SearchPopup {
+            input: TextInput::new("Search")
+                .view(View::Popup)
+                .size((size.width - 4, 3))
+                .placeholder("Search projects, cards and subtasks...")
+                .max(100),
+            results: vec![],
+            selection: Scrollable::default().row_height(2),
+            matcher: Matcher::default(),
+            size,
+        }
+    }
+
+    /// Returns the result to go to when one has been chosen.
+    fn key_event_handler(
+        &mut self,
+        app: &mut App,
+        key_event: KeyEvent,
+    ) -> Result<Option<CardSearchResult>> {
+        if key_event.code == KeyCode::Enter {
+            let result = self.results.get(self.selection.focused).cloned();
+            if result.is_some() {
+                self.close(app);
+            }
+            return Ok(result);
+        }
+
+        if app.mode.is_normal() {
+            let keys = &app.config.keys.navigation;
+            if keys.close.matches(&key_event) {
+                self.close(app);
+                return Ok(None);
+            }
+            if [&keys.up, &keys.down, &keys.top, &keys.bottom]
+                .iter()
+                .any(|k| k.matches(&key_event))
+            {
+                self.selection.key_event_handler(app, key_event);
+                return Ok(None);
+            }
+        }
+
+        let previous_input = self.input.input_string();
+        self.input.key_event_handler(app, key_event);
+        if self.input.input_string() != previous_input {
+            self.search(app)?;
+        }
+
+        Ok(None)
+    }
+
+    fn render(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let popup = PopupWidget::new(app, area)
+            .title_top("Search")
+            .size(self.size)
+            .render(frame);
+
+        let [input_layout, results_layout] = Layout::default()
+            .vertical_margin(1)
+            .horizontal_margin(2)
+            .constraints([Constraint::Length(4), Constraint::Fill(1)])
+            .areas(popup.popup_area);
+
+        self.input.render(frame, app, input_layout, true);
+
+        if self.results.is_empty() {
+            let message = if self.input.is_empty() {
+                "Type to search the cards of all projects."
+            } else {
+                "No cards found."
+            };
+            frame.render_widget(
+                Paragraph::new(message).fg(colors.secondary_fg),
+                results_layout,
+            );
+            return;
+        }
+
+        let rows = self
+            .results
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let focused = i == self.selection.focused;
+                let mut location =
+                    vec![
+                        Span::from(format!(" {} / {}", r.project_title, r.list_title))
+                            .fg(colors.secondary_fg),
+                    ];
+                if !r.snippet.is_empty() && r.snippet != r.card_title {
+                    location.push(Span::from(format!("  {}", r.snippet)).fg(colors.tertiary_fg));
+                }
+                Paragraph::new(vec![
+                    Line::from(format!(" {}", r.card_title))
+                        .bold()
+                        .fg(if focused { colors.active_fg } else { colors.fg }),
+                    Line::from(location),
+                ])
+                .bg(if focused {
+                    colors.input_focus_bg
+                } else {
+                    colors.popup_bg
+                })
+            })
+            .collect::<Vec<Paragraph>>();
+
+        self.selection.render(frame, results_layout, rows);
+    }
+}
+
+impl SearchPopup {
+    /// Show the popup with an empty search.
+    pub fn open(&mut self, app: &mut App) {
+        self.input.reset();
+        self.results = vec![];
+        self.selection.reset();
+        app.popup = AppPopup::Search;
+        app.view.popup();
+        app.mode.insert();
+    }
+
+    fn close(&mut self, app: &mut App) {
+        app.popup = AppPopup::None;
+        app.view.default();
+        app.mode.normal();
+    }
+
+    fn search(&mut self, app: &App) -> Result<()> {
+        let input = self.input.input_string();
+        let mut results = db_search_cards(&app.db, &input)?;
+
+        let pattern = Atom::new(
+            &input,
+            CaseMatching::Smart,
+            Normalization::Smart,
+            AtomKind::Fuzzy,
+            false,
+        );
+        let mut buf = vec![];
+        // The sort is stable, so cards with the same score, or that only match
+        // in other fields, keep the order of the full-text search.
+        results.sort_by_cached_key(|r| {
+            let score = pattern.score(Utf32Str::new(&r.card_title, &mut buf), &mut self.matcher);
+            std::cmp::Reverse(score.unwrap_or(0))
+        });
+
+        self.results = results;
+        self.selection.reset();
+        Ok(())
+    }
+}
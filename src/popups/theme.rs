@@ -0,0 +1,141 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use pltx_app::{state::AppPopup, App, Popup};
+use pltx_config::{theme_colors, theme_names, ColorsConfig, UserColors};
+use pltx_widgets::{PopupSize, PopupWidget};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Text},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// Switches the color preset. The focused theme is previewed, and the colors
+/// from before the popup was opened are restored if it's closed without
+/// choosing one.
+pub struct ThemePopup {
+    themes: Vec<String>,
+    focused: usize,
+    previous_colors: Option<ColorsConfig>,
+    /// The colors of the config files that override the previewed theme.
+    user_colors: UserColors,
+    size: PopupSize,
+}
+
+impl Popup for ThemePopup {
+    fn init() -> ThemePopup {
+        ThemePopup {
+            themes: vec![],
+            focused: 0,
+            previous_colors: None,
+            user_colors: UserColors::default(),
+            size: PopupSize::default().width(50).height(16),
+        }
+    }
+
+    fn key_event_handler(&mut self, app: &mut App, key_event: KeyEvent) {
+        let keys = app.config.keys.navigation.clone();
+
+        if keys.down.matches(&key_event) || key_event.code == KeyCode::Down {
+            if self.focused + 1 < self.themes.len() {
+                self.focused += 1;
+                self.preview(app);
+            }
+        } else if keys.up.matches(&key_event) || key_event.code == KeyCode::Up {
+            if self.focused > 0 {
+                self.focused -= 1;
+                self.preview(app);
+            }
+        } else if keys.open.matches(&key_event) {
+            self.previous_colors = None;
+            app.status_message(format!(
+                "Switched to the {} theme",
+                app.config.colors.preset
+            ));
+            self.close(app);
+        } else if keys.close.matches(&key_event) || key_event.code == KeyCode::Esc {
+            if let Some(colors) = self.previous_colors.take() {
+                app.config.colors = colors;
+            }
+            self.close(app);
+        }
+    }
+
+    fn render(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+
+        let popup = PopupWidget::new(app, area)
+            .title_top("Theme")
+            .size(self.size)
+            .render(frame);
+
+        let [themes_layout, hint_layout] = Layout::default()
+            .vertical_margin(1)
+            .horizontal_margin(2)
+            .constraints([Constraint::Fill(1), Constraint::Length(2)])
+            .areas(popup.popup_area);
+
+        let lines = self
+            .themes
+            .iter()
+            .enumerate()
+            .map(|(i, theme)| {
+                Line::from(format!(" {} ", theme)).style(if i == self.focused {
+                    Style::new()
+                        .bold()
+                        .fg(colors.active_fg)
+                        .bg(colors.active_bg)
+                } else {
+                    Style::new().fg(colors.secondary_fg)
+                })
+            })
+            .collect::<Vec<Line>>();
+        // Keep the focused theme in view when there are more themes than rows.
+        let scroll = (self.focused as u16).saturating_sub(themes_layout.height.saturating_sub(1));
+        frame.render_widget(
+            Paragraph::new(Text::from(lines)).scroll((scroll, 0)),
+            themes_layout,
+        );
+
+        frame.render_widget(
+            Paragraph::new("Set colors.preset in the config file to keep the theme.")
+                .fg(colors.tertiary_fg),
+            hint_layout,
+        );
+    }
+}
+
+impl ThemePopup {
+    /// Show the popup with the current theme focused.
+    pub fn open(&mut self, app: &mut App) {
+        self.themes = theme_names();
+        self.focused = self
+            .themes
+            .iter()
+            .position(|t| *t == app.config.colors.preset)
+            .unwrap_or(0);
+        self.previous_colors = Some(app.config.colors.clone());
+        self.user_colors = UserColors::read(&app.profile);
+        app.popup = AppPopup::Theme;
+        app.view.popup();
+        app.mode.normal();
+    }
+
+    fn close(&mut self, app: &mut App) {
+        app.popup = AppPopup::None;
+        app.view.default();
+        app.mode.normal();
+    }
+
+    /// Apply the focused theme. A theme that fails to load is reported in a
+    /// notification and the colors are left as they are.
+    fn preview(&self, app: &mut App) {
+        let Some(theme) = self.themes.get(self.focused) else {
+            return;
+        };
+        match theme_colors(&self.user_colors, theme) {
+            Ok(colors) => app.config.colors = colors,
+            Err(err) => app.error(format!("Failed to load the {} theme: {}", theme, err)),
+        }
+    }
+}
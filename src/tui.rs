@@ -3,7 +3,10 @@ use std::{
     time::Instant,
 };
 
-use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{
+    event::{DisableFocusChange, EnableFocusChange},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use tracing::info;
 
@@ -23,7 +26,8 @@ impl Tui {
         let mut terminal = Terminal::new(backend)?;
 
         terminal::enable_raw_mode()?;
-        crossterm::execute!(stdout(), EnterAlternateScreen)?;
+        // Focus changes pause and resume the session.
+        crossterm::execute!(stdout(), EnterAlternateScreen, EnableFocusChange)?;
         terminal.clear()?;
 
         let tui = Self {
@@ -38,7 +42,7 @@ impl Tui {
     pub fn restore() -> io::Result<()> {
         let start = Instant::now();
         terminal::disable_raw_mode()?;
-        crossterm::execute!(stdout(), LeaveAlternateScreen)?;
+        crossterm::execute!(stdout(), LeaveAlternateScreen, DisableFocusChange)?;
         info!("restored the terminal in {:?}", start.elapsed());
         Ok(())
     }
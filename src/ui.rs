@@ -3,22 +3,42 @@ use std::{str::FromStr, time::Instant};
 use color_eyre::Result;
 use pltx_app::{
     state::{AppModule, AppPopup},
-    App, DebugPosition, Module,
+    App, DebugPosition, Module, Notification, Popup,
 };
 use pltx_config::ColorsConfig;
 use pltx_home::Home;
 use pltx_project_management::ProjectManagement;
-use pltx_utils::DateTime;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph},
+    widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
     Frame,
 };
 use tracing::info;
 
-use crate::command_handler::CommandHandler;
+use crate::{
+    command_handler::CommandHandler,
+    popups::{
+        config_errors::ConfigErrorsPopup, notifications::NotificationsPopup, search::SearchPopup,
+        theme::ThemePopup,
+    },
+};
+
+/// The width of the toasts of the notifications.
+const TOAST_WIDTH: u16 = 48;
+
+/// The height of the toast of a notification, which is the wrapped text with
+/// the border and padding.
+fn toast_height(notification: &Notification, width: u16) -> u16 {
+    let text_width = width.saturating_sub(4).max(1) as usize;
+    let lines: usize = notification
+        .text
+        .lines()
+        .map(|line| Line::from(line).width().div_ceil(text_width).max(1))
+        .sum();
+    lines.max(1) as u16 + 2
+}
 
 /// States for each module.
 pub struct InterfaceModule {
@@ -27,14 +47,18 @@ pub struct InterfaceModule {
 }
 
 /// States for each popup.
-pub struct PopupState {}
+pub struct PopupState {
+    pub search: SearchPopup,
+    pub theme: ThemePopup,
+    pub config_errors: ConfigErrorsPopup,
+    pub notifications: NotificationsPopup,
+}
 
 pub struct Interface {
     pub modules: InterfaceModule,
     /// Global popups. Module popups are located within the modules own
     /// directories.
-    // TODO: Remove underscore when popups are added.
-    pub _popups: PopupState,
+    pub popups: PopupState,
 }
 
 impl Interface {
@@ -45,7 +69,12 @@ impl Interface {
                 home: Home::init(app)?,
                 project_management: ProjectManagement::init(app)?,
             },
-            _popups: PopupState {},
+            popups: PopupState {
+                search: SearchPopup::init(),
+                theme: ThemePopup::init(),
+                config_errors: ConfigErrorsPopup::init(),
+                notifications: NotificationsPopup::init(),
+            },
         };
         info!("initialized interface in {:?}", start.elapsed());
         Ok(interface)
@@ -98,9 +127,19 @@ impl Interface {
         if app.view.is_popup() {
             match app.popup {
                 AppPopup::None => {}
+                AppPopup::Search => self.popups.search.render(app, frame, module_layout),
+                AppPopup::Theme => self.popups.theme.render(app, frame, module_layout),
+                AppPopup::ConfigErrors => {
+                    self.popups.config_errors.render(app, frame, module_layout)
+                }
+                AppPopup::Notifications => {
+                    self.popups.notifications.render(app, frame, module_layout)
+                }
             }
         }
 
+        self.toasts(app, frame, module_layout);
+
         if app.view.is_command() {
             command_handler.render(app, frame, area);
         }
@@ -153,6 +192,45 @@ impl Interface {
         }
     }
 
+    /// Stack the toasts of the notifications in the top right corner of the
+    /// module, the newest first.
+    fn toasts(&self, app: &App, frame: &mut Frame, area: Rect) {
+        let colors = &app.config.colors;
+        let width = TOAST_WIDTH.min(area.width.saturating_sub(2));
+        let x = area.right().saturating_sub(width + 1);
+        let mut y = area.y + 1;
+
+        for notification in app.notifications.toasts().iter().rev() {
+            let height = toast_height(notification, width);
+            if y + height > area.bottom() {
+                break;
+            }
+            let toast_area = Rect::new(x, y, width, height);
+            let color = notification.level.color(colors);
+            frame.render_widget(Clear, toast_area);
+            frame.render_widget(
+                Paragraph::new(notification.text.as_str())
+                    .wrap(Wrap { trim: true })
+                    .fg(colors.fg)
+                    .block(
+                        Block::new()
+                            .title(
+                                Span::from(format!(" {} ", notification.level))
+                                    .fg(color)
+                                    .bold(),
+                            )
+                            .borders(Borders::ALL)
+                            .border_type(BorderType::Rounded)
+                            .border_style(Style::new().fg(color))
+                            .padding(Padding::horizontal(1))
+                            .bg(colors.popup_bg),
+                    ),
+                toast_area,
+            );
+            y += height;
+        }
+    }
+
     fn title_bar(&self, colors: &ColorsConfig) -> Paragraph {
         let title_bar_content = vec![Line::from(
             vec![Span::from(" Privacy Life Tracker ").bold()],
@@ -202,20 +280,33 @@ impl Interface {
             .style(Style::new().fg(status_bar_fg).bg(status_bar_bg));
         frame.render_widget(left_content, left_layout);
 
-        let center_text = vec![Line::from(vec![Span::from(format!(
-            "Session duration: {}",
-            if let Some(started) = &app.db.started {
-                DateTime::new().duration_since(started).to_string()
-            } else {
-                "<pending>".to_string()
-            }
-        ))])];
+        let center_text = if let Some(message) = &app.status_message {
+            vec![Line::from(vec![Span::from(message.text.as_str()).bold()])]
+        } else {
+            vec![Line::from(vec![Span::from(format!(
+                "Session duration: {}{}",
+                if let Some(duration) = app.db.session_duration() {
+                    duration.to_string()
+                } else {
+                    "<pending>".to_string()
+                },
+                if app.db.is_idle() { " (idle)" } else { "" }
+            ))])]
+        };
         let center_content = Paragraph::new(center_text)
             .alignment(Alignment::Center)
             .style(Style::new().fg(status_bar_fg).bg(status_bar_bg));
         frame.render_widget(center_content, center_layout);
 
-        let right_text = vec![Line::from(vec![Span::from("Press ? for help ")])];
+        let unread = app.notifications.unread();
+        let right_text = vec![Line::from(vec![
+            Span::from(match unread {
+                0 => String::new(),
+                1 => String::from("1 notification | "),
+                _ => format!("{} notifications | ", unread),
+            }),
+            Span::from("Press ? for help "),
+        ])];
         let right_content = Paragraph::new(if app.mode.is_delete() {
             vec![Line::from("")]
         } else {